@@ -0,0 +1,612 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::biarc::*;
+use crate::point::Vec2;
+use crate::predicates::*;
+use crate::scalar::Scalar;
+use crate::tree::*;
+use crate::util::*;
+
+/// Where each biarc joins its two arcs, on the joint circle
+/// (the locus of all joints that keep the biarc G1 continuous).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointStrategy {
+    /// Where the perpendicular bisector of the chord meets the joint circle
+    ChordBisector,
+    /// Nearest to the curve point at the middle of the interval
+    CurveMidpoint,
+}
+
+// `#[default]` on enum variants needs a newer compiler than the GUI crates use
+#[allow(clippy::derivable_impls)]
+impl Default for JointStrategy {
+    fn default() -> Self {
+        JointStrategy::ChordBisector
+    }
+}
+
+/// How `build_biarc` fits the biarc of one parameter interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiarcConstruction<S> {
+    pub start: Vec2<S>,
+    pub end: Vec2<S>,
+    /// Unit tangents of the curve at `start` and `end`
+    pub start_tangent: Vec2<S>,
+    pub end_tangent: Vec2<S>,
+    /// Center and radius of the joint circle, `None` where it degenerates to the chord
+    pub joint_circle: Option<(Vec2<S>, S)>,
+    /// Where the two arcs meet
+    pub joint: Vec2<S>,
+    pub left: ArcData<S>,
+    pub right: ArcData<S>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BezierCurve<S> {
+    pub control_pts: [Vec2<S>; 4],
+}
+
+impl<S: Scalar> BezierCurve<S> {
+    /// Straight segment as a cubic
+    pub fn line(p0: Vec2<S>, p1: Vec2<S>) -> Self {
+        let third = S::ONE / S::from_f64(3.0);
+        BezierCurve {
+            control_pts: [p0, p0.lerp(&p1, third), p1.lerp(&p0, third), p1],
+        }
+    }
+
+    /// Degree-elevated quadratic with control point `q`
+    pub fn quadratic(p0: Vec2<S>, q: Vec2<S>, p1: Vec2<S>) -> Self {
+        let two_thirds = S::TWO / S::from_f64(3.0);
+        BezierCurve {
+            control_pts: [p0, p0.lerp(&q, two_thirds), p1.lerp(&q, two_thirds), p1],
+        }
+    }
+}
+
+impl<S: Scalar + 'static> BezierCurve<S> {
+    pub fn cubic_curve_to(&self, point: &mut Vec2<S>, t: S) {
+        let three = S::from_f64(3.0);
+        let t_inv = S::ONE - t;
+        let t_inv_sq = t_inv * t_inv;
+        let t_sq = t * t;
+        let b0 = t_inv_sq * t_inv;
+        let b1 = three * t_inv_sq * t;
+        let b2 = three * t_inv * t_sq;
+        let b3 = t_sq * t;
+        point_clear(point);
+        point_add_weight_vec(point, b0, &self.control_pts[0]);
+        point_add_weight_vec(point, b1, &self.control_pts[1]);
+        point_add_weight_vec(point, b2, &self.control_pts[2]);
+        point_add_weight_vec(point, b3, &self.control_pts[3]);
+    }
+
+    pub fn cubic_deriv_to(&self, point: &mut Vec2<S>, t: S) {
+        let three = S::from_f64(3.0);
+        let t_inv = S::ONE - t;
+
+        let b0 = three * t_inv * t_inv;
+        let b1 = S::from_f64(6.0) * t * t_inv;
+        let b2 = three * t * t;
+        let p0 = &self.control_pts[0];
+        let p1 = &self.control_pts[1];
+        let p2 = &self.control_pts[2];
+        let p3 = &self.control_pts[3];
+        point_clear(point);
+        point.x = b0 * (p1.x - p0.x) + b1 * (p2.x - p1.x) + b2 * (p3.x - p2.x);
+        point.y = b0 * (p1.y - p0.y) + b1 * (p2.y - p1.y) + b2 * (p3.y - p2.y);
+    }
+
+    pub fn cubic_deriv2_to(&self, point: &mut Vec2<S>, t: S) {
+        let six = S::from_f64(6.0);
+        let p = &self.control_pts;
+        let b0 = six * (S::ONE - t);
+        let b1 = six * t;
+        point_clear(point);
+        point.x =
+            b0 * (p[2].x - p[1].x - p[1].x + p[0].x) + b1 * (p[3].x - p[2].x - p[2].x + p[1].x);
+        point.y =
+            b0 * (p[2].y - p[1].y - p[1].y + p[0].y) + b1 * (p[3].y - p[2].y - p[2].y + p[1].y);
+    }
+
+    /// Signed curvature at `t`, positive when turning from +x-axis towards +y-axis
+    pub fn curvature(&self, t: S) -> S {
+        let mut d1 = Vec2::zero();
+        let mut d2 = Vec2::zero();
+        self.cubic_deriv_to(&mut d1, t);
+        self.cubic_deriv2_to(&mut d2, t);
+        let speed = d1.dot(&d1).sqrt();
+        d1.cross(&d2) / (speed * speed * speed)
+    }
+
+    /// Parameter of the point nearest to `p`
+    pub fn nearest_t(&self, p: &Vec2<S>) -> S {
+        // coarse samples, then Newton steps on (B(t) - p) . B'(t) = 0
+        let mut point = Vec2::zero();
+        let mut best = (S::ZERO, S::ZERO);
+        for i in 0..=RESOLUTION {
+            let t = S::from_usize(i) / S::from_usize(RESOLUTION);
+            self.cubic_curve_to(&mut point, t);
+            let d = distance(&point, p);
+            if i == 0 || d < best.1 {
+                best = (t, d);
+            }
+        }
+        let mut t = best.0;
+        let mut d1 = Vec2::zero();
+        let mut d2 = Vec2::zero();
+        for _ in 0..4 {
+            self.cubic_curve_to(&mut point, t);
+            self.cubic_deriv_to(&mut d1, t);
+            self.cubic_deriv2_to(&mut d2, t);
+            let offset = point - *p;
+            let slope = d1.dot(&d1) + offset.dot(&d2);
+            if slope <= S::ZERO {
+                break;
+            }
+            t = (t - offset.dot(&d1) / slope).max(S::ZERO).min(S::ONE);
+        }
+        self.cubic_curve_to(&mut point, t);
+        if distance(&point, p) <= best.1 {
+            t
+        } else {
+            best.0
+        }
+    }
+
+    /// Points of de Casteljau's algorithm at `t`: the control polygon, then each
+    /// polygon of interpolated points, down to the curve point itself.
+    /// The edge of the two-point polygon is the tangent direction.
+    pub fn de_casteljau(&self, t: S) -> Vec<Vec<Vec2<S>>> {
+        let mut levels = vec![self.control_pts.to_vec()];
+        while levels.last().unwrap().len() > 1 {
+            let points = levels.last().unwrap();
+            let next = points
+                .windows(2)
+                .map(|pair| pair[0].lerp(&pair[1], t))
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+
+    /// Biarc of the interval `t0`-`t1`: end tangents, joint circle, joint and both arcs
+    pub fn biarc_construction(
+        &self,
+        t0: S,
+        t1: S,
+        joint: JointStrategy,
+        tol: &Tolerance<S>,
+    ) -> BiarcConstruction<S> {
+        let mut start = Vec2::zero();
+        let mut mid = Vec2::zero();
+        let mut end = Vec2::zero();
+        let mut u0 = Vec2::zero();
+        let mut u1 = Vec2::zero();
+        let mut mid0 = Vec2::zero();
+        let mut mid1 = Vec2::zero();
+        let mut v0 = Vec2::zero();
+        let mut v1 = Vec2::zero();
+
+        self.cubic_curve_to(&mut start, t0);
+        self.cubic_curve_to(&mut mid, (t0 + t1) * S::HALF);
+        self.cubic_curve_to(&mut end, t1);
+
+        self.cubic_deriv_to(&mut u0, t0);
+        self.cubic_deriv_to(&mut u1, t1);
+        // the derivative vanishes where control points coincide;
+        // fall back to the chord direction there.
+        if norm(&u0) <= tol.distance {
+            u0 = end - start;
+        }
+        if norm(&u1) <= tol.distance {
+            u1 = end - start;
+        }
+        normalize(&mut u0);
+        normalize(&mut u1);
+
+        // calculate the center of joint circle
+        mid0.x = (start.x + end.x) * S::HALF;
+        mid0.y = (start.y + end.y) * S::HALF;
+        mid1.x = (start.x + u0.x + end.x + u1.x) * S::HALF;
+        mid1.y = (start.y + u0.y + end.y + u1.y) * S::HALF;
+        v0.x = end.y - start.y;
+        v0.y = -(end.x - start.x);
+        v1.x = (end.y + u1.y) - (start.y + u0.y);
+        v1.y = (start.x + u0.x) - (end.x + u1.x);
+        let (joint_circle, control) = match line_intersection(&mid0, &v0, &mid1, &v1, tol) {
+            LineIntersection::Point(center, _, _) => {
+                // calculate radius and control point
+                let radius = distance(&center, &start);
+                let toward = match joint {
+                    JointStrategy::ChordBisector => mid0,
+                    JointStrategy::CurveMidpoint => mid,
+                };
+                let theta = point_angle(&center, &toward);
+                let control = Vec2::new(
+                    center.x + radius * theta.cos(),
+                    center.y + radius * theta.sin(),
+                );
+                (Some((center, radius)), control)
+            }
+            // joint circle degenerates to the chord (e.g. parallel end tangents)
+            LineIntersection::Parallel | LineIntersection::Coincident => (None, mid0),
+        };
+
+        // calculate the centers and sweeps of both arcs
+        let center = arc_center(&start, &u0, &control, tol);
+        let left = ArcData::from_endpoints(center, &start, &control, (start - center).cross(&u0));
+        let center = arc_center(&end, &u1, &control, tol);
+        let right = ArcData::from_endpoints(center, &control, &end, (end - center).cross(&u1));
+
+        BiarcConstruction {
+            start,
+            end,
+            start_tangent: u0,
+            end_tangent: u1,
+            joint_circle,
+            joint: control,
+            left,
+            right,
+        }
+    }
+
+    pub fn build_biarc(
+        &self,
+        arc_cell: Rc<RefCell<Tree<ArcBox<S>>>>,
+        split_num: usize,
+        use_bezier_aabb: bool,
+        joint: JointStrategy,
+        tol: &Tolerance<S>,
+    ) {
+        let depth = split_num + 1;
+        let node_n = 2usize.pow((depth + 1) as u32) - 1;
+        let biarc_n = 2usize.pow(split_num as u32);
+
+        if node_n != arc_cell.borrow().len() {
+            let mut arc_mut = arc_cell.borrow_mut();
+            arc_mut.set_new_complete(depth, ArcBox::arc_builder(depth));
+        }
+
+        let delta = S::ONE / S::from_usize(biarc_n);
+        let three = S::from_f64(3.0);
+        let eighth = S::from_f64(0.125);
+        let three_eighths = S::from_f64(0.375);
+        let mut start = Vec2::zero();
+        let mut mid = Vec2::zero();
+        let mut end = Vec2::zero();
+        let mut control = Vec2::zero();
+        let mut u0 = Vec2::zero();
+        let mut u1 = Vec2::zero();
+        let mut right_arc = ArcData::default();
+
+        let mut arc_mid = Vec2::zero();
+        let mut tangent_left = Vec2::zero();
+        let mut tangent_right = Vec2::zero();
+        let mut tangent_mid = Vec2::zero();
+
+        let mut i: usize = 0;
+        let mut is_left: bool = true;
+
+        Tree::post_trav(arc_cell.clone(), |node_id| {
+            // TODO: merge radius
+            let mut left_aabb: Option<AABB<S>> = None;
+            let mut right_aabb: Option<AABB<S>> = None;
+            let mut aabb_radius = S::ZERO;
+
+            {
+                let tree = arc_cell.borrow();
+                let node = tree.get(node_id).unwrap();
+                if let Some(left_node) = tree.left(node) {
+                    left_aabb = Some(left_node.aabb.clone());
+                    aabb_radius = left_node.radius;
+                }
+                if let Some(right_node) = tree.right(node) {
+                    right_aabb = Some(right_node.aabb.clone());
+                    if aabb_radius < right_node.radius {
+                        aabb_radius = right_node.radius;
+                    }
+                }
+
+                if let Some(ref left_value) = left_aabb {
+                    if let Some(ref right_value) = right_aabb {
+                        left_aabb = Some(AABB::merge_two(left_value, right_value));
+                    }
+                } else if right_aabb.is_some() {
+                    left_aabb = right_aabb
+                }
+            }
+
+            let mut tree = arc_cell.borrow_mut();
+            let arc_node = &mut tree.get_mut(node_id).unwrap().value;
+
+            // leaf node
+            if let Some(ref mut arc) = arc_node.arc {
+                // cache joint circle
+                if is_left {
+                    let t = delta * S::from_usize(i);
+                    i += 1;
+                    let construction = self.biarc_construction(t, t + delta, joint, tol);
+                    self.cubic_curve_to(&mut mid, t + delta * S::HALF);
+                    start = construction.start;
+                    end = construction.end;
+                    u0 = construction.start_tangent;
+                    u1 = construction.end_tangent;
+                    control = construction.joint;
+                    *arc = construction.left;
+                    right_arc = construction.right;
+                    arc_mid = arc.mid_point();
+
+                    // calculate aabb radius
+                    tangent_right.x = control.y - arc.center.y;
+                    tangent_right.y = arc.center.x - control.x;
+                    match line_intersection(&start, &u0, &control, &tangent_right, tol) {
+                        LineIntersection::Point(corner, _, _) => {
+                            tangent_left.x = (start.x + corner.x * S::TWO) / three;
+                            tangent_left.y = (start.y + corner.y * S::TWO) / three;
+                            tangent_right.x = (control.x + corner.x * S::TWO) / three;
+                            tangent_right.y = (control.y + corner.y * S::TWO) / three;
+                        }
+                        // half-circle or straight segment: end tangents never meet,
+                        // use the cubic arc handles (4/3 * tan(sweep / 4) * radius)
+                        LineIntersection::Parallel | LineIntersection::Coincident => {
+                            let quarter = arc.sweep.abs() / S::from_f64(4.0);
+                            let handle = u0
+                                * (arc.radius * S::from_f64(4.0) / three * quarter.sin()
+                                    / quarter.cos());
+                            tangent_left = start + handle;
+                            tangent_right = control + handle;
+                        }
+                    }
+
+                    // inlined cubic curve calculation
+                    point_clear(&mut tangent_mid);
+                    point_add_weight_vec(&mut tangent_mid, eighth, &start);
+                    point_add_weight_vec(&mut tangent_mid, three_eighths, &tangent_left);
+                    point_add_weight_vec(&mut tangent_mid, three_eighths, &tangent_right);
+                    point_add_weight_vec(&mut tangent_mid, eighth, &control);
+
+                    arc_node.radius = distance(&arc_mid, &tangent_mid);
+
+                    let mut dist_max = S::ZERO;
+                    let bezier_aabb = {
+                        let tn = t + delta * S::HALF;
+                        let tn_inv = S::ONE - tn;
+                        let t_sq = t * t;
+                        let tn_sq = tn * tn;
+                        let t_inv = S::ONE - t;
+                        let t_inv_sq = t_inv * t_inv;
+                        let tn_inv_sq = tn_inv * tn_inv;
+                        let t_t_inv = t * t_inv;
+                        let tn_tn_inv = tn * tn_inv;
+
+                        let mid_control_left = Vec2 {
+                            x: tn_inv
+                                * (t_inv_sq * self.control_pts[0].x
+                                    + S::TWO * t_t_inv * self.control_pts[1].x
+                                    + t_sq * self.control_pts[2].x)
+                                + tn * (t_inv_sq * self.control_pts[1].x
+                                    + S::TWO * t_t_inv * self.control_pts[2].x
+                                    + t_sq * self.control_pts[3].x),
+                            y: tn_inv
+                                * (t_inv_sq * self.control_pts[0].y
+                                    + S::TWO * t_t_inv * self.control_pts[1].y
+                                    + t_sq * self.control_pts[2].y)
+                                + tn * (t_inv_sq * self.control_pts[1].y
+                                    + S::TWO * t_t_inv * self.control_pts[2].y
+                                    + t_sq * self.control_pts[3].y),
+                        };
+                        let mid_control_right = Vec2 {
+                            x: t_inv
+                                * (tn_inv_sq * self.control_pts[0].x
+                                    + S::TWO * tn_tn_inv * self.control_pts[1].x
+                                    + tn_sq * self.control_pts[2].x)
+                                + t * (tn_inv_sq * self.control_pts[1].x
+                                    + S::TWO * tn_tn_inv * self.control_pts[2].x
+                                    + tn_sq * self.control_pts[3].x),
+                            y: t_inv
+                                * (tn_inv_sq * self.control_pts[0].y
+                                    + S::TWO * tn_tn_inv * self.control_pts[1].y
+                                    + tn_sq * self.control_pts[2].y)
+                                + t * (tn_inv_sq * self.control_pts[1].y
+                                    + S::TWO * tn_tn_inv * self.control_pts[2].y
+                                    + tn_sq * self.control_pts[3].y),
+                        };
+                        let mut dist_left = distance(&mid_control_left, &tangent_left);
+                        if dist_max < dist_left {
+                            dist_max = dist_left;
+                        }
+
+                        dist_left = distance(&mid_control_right, &tangent_right);
+                        if dist_max < dist_left {
+                            dist_max = dist_left;
+                        }
+
+                        if use_bezier_aabb {
+                            let mut aabb = AABB::new_point(start, mid_control_left);
+                            let aabb2 = AABB::new_point(mid_control_right, mid);
+                            aabb.merge(&aabb2);
+                            Some(aabb)
+                        } else {
+                            None
+                        }
+                    };
+
+                    arc_node.radius += dist_max;
+                    arc_node.aabb = arc.aabb();
+
+                    if use_bezier_aabb {
+                        let mut aabb2 = bezier_aabb.unwrap();
+                        aabb2.merge(&arc_node.aabb);
+                        if aabb2.h < arc_node.aabb.h + S::TWO * arc_node.radius
+                            && aabb2.w < arc_node.aabb.w + S::TWO * arc_node.radius
+                        {
+                            arc_node.radius = S::ZERO;
+                            arc_node.aabb = aabb2.clone();
+                        }
+                    }
+                } else {
+                    *arc = right_arc.clone();
+                    arc_mid = arc.mid_point();
+
+                    // calculate aabb radius
+                    tangent_right.x = control.y - arc.center.y;
+                    tangent_right.y = arc.center.x - control.x;
+                    match line_intersection(&end, &u1, &control, &tangent_right, tol) {
+                        LineIntersection::Point(corner, _, _) => {
+                            tangent_left.x = (control.x + corner.x * S::TWO) / three;
+                            tangent_left.y = (control.y + corner.y * S::TWO) / three;
+                            tangent_right.x = (end.x + corner.x * S::TWO) / three;
+                            tangent_right.y = (end.y + corner.y * S::TWO) / three;
+                        }
+                        // half-circle or straight segment: end tangents never meet,
+                        // use the cubic arc handles (4/3 * tan(sweep / 4) * radius)
+                        LineIntersection::Parallel | LineIntersection::Coincident => {
+                            let quarter = arc.sweep.abs() / S::from_f64(4.0);
+                            let handle = u1
+                                * (arc.radius * S::from_f64(4.0) / three * quarter.sin()
+                                    / quarter.cos());
+                            tangent_left = control - handle;
+                            tangent_right = end - handle;
+                        }
+                    }
+
+                    // inlined cubic curve calculation
+                    point_clear(&mut tangent_mid);
+                    point_add_weight_vec(&mut tangent_mid, eighth, &control);
+                    point_add_weight_vec(&mut tangent_mid, three_eighths, &tangent_left);
+                    point_add_weight_vec(&mut tangent_mid, three_eighths, &tangent_right);
+                    point_add_weight_vec(&mut tangent_mid, eighth, &end);
+
+                    arc_node.radius = distance(&arc_mid, &tangent_mid);
+
+                    let mut dist_max = S::ZERO;
+                    let bezier_aabb = {
+                        let t = delta * (S::from_usize(i - 1) + S::HALF);
+                        let tn = t + (delta * S::HALF);
+                        let tn_inv = S::ONE - tn;
+                        let t_sq = t * t;
+                        let tn_sq = tn * tn;
+                        let t_inv = S::ONE - t;
+                        let t_inv_sq = t_inv * t_inv;
+                        let tn_inv_sq = tn_inv * tn_inv;
+                        let t_t_inv = t * t_inv;
+                        let tn_tn_inv = tn * tn_inv;
+
+                        let mid_control_left = Vec2 {
+                            x: tn_inv
+                                * (t_inv_sq * self.control_pts[0].x
+                                    + S::TWO * t_t_inv * self.control_pts[1].x
+                                    + t_sq * self.control_pts[2].x)
+                                + tn * (t_inv_sq * self.control_pts[1].x
+                                    + S::TWO * t_t_inv * self.control_pts[2].x
+                                    + t_sq * self.control_pts[3].x),
+                            y: tn_inv
+                                * (t_inv_sq * self.control_pts[0].y
+                                    + S::TWO * t_t_inv * self.control_pts[1].y
+                                    + t_sq * self.control_pts[2].y)
+                                + tn * (t_inv_sq * self.control_pts[1].y
+                                    + S::TWO * t_t_inv * self.control_pts[2].y
+                                    + t_sq * self.control_pts[3].y),
+                        };
+                        let mid_control_right = Vec2 {
+                            x: t_inv
+                                * (tn_inv_sq * self.control_pts[0].x
+                                    + S::TWO * tn_tn_inv * self.control_pts[1].x
+                                    + tn_sq * self.control_pts[2].x)
+                                + t * (tn_inv_sq * self.control_pts[1].x
+                                    + S::TWO * tn_tn_inv * self.control_pts[2].x
+                                    + tn_sq * self.control_pts[3].x),
+                            y: t_inv
+                                * (tn_inv_sq * self.control_pts[0].y
+                                    + S::TWO * tn_tn_inv * self.control_pts[1].y
+                                    + tn_sq * self.control_pts[2].y)
+                                + t * (tn_inv_sq * self.control_pts[1].y
+                                    + S::TWO * tn_tn_inv * self.control_pts[2].y
+                                    + tn_sq * self.control_pts[3].y),
+                        };
+                        let mut dist_left = distance(&mid_control_left, &tangent_left);
+                        if dist_max < dist_left {
+                            dist_max = dist_left;
+                        }
+
+                        dist_left = distance(&mid_control_right, &tangent_right);
+                        if dist_max < dist_left {
+                            dist_max = dist_left;
+                        }
+
+                        if use_bezier_aabb {
+                            let mut aabb = AABB::new_point(mid, mid_control_left);
+                            let aabb2 = AABB::new_point(mid_control_right, end);
+                            aabb.merge(&aabb2);
+                            Some(aabb)
+                        } else {
+                            None
+                        }
+                    };
+
+                    arc_node.radius += dist_max;
+                    arc_node.aabb = arc.aabb();
+
+                    if use_bezier_aabb {
+                        let mut aabb2 = bezier_aabb.unwrap();
+                        aabb2.merge(&arc_node.aabb);
+                        if aabb2.h < arc_node.aabb.h + S::TWO * arc_node.radius
+                            && aabb2.w < arc_node.aabb.w + S::TWO * arc_node.radius
+                        {
+                            arc_node.radius = S::ZERO;
+                            arc_node.aabb = aabb2.clone();
+                        }
+                    }
+                }
+                is_left = !is_left;
+
+                // calculate aabb
+                // arc_node.aabb = arc.aabb();
+            } else if let Some(left_value) = left_aabb {
+                arc_node.aabb = left_value;
+                arc_node.radius = aabb_radius;
+            }
+        });
+    }
+}
+
+/// Center of the arc through `from` and `to` whose tangent at `from` is `tangent`.
+/// A straight segment is replaced by an arc of radius `tol.max_radius`.
+fn arc_center<S: Scalar>(
+    from: &Vec2<S>,
+    tangent: &Vec2<S>,
+    to: &Vec2<S>,
+    tol: &Tolerance<S>,
+) -> Vec2<S> {
+    let arc_mid = from.lerp(to, S::HALF);
+    let bisector = (*to - *from).perp();
+
+    match line_intersection(from, &tangent.perp(), &arc_mid, &bisector, tol) {
+        LineIntersection::Point(center, _, _) => center,
+        LineIntersection::Parallel | LineIntersection::Coincident => {
+            let chord_len = norm(&bisector);
+            if chord_len <= tol.distance {
+                // zero-length arc
+                *from
+            } else {
+                arc_mid + bisector * (tol.max_radius / chord_len)
+            }
+        }
+    }
+}
+
+impl<S: Scalar> Default for BezierCurve<S> {
+    fn default() -> Self {
+        Self {
+            control_pts: [
+                Vec2::new(S::from_f64(50.0), S::from_f64(100.0)),
+                Vec2::new(S::from_f64(200.0), S::from_f64(300.0)),
+                Vec2::new(S::from_f64(400.0), S::from_f64(300.0)),
+                Vec2::new(S::from_f64(550.0), S::from_f64(100.0)),
+            ],
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::point::Vec2;
+use crate::scalar::Scalar;
+use crate::tree::{complete_depth, complete_parent, Tree};
+
+/// Biarc tree of a single cubic, shared with `build_biarc`
+pub type ArcTree<S> = Rc<RefCell<Tree<ArcBox<S>>>>;
+
+/// Circular arc from `start` (angle aligned to +x-axis) sweeping by `sweep`.
+/// Positive sweep turns from +x-axis towards +y-axis.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ArcData<S> {
+    pub center: Vec2<S>,
+    pub radius: S,
+    pub start: S,
+    pub sweep: S,
+}
+
+impl<S: Scalar> ArcData<S> {
+    /// Arc around `center` from the direction of `from` to the direction of `to`.
+    /// `sweep_sign` chooses the direction; the sweep is in `[0, 2pi)` along it.
+    pub fn from_endpoints(center: Vec2<S>, from: &Vec2<S>, to: &Vec2<S>, sweep_sign: S) -> Self {
+        let v0 = *from - center;
+        let v1 = *to - center;
+        let mut sweep = v0.cross(&v1).atan2(v0.dot(&v1));
+        if sweep_sign >= S::ZERO && sweep < S::ZERO {
+            sweep += S::TAU;
+        } else if sweep_sign < S::ZERO && sweep > S::ZERO {
+            sweep -= S::TAU;
+        }
+
+        ArcData {
+            center,
+            radius: v0.dot(&v0).sqrt(),
+            start: v0.y.atan2(v0.x),
+            sweep,
+        }
+    }
+
+    /// Arc from `p0` to `p1` with `bulge = tan(sweep / 4)`.
+    /// Returns `None` for a straight segment (zero bulge) or coincident points.
+    pub fn from_bulge(p0: &Vec2<S>, p1: &Vec2<S>, bulge: S) -> Option<Self> {
+        let chord = *p1 - *p0;
+        if bulge == S::ZERO || chord.dot(&chord) == S::ZERO {
+            return None;
+        }
+
+        let sweep = S::from_f64(4.0) * bulge.atan();
+        let half = sweep * S::HALF;
+        // offset of the center from the chord midpoint, along the chord normal
+        let center = p0.lerp(p1, S::HALF) + chord.perp() * (S::HALF * half.cos() / half.sin());
+        let mut arc = ArcData::from_endpoints(center, p0, p1, sweep);
+        // keep the exact sweep, rounding could wrap a near-full circle to zero
+        arc.sweep = sweep;
+        Some(arc)
+    }
+
+    /// Start point, end point and bulge (`tan(sweep / 4)`).
+    pub fn to_bulge(&self) -> (Vec2<S>, Vec2<S>, S) {
+        let quarter = self.sweep / S::from_f64(4.0);
+        (
+            self.start_point(),
+            self.end_point(),
+            quarter.sin() / quarter.cos(),
+        )
+    }
+
+    pub fn end(&self) -> S {
+        self.start + self.sweep
+    }
+
+    /// Is the arc turning counter-clockwise (in y-up coordinates)?
+    pub fn is_ccw(&self) -> bool {
+        self.sweep >= S::ZERO
+    }
+
+    /// Point at parameter `t` in `[0, 1]`
+    pub fn point_at(&self, t: S) -> Vec2<S> {
+        self.center + Vec2::from_angle(self.start + self.sweep * t) * self.radius
+    }
+
+    /// Unit tangent at parameter `t` in `[0, 1]`, along the sweep direction
+    pub fn tangent_at(&self, t: S) -> Vec2<S> {
+        let tangent = Vec2::from_angle(self.start + self.sweep * t).perp();
+        if self.is_ccw() {
+            tangent
+        } else {
+            -tangent
+        }
+    }
+
+    pub fn start_point(&self) -> Vec2<S> {
+        self.point_at(S::ZERO)
+    }
+
+    pub fn mid_point(&self) -> Vec2<S> {
+        self.point_at(S::HALF)
+    }
+
+    pub fn end_point(&self) -> Vec2<S> {
+        self.point_at(S::ONE)
+    }
+
+    pub fn length(&self) -> S {
+        self.radius * self.sweep.abs()
+    }
+
+    pub fn contains_angle(&self, angle: S) -> bool {
+        sweep_contains(self.start, self.sweep, angle)
+    }
+
+    /// Sample the arc with `n` segments (`n + 1` points).
+    pub fn sample(&self, n: usize) -> Vec<Vec2<S>> {
+        (0..=n)
+            .map(|i| self.point_at(S::from_usize(i) / S::from_usize(n)))
+            .collect()
+    }
+
+    /// Parallel arc at `distance` to the left of the travel direction (negative: right),
+    /// or `None` if the radius collapses.
+    pub fn offset(&self, distance: S) -> Option<Self> {
+        // the center is on the left of a counter-clockwise arc
+        let radius = if self.is_ccw() {
+            self.radius - distance
+        } else {
+            self.radius + distance
+        };
+        if radius <= S::ZERO {
+            return None;
+        }
+        Some(ArcData {
+            radius,
+            ..self.clone()
+        })
+    }
+
+    /// Distance from `p` to the nearest point on the arc
+    pub fn distance(&self, p: &Vec2<S>) -> S {
+        let v = *p - self.center;
+        if self.contains_angle(v.y.atan2(v.x)) {
+            return (v.dot(&v).sqrt() - self.radius).abs();
+        }
+        let d0 = *p - self.start_point();
+        let d1 = *p - self.end_point();
+        d0.dot(&d0).min(d1.dot(&d1)).sqrt()
+    }
+
+    pub fn aabb(&self) -> AABB<S> {
+        AABB::from_arc(&self.center, self.radius, self.start, self.sweep)
+    }
+}
+
+/// Is `angle` on the arc starting at `start` and sweeping by `sweep` (either direction)?
+pub fn sweep_contains<S: Scalar>(start: S, sweep: S, angle: S) -> bool {
+    if sweep.abs() >= S::TAU {
+        return true;
+    }
+    let mut offset = if sweep >= S::ZERO {
+        angle - start
+    } else {
+        start - angle
+    };
+    // bring the offset into [0, 2pi)
+    while offset < S::ZERO {
+        offset += S::TAU;
+    }
+    while offset >= S::TAU {
+        offset -= S::TAU;
+    }
+    offset <= sweep.abs()
+}
+
+// AABB origin is bottom-left
+#[derive(Debug, Default, Clone)]
+pub struct AABB<S> {
+    pub x: S,
+    pub y: S,
+    pub h: S,
+    pub w: S,
+}
+
+impl<S: Scalar> AABB<S> {
+    /// Bounding box of an arc: both endpoints, plus every axis-extreme point
+    /// (0, 90, 180, 270 degree) the sweep passes through.
+    pub fn from_arc(center: &Vec2<S>, radius: S, start: S, sweep: S) -> Self {
+        let end = start + sweep;
+        let mut aabb = AABB::new_point(
+            *center + Vec2::from_angle(start) * radius,
+            *center + Vec2::from_angle(end) * radius,
+        );
+
+        let extremes = [
+            (S::ZERO, Vec2::new(radius, S::ZERO)),
+            (S::FRAC_PI_2, Vec2::new(S::ZERO, radius)),
+            (S::PI, Vec2::new(-radius, S::ZERO)),
+            (-S::FRAC_PI_2, Vec2::new(S::ZERO, -radius)),
+        ];
+        for (angle, offset) in extremes.iter() {
+            if sweep_contains(start, sweep, *angle) {
+                aabb.merge(&AABB::new_point(*center + *offset, *center + *offset));
+            }
+        }
+        aabb
+    }
+
+    pub fn new_point(p0: Vec2<S>, p1: Vec2<S>) -> Self {
+        AABB {
+            x: if p0.x < p1.x { p0.x } else { p1.x },
+            y: if p0.y < p1.y { p0.y } else { p1.y },
+            h: (p0.y - p1.y).abs(),
+            w: (p0.x - p1.x).abs(),
+        }
+    }
+
+    pub fn merge(&mut self, other: &AABB<S>) {
+        if other.x < self.x {
+            self.w += self.x - other.x;
+            self.x = other.x;
+        }
+        if other.y < self.y {
+            self.h += self.y - other.y;
+            self.y = other.y;
+        }
+        if self.y + self.h < other.y + other.h {
+            self.h = other.y + other.h - self.y;
+        }
+        if self.x + self.w < other.x + other.w {
+            self.w = other.x + other.w - self.x;
+        }
+    }
+
+    pub fn cast<T: Scalar>(&self) -> AABB<T> {
+        AABB {
+            x: self.x.cast(),
+            y: self.y.cast(),
+            h: self.h.cast(),
+            w: self.w.cast(),
+        }
+    }
+
+    pub fn merge_two(this: &AABB<S>, other: &AABB<S>) -> AABB<S> {
+        let mut aabb = this.clone();
+        aabb.merge(other);
+        aabb
+    }
+
+    /// Area of the box grown by `radius`, with corners rounded by `radius`
+    pub fn rounded_area(&self, radius: S) -> S {
+        let four = S::from_f64(4.0);
+        (self.w + S::TWO * radius) * (self.h + S::TWO * radius) - (four - S::PI) * radius * radius
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ArcBox<S> {
+    pub arc: Option<ArcData<S>>,
+    pub aabb: AABB<S>,
+    pub radius: S,
+}
+
+impl<S: Scalar + 'static> ArcBox<S> {
+    pub fn arc_builder(depth: usize) -> Box<dyn Fn(usize) -> ArcBox<S>> {
+        let leaf_id = 2usize.pow(depth as u32) - 1;
+        Box::new(move |node_id| ArcBox {
+            arc: if node_id >= leaf_id {
+                Some(ArcData::default())
+            } else {
+                None
+            },
+            aabb: Default::default(),
+            radius: S::ZERO,
+        })
+    }
+}
+
+/// Parameter interval of the cubic covered by the node `index` of a tree built by
+/// `build_biarc` with `split_num`. Both arcs of a biarc share the interval of their parent,
+/// since the joint has no parameter on the cubic.
+pub fn node_interval<S: Scalar>(index: usize, split_num: usize) -> (S, S) {
+    let mut index = index;
+    while complete_depth(index) > split_num {
+        index = complete_parent(index).unwrap();
+    }
+    let depth = complete_depth(index);
+    let first = (1usize << depth) - 1;
+    let count = S::from_usize(1usize << depth);
+    (
+        S::from_usize(index - first) / count,
+        S::from_usize(index - first + 1) / count,
+    )
+}
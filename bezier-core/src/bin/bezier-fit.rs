@@ -0,0 +1,521 @@
+// Headless biarc fitting: read curves, fit, write JSON / SVG / G-code / PNG / sample tables
+//
+// Error statistics go to stderr so the output can be piped.
+// Projects keep their y-up world coordinates except in the y-down SVG and PNG output;
+// the other inputs are y-down drawings and are written as they are.
+
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+
+use bezier_core::bezier::{BezierCurve, JointStrategy};
+use bezier_core::font::Font;
+use bezier_core::gcode::{export_gcode, GcodeOptions, Units};
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::project::{Display, Project};
+use bezier_core::raster::{render_png, RenderOptions};
+use bezier_core::samples::*;
+use bezier_core::svg::{export_svg, SvgOptions};
+use bezier_core::svg_path::import_svg;
+use serde_json::json;
+
+const USAGE: &str = "\
+usage: bezier-fit (--points X,Y,... | --project FILE | --svg FILE | --font FILE) [options]
+
+input:
+  --points X,Y,...    control points of a composite cubic (3n + 1 points)
+  --project FILE      project file saved by the editor, y-up
+  --svg FILE          path data of an SVG document
+  --font FILE         glyph outlines of a TrueType font, with --text
+  --text TEXT         characters to outline (default A)
+  --size N            font size in canvas units per em (default 200)
+
+fitting:
+  --split N           split the curve into 2^N biarcs per segment, N at most 16
+  --tolerance E       smallest split whose maximum deviation is at most E
+  --max-split N       largest split tried for --tolerance (default 8, at most 16)
+  --joint KIND        joint point: bisector (default) or midpoint
+  --bezier-aabb       bound the tree nodes by the Bezier control points
+  --samples N         curve samples per segment for the statistics
+                      and the sample tables (default 64)
+  --spacing KIND      sample tables at equal steps of t (default) or length
+
+output:
+  --format FORMAT     json (default), svg, gcode, png, samples-csv,
+                      leaves-csv or samples-json (both tables)
+  --aabb-depth N      boxes of the N lowest tree levels in json / svg / png output
+  --width W           svg / png scene width (default 720)
+  --height H          svg / png scene height (default 480)
+  --dpi DPI           png resolution, 96 is one pixel per unit (default 96)
+  -o, --output FILE   write to FILE instead of stdout
+  -h, --help          print this help
+
+gcode output:
+  --units UNITS       mm (default) or in
+  --scale S           machine units per drawing unit (default 1)
+  --feed F            feed rate of cutting moves (default 600)
+  --plunge F          feed rate of the plunge (default 100)
+  --safe-z Z          height of rapid moves between chains (default 5)
+  --cut-z Z           height while cutting (default -1)
+  --precision N       digits after the decimal point (default 3)
+  --offset D          tool offset left of the travel direction,
+                      negative to the right (default 0)
+  --max-radius R      arcs flatter than R become straight moves (default 1e5)
+  --flip-y on|off     mirror y for y-up machine axes
+                      (default off for --project, on otherwise)";
+
+/// Largest split; each step doubles the arcs and the memory
+const MAX_SPLIT: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Svg,
+    Gcode,
+    Png,
+    SamplesCsv,
+    LeavesCsv,
+    SamplesJson,
+}
+
+enum Input {
+    Points(String),
+    Project(String),
+    Svg(String),
+    Font(String),
+}
+
+struct Args {
+    input: Input,
+    split: Option<usize>,
+    tolerance: Option<f64>,
+    max_split: usize,
+    joint: JointStrategy,
+    use_bezier_aabb: bool,
+    samples: usize,
+    spacing: Spacing,
+    format: Format,
+    aabb_depth: Option<usize>,
+    width: f64,
+    height: f64,
+    dpi: f64,
+    text: String,
+    size: f64,
+    output: Option<String>,
+    gcode: GcodeOptions,
+    // unless given, only the y-down inputs are mirrored
+    flip_y: Option<bool>,
+}
+
+fn parse_args(mut argv: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut input = None;
+    let mut args = Args {
+        input: Input::Points(String::new()),
+        split: None,
+        tolerance: None,
+        max_split: 8,
+        joint: JointStrategy::default(),
+        use_bezier_aabb: false,
+        samples: 64,
+        spacing: Spacing::Parameter,
+        format: Format::Json,
+        aabb_depth: None,
+        width: 720.0,
+        height: 480.0,
+        dpi: 96.0,
+        text: String::from("A"),
+        size: 200.0,
+        output: None,
+        gcode: GcodeOptions::default(),
+        flip_y: None,
+    };
+
+    while let Some(arg) = argv.next() {
+        let mut value = || argv.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--points" => input = Some(Input::Points(value()?)),
+            "--project" => input = Some(Input::Project(value()?)),
+            "--svg" => input = Some(Input::Svg(value()?)),
+            "--font" => input = Some(Input::Font(value()?)),
+            "--text" => args.text = value()?,
+            "--size" => args.size = parse_num(&value()?, "--size")?,
+            "--split" => args.split = Some(parse_num(&value()?, "--split")?),
+            "--tolerance" => args.tolerance = Some(parse_num(&value()?, "--tolerance")?),
+            "--max-split" => args.max_split = parse_num(&value()?, "--max-split")?,
+            "--joint" => {
+                args.joint = match value()?.as_str() {
+                    "bisector" => JointStrategy::ChordBisector,
+                    "midpoint" => JointStrategy::CurveMidpoint,
+                    other => return Err(format!("unknown joint '{}'", other)),
+                }
+            }
+            "--bezier-aabb" => args.use_bezier_aabb = true,
+            "--samples" => args.samples = parse_num(&value()?, "--samples")?,
+            "--spacing" => {
+                args.spacing = match value()?.as_str() {
+                    "t" => Spacing::Parameter,
+                    "length" => Spacing::ArcLength,
+                    other => return Err(format!("unknown spacing '{}'", other)),
+                }
+            }
+            "--format" => {
+                args.format = match value()?.as_str() {
+                    "json" => Format::Json,
+                    "svg" => Format::Svg,
+                    "gcode" => Format::Gcode,
+                    "png" => Format::Png,
+                    "samples-csv" => Format::SamplesCsv,
+                    "leaves-csv" => Format::LeavesCsv,
+                    "samples-json" => Format::SamplesJson,
+                    other => return Err(format!("unknown format '{}'", other)),
+                }
+            }
+            "--aabb-depth" => args.aabb_depth = Some(parse_num(&value()?, "--aabb-depth")?),
+            "--width" => args.width = parse_num(&value()?, "--width")?,
+            "--height" => args.height = parse_num(&value()?, "--height")?,
+            "--dpi" => args.dpi = parse_num(&value()?, "--dpi")?,
+            "-o" | "--output" => args.output = Some(value()?),
+            "--units" => {
+                args.gcode.units = match value()?.as_str() {
+                    "mm" => Units::Millimeters,
+                    "in" => Units::Inches,
+                    other => return Err(format!("unknown units '{}'", other)),
+                }
+            }
+            "--scale" => args.gcode.scale = parse_num(&value()?, "--scale")?,
+            "--feed" => args.gcode.feed_rate = parse_num(&value()?, "--feed")?,
+            "--plunge" => args.gcode.plunge_rate = parse_num(&value()?, "--plunge")?,
+            "--safe-z" => args.gcode.safe_z = parse_num(&value()?, "--safe-z")?,
+            "--cut-z" => args.gcode.cut_z = parse_num(&value()?, "--cut-z")?,
+            "--precision" => args.gcode.precision = parse_num(&value()?, "--precision")?,
+            "--offset" => args.gcode.offset = parse_num(&value()?, "--offset")?,
+            "--max-radius" => args.gcode.max_radius = parse_num(&value()?, "--max-radius")?,
+            "--flip-y" => {
+                args.flip_y = match value()?.as_str() {
+                    "on" => Some(true),
+                    "off" => Some(false),
+                    other => return Err(format!("--flip-y takes on or off, not '{}'", other)),
+                }
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    args.input = input.ok_or("no input, use --points, --project, --svg or --font")?;
+    if args.split.is_some() && args.tolerance.is_some() {
+        return Err(String::from("--split and --tolerance are exclusive"));
+    }
+    if !(args.width > 0.0 && args.height > 0.0 && args.dpi > 0.0) {
+        return Err(String::from("--width, --height and --dpi must be positive"));
+    }
+    if args.split.unwrap_or(0) > MAX_SPLIT || args.max_split > MAX_SPLIT {
+        return Err(format!(
+            "--split and --max-split must be at most {}",
+            MAX_SPLIT
+        ));
+    }
+    if args.samples == 0 {
+        return Err(String::from("--samples must be positive"));
+    }
+    let gcode = &args.gcode;
+    if !(gcode.scale > 0.0 && gcode.feed_rate > 0.0 && gcode.plunge_rate > 0.0) {
+        return Err(String::from(
+            "--scale, --feed and --plunge must be positive",
+        ));
+    }
+    if gcode.safe_z <= gcode.cut_z {
+        return Err(String::from("--safe-z must be above --cut-z"));
+    }
+    Ok(args)
+}
+
+fn parse_num<T: std::str::FromStr>(text: &str, name: &str) -> Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("invalid value '{}' for {}", text, name))
+}
+
+/// Control points `x0,y0,x1,y1,...` as cubics sharing their endpoints
+fn parse_points(text: &str) -> Result<BezierPath<f64>, String> {
+    let coords = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_num(s, "--points"))
+        .collect::<Result<Vec<f64>, String>>()?;
+    if coords.len() % 2 != 0 {
+        return Err(String::from("--points needs an even number of coordinates"));
+    }
+    let points: Vec<Vec2<f64>> = coords.chunks(2).map(|c| Vec2::new(c[0], c[1])).collect();
+    if points.len() < 4 || points.len() % 3 != 1 {
+        return Err(format!(
+            "--points needs 3n + 1 control points, got {}",
+            points.len()
+        ));
+    }
+
+    let segments = (0..points.len() / 3)
+        .map(|i| BezierCurve {
+            control_pts: [
+                points[3 * i],
+                points[3 * i + 1],
+                points[3 * i + 2],
+                points[3 * i + 3],
+            ],
+        })
+        .collect();
+    Ok(BezierPath {
+        segments,
+        closed: false,
+    })
+}
+
+/// Paths fitted at their splits, with the Bezier boxes of `--bezier-aabb` or their display
+fn fit(
+    paths: &[BezierPath<f64>],
+    displays: &[Display],
+    splits: &[usize],
+    args: &Args,
+) -> Vec<FittedPath<f64>> {
+    let tol = Tolerance::default();
+    paths
+        .iter()
+        .zip(displays.iter().zip(splits.iter()))
+        .map(|(path, (display, &split))| {
+            let use_bezier_aabb = args.use_bezier_aabb || display.use_bezier_aabb;
+            FittedPath::with_joint(path.clone(), split, use_bezier_aabb, args.joint, &tol)
+        })
+        .collect()
+}
+
+// `N`, or `N to M` for curves fitted at different splits
+fn format_splits(splits: &[usize]) -> String {
+    let min = splits.iter().min().copied().unwrap_or(0);
+    let max = splits.iter().max().copied().unwrap_or(0);
+    if min == max {
+        min.to_string()
+    } else {
+        format!("{} to {}", min, max)
+    }
+}
+
+/// Combined statistics of all paths
+fn deviation(fitted: &[FittedPath<f64>], samples: usize) -> Deviation<f64> {
+    fitted
+        .iter()
+        .map(|path| path.deviation(samples))
+        .fold(Deviation::default(), |total, d| total.merge(&d))
+}
+
+fn to_json(
+    fitted: &[FittedPath<f64>],
+    splits: &[usize],
+    styles: &[PathStyle],
+    stats: &Deviation<f64>,
+) -> String {
+    let curves: Vec<_> = fitted
+        .iter()
+        .zip(splits.iter().zip(styles.iter()))
+        .map(|(path, (split, style))| {
+            let arcs: Vec<_> = path
+                .leaves()
+                .iter()
+                .map(|arc| {
+                    json!({
+                        "center": [arc.center.x, arc.center.y],
+                        "radius": arc.radius,
+                        "start": arc.start,
+                        "sweep": arc.sweep,
+                    })
+                })
+                .collect();
+            let boxes: Vec<_> = path
+                .boxes(style.aabb_depth)
+                .iter()
+                .map(|(aabb, radius)| {
+                    json!({
+                        "x": aabb.x,
+                        "y": aabb.y,
+                        "w": aabb.w,
+                        "h": aabb.h,
+                        "radius": radius,
+                    })
+                })
+                .collect();
+            json!({ "split": split, "arcs": arcs, "boxes": boxes })
+        })
+        .collect();
+
+    // the largest split, kept for readers of single-curve documents
+    let document = json!({
+        "split": splits.iter().max(),
+        "deviation": {
+            "max": stats.max,
+            "mean": stats.mean,
+            "rms": stats.rms,
+            "samples": stats.samples,
+        },
+        "curves": curves,
+    });
+    serde_json::to_string_pretty(&document).unwrap()
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let mut project = Project::default();
+    let paths = match &args.input {
+        Input::Points(text) => vec![parse_points(text)?],
+        Input::Project(file) => {
+            project = Project::load(file).map_err(|err| format!("{}: {}", file, err))?;
+            // world coordinates, mirrored only for the y-down formats as in the editor
+            let mut curves = project.curves.clone();
+            if matches!(args.format, Format::Svg | Format::Png) {
+                mirror_y(&mut curves);
+            }
+            curves
+        }
+        Input::Font(file) => {
+            // first line at a size-high margin from the top left
+            let origin = Vec2::new(args.size * 0.25, args.size);
+            Font::load(file)
+                .and_then(|font| font.text_outline(&args.text, args.size, origin))
+                .map_err(|err| format!("{}: {}", file, err))?
+        }
+        Input::Svg(file) => {
+            let svg = fs::read_to_string(file).map_err(|err| format!("{}: {}", file, err))?;
+            import_svg(&svg).map_err(|err| format!("{}: {}", file, err))?
+        }
+    };
+    if paths.is_empty() {
+        return Err(String::from("no curves in the input"));
+    }
+
+    // settings saved with each curve; --split, --bezier-aabb and --aabb-depth override them
+    let displays: Vec<Display> = (0..paths.len())
+        .map(|i| project.curve_display(i).clone())
+        .collect();
+
+    // the smallest split meeting the tolerance, else the given or saved ones
+    let (splits, fitted) = match args.tolerance {
+        Some(tolerance) => {
+            let mut split = 0;
+            loop {
+                let splits = vec![split; paths.len()];
+                let fitted = fit(&paths, &displays, &splits, &args);
+                let stats = deviation(&fitted, args.samples);
+                if stats.max <= tolerance || split >= args.max_split {
+                    if stats.max > tolerance {
+                        eprintln!(
+                            "warning: tolerance {} not met at --max-split {}",
+                            tolerance, args.max_split
+                        );
+                    }
+                    break (splits, fitted);
+                }
+                split += 1;
+            }
+        }
+        None => {
+            let splits: Vec<usize> = displays
+                .iter()
+                .map(|display| args.split.unwrap_or(display.num_split))
+                .collect();
+            if let Some(split) = splits.iter().find(|&&split| split > MAX_SPLIT) {
+                return Err(format!(
+                    "split {} is above the limit of {}",
+                    split, MAX_SPLIT
+                ));
+            }
+            let fitted = fit(&paths, &displays, &splits, &args);
+            (splits, fitted)
+        }
+    };
+
+    let stats = deviation(&fitted, args.samples);
+    let arcs: usize = fitted.iter().map(|path| path.leaves().len()).sum();
+    eprintln!(
+        "split {}: {} arcs, deviation max {:.6} mean {:.6} rms {:.6} ({} samples)",
+        format_splits(&splits),
+        arcs,
+        stats.max,
+        stats.mean,
+        stats.rms,
+        stats.samples
+    );
+
+    let styles: Vec<PathStyle> = displays
+        .iter()
+        .map(|display| PathStyle {
+            is_dotted: display.is_dotted,
+            is_meshed: display.is_meshed,
+            aabb_depth: args.aabb_depth.unwrap_or(display.aabb_depth),
+        })
+        .collect();
+    let sample_options = SampleOptions {
+        spacing: args.spacing,
+        samples: args.samples,
+    };
+    let output = match args.format {
+        Format::Json => to_json(&fitted, &splits, &styles, &stats).into_bytes(),
+        Format::Svg => export_svg(
+            &fitted,
+            &SvgOptions {
+                width: args.width,
+                height: args.height,
+                left_color: project.colors.left_arc,
+                right_color: project.colors.right_arc,
+                path_styles: styles,
+                ..SvgOptions::default()
+            },
+        )
+        .into_bytes(),
+        Format::Gcode => {
+            let chains: Vec<_> = fitted.iter().map(|path| path.leaves()).collect();
+            let y_up = matches!(args.input, Input::Project(_));
+            let options = GcodeOptions {
+                flip_y: args.flip_y.unwrap_or(!y_up),
+                ..args.gcode.clone()
+            };
+            export_gcode(&chains, &options).into_bytes()
+        }
+        Format::Png => render_png(
+            &fitted,
+            &RenderOptions {
+                width: args.width,
+                height: args.height,
+                dpi: args.dpi,
+                left_color: project.colors.left_arc,
+                right_color: project.colors.right_arc,
+                path_styles: styles,
+                ..RenderOptions::default()
+            },
+        ),
+        Format::SamplesCsv => samples_csv(&sample_paths(&fitted, &sample_options), 6).into_bytes(),
+        Format::LeavesCsv => leaves_csv(&leaf_table(&fitted), 6).into_bytes(),
+        Format::SamplesJson => export_json(
+            &sample_paths(&fitted, &sample_options),
+            &leaf_table(&fitted),
+            6,
+        )
+        .into_bytes(),
+    };
+
+    match &args.output {
+        Some(file) => fs::write(file, output).map_err(|err| format!("{}: {}", file, err)),
+        None => io::stdout()
+            .write_all(&output)
+            .map_err(|err| err.to_string()),
+    }
+}
+
+fn main() {
+    let result = parse_args(std::env::args().skip(1)).and_then(run);
+    if let Err(err) = result {
+        eprintln!("bezier-fit: {}", err);
+        eprintln!("try 'bezier-fit --help'");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,283 @@
+// DXF export of the fitted arc chains
+//
+// Minimal ASCII DXF: a layer table and the entities section, which CAD tools accept
+// without handles. DXF is y-up and its ARC entities always run counter-clockwise.
+
+use std::fmt::Write;
+
+use crate::biarc::*;
+use crate::path::FittedPath;
+use crate::point::Vec2;
+use crate::svg::format_num;
+
+/// How the biarcs are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArcOutput {
+    /// One ARC entity per arc
+    Arcs,
+    /// One LWPOLYLINE with bulges per curve
+    Polyline,
+}
+
+#[derive(Debug, Clone)]
+pub struct DxfOptions {
+    pub arc_output: ArcOutput,
+    /// Also write the Bezier curves as SPLINE entities
+    pub spline: bool,
+    /// Write the boxes of this many lowest tree levels, as the editor's AABB depth
+    pub aabb_depth: usize,
+    /// Box depth of each path, in path order; paths without an entry use `aabb_depth`
+    pub aabb_depths: Vec<usize>,
+    /// Mirror y, for y-down drawings such as imported SVG or glyph outlines;
+    /// the editor's world is y-up like DXF and exports with it off
+    pub flip_y: bool,
+    /// Digits after the decimal point
+    pub precision: usize,
+    pub curve_layer: String,
+    pub arc_layer: String,
+    pub box_layer: String,
+}
+
+impl Default for DxfOptions {
+    fn default() -> Self {
+        DxfOptions {
+            arc_output: ArcOutput::Arcs,
+            spline: false,
+            aabb_depth: 0,
+            aabb_depths: Vec::new(),
+            flip_y: true,
+            precision: 6,
+            curve_layer: String::from("curve"),
+            arc_layer: String::from("arcs"),
+            box_layer: String::from("boxes"),
+        }
+    }
+}
+
+struct DxfWriter<'a> {
+    out: String,
+    options: &'a DxfOptions,
+}
+
+impl<'a> DxfWriter<'a> {
+    fn pair(&mut self, code: u32, value: &str) {
+        writeln!(self.out, "{:>3}\n{}", code, value).unwrap();
+    }
+
+    fn num(&mut self, code: u32, value: f64) {
+        let value = format_num(value, self.options.precision);
+        self.pair(code, &value);
+    }
+
+    fn point(&mut self, code: u32, p: &Vec2<f64>) {
+        self.num(code, p.x);
+        self.num(code + 10, p.y);
+    }
+
+    fn map(&self, p: &Vec2<f64>) -> Vec2<f64> {
+        if self.options.flip_y {
+            Vec2::new(p.x, -p.y)
+        } else {
+            *p
+        }
+    }
+
+    // arc in output coordinates
+    fn map_arc(&self, arc: &ArcData<f64>) -> ArcData<f64> {
+        if self.options.flip_y {
+            ArcData {
+                center: self.map(&arc.center),
+                radius: arc.radius,
+                start: -arc.start,
+                sweep: -arc.sweep,
+            }
+        } else {
+            arc.clone()
+        }
+    }
+
+    fn entity(&mut self, kind: &str, layer: &str) {
+        self.pair(0, kind);
+        self.pair(8, layer);
+    }
+
+    fn arc(&mut self, arc: &ArcData<f64>) {
+        let arc = self.map_arc(arc);
+        // counter-clockwise from the smaller angle
+        let (start, end) = if arc.is_ccw() {
+            (arc.start, arc.end())
+        } else {
+            (arc.end(), arc.start)
+        };
+        let layer = self.options.arc_layer.clone();
+        self.entity("ARC", &layer);
+        self.point(10, &arc.center);
+        self.num(40, arc.radius);
+        self.num(50, normalize_degrees(start.to_degrees()));
+        self.num(51, normalize_degrees(end.to_degrees()));
+    }
+
+    // each vertex with the bulge of the edge leaving it
+    fn polyline(&mut self, layer: &str, vertices: &[(Vec2<f64>, f64)], closed: bool) {
+        self.entity("LWPOLYLINE", layer);
+        self.pair(90, &vertices.len().to_string());
+        self.pair(70, if closed { "1" } else { "0" });
+        for (p, bulge) in vertices.iter() {
+            self.point(10, p);
+            if *bulge != 0.0 {
+                self.num(42, *bulge);
+            }
+        }
+    }
+
+    fn arc_polyline(&mut self, arcs: &[ArcData<f64>]) {
+        let mut vertices: Vec<(Vec2<f64>, f64)> = arcs
+            .iter()
+            .map(|arc| {
+                let (start, _, bulge) = self.map_arc(arc).to_bulge();
+                (start, bulge)
+            })
+            .collect();
+        if let Some(last) = arcs.last() {
+            vertices.push((self.map(&last.end_point()), 0.0));
+        }
+        let layer = self.options.arc_layer.clone();
+        self.polyline(&layer, &vertices, false);
+    }
+
+    // rounded box as a closed polyline, counter-clockwise
+    fn aabb(&mut self, aabb: &AABB<f64>, r: f64) {
+        let y = if self.options.flip_y {
+            -aabb.y - aabb.h
+        } else {
+            aabb.y
+        };
+        let (x0, y0, x1, y1) = (aabb.x, y, aabb.x + aabb.w, y + aabb.h);
+        let vertices = if r <= 0.0 {
+            vec![
+                (Vec2::new(x0, y0), 0.0),
+                (Vec2::new(x1, y0), 0.0),
+                (Vec2::new(x1, y1), 0.0),
+                (Vec2::new(x0, y1), 0.0),
+            ]
+        } else {
+            // quarter circle: tan(90 / 4 degree)
+            let corner = (std::f64::consts::PI / 8.0).tan();
+            vec![
+                (Vec2::new(x0, y0 - r), 0.0),
+                (Vec2::new(x1, y0 - r), corner),
+                (Vec2::new(x1 + r, y0), 0.0),
+                (Vec2::new(x1 + r, y1), corner),
+                (Vec2::new(x1, y1 + r), 0.0),
+                (Vec2::new(x0, y1 + r), corner),
+                (Vec2::new(x0 - r, y1), 0.0),
+                (Vec2::new(x0 - r, y0), corner),
+            ]
+        };
+        let layer = self.options.box_layer.clone();
+        self.polyline(&layer, &vertices, true);
+    }
+
+    // cubic segments as one clamped degree-3 B-spline
+    fn spline(&mut self, fitted: &FittedPath<f64>) {
+        let segments = &fitted.path.segments;
+        if segments.is_empty() {
+            return;
+        }
+        let mut controls = vec![segments[0].control_pts[0]];
+        for segment in segments.iter() {
+            controls.extend_from_slice(&segment.control_pts[1..]);
+        }
+        // every interior knot repeated 3 times keeps the Bezier segments exact
+        let mut knots = vec![0.0; 4];
+        for i in 1..segments.len() {
+            knots.extend_from_slice(&[i as f64; 3]);
+        }
+        knots.extend_from_slice(&[segments.len() as f64; 4]);
+
+        let layer = self.options.curve_layer.clone();
+        self.entity("SPLINE", &layer);
+        // planar
+        self.pair(70, "8");
+        self.pair(71, "3");
+        self.pair(72, &knots.len().to_string());
+        self.pair(73, &controls.len().to_string());
+        for knot in knots.iter() {
+            self.num(40, *knot);
+        }
+        for p in controls.iter() {
+            let p = self.map(p);
+            self.point(10, &p);
+            self.num(30, 0.0);
+        }
+    }
+}
+
+/// Angle in `[0, 360)` degree
+fn normalize_degrees(angle: f64) -> f64 {
+    let angle = angle % 360.0;
+    if angle < 0.0 {
+        angle + 360.0
+    } else {
+        angle
+    }
+}
+
+/// Write the biarcs of every path (and optionally its spline and boxes) as a DXF document.
+pub fn export_dxf(paths: &[FittedPath<f64>], options: &DxfOptions) -> String {
+    let mut dxf = DxfWriter {
+        out: String::new(),
+        options,
+    };
+
+    dxf.pair(0, "SECTION");
+    dxf.pair(2, "HEADER");
+    dxf.pair(9, "$ACADVER");
+    dxf.pair(1, "AC1015");
+    dxf.pair(0, "ENDSEC");
+
+    // layers: name and color number
+    let layers = [
+        (&options.curve_layer, "7"),
+        (&options.arc_layer, "3"),
+        (&options.box_layer, "5"),
+    ];
+    dxf.pair(0, "SECTION");
+    dxf.pair(2, "TABLES");
+    dxf.pair(0, "TABLE");
+    dxf.pair(2, "LAYER");
+    dxf.pair(70, &layers.len().to_string());
+    for (name, color) in layers.iter() {
+        dxf.pair(0, "LAYER");
+        dxf.pair(2, name);
+        dxf.pair(70, "0");
+        dxf.pair(62, color);
+        dxf.pair(6, "CONTINUOUS");
+    }
+    dxf.pair(0, "ENDTAB");
+    dxf.pair(0, "ENDSEC");
+
+    dxf.pair(0, "SECTION");
+    dxf.pair(2, "ENTITIES");
+    for (i, fitted) in paths.iter().enumerate() {
+        let aabb_depth = options.aabb_depths.get(i).copied();
+        for (aabb, r) in fitted.boxes(aabb_depth.unwrap_or(options.aabb_depth)) {
+            dxf.aabb(&aabb, r);
+        }
+        if options.spline {
+            dxf.spline(fitted);
+        }
+        let arcs = fitted.leaves();
+        match options.arc_output {
+            ArcOutput::Arcs => {
+                for arc in arcs.iter() {
+                    dxf.arc(arc);
+                }
+            }
+            ArcOutput::Polyline => dxf.arc_polyline(&arcs),
+        }
+    }
+    dxf.pair(0, "ENDSEC");
+    dxf.pair(0, "EOF");
+    dxf.out
+}
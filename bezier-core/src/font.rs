@@ -0,0 +1,498 @@
+// TrueType glyph outlines as composite curves
+//
+// Reads the tables needed for outlines (head, maxp, hhea, hmtx, cmap, loca, glyf).
+// Quadratic contours become closed paths of cubics; consecutive off-curve points
+// imply the on-curve point halfway between them.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::bezier::BezierCurve;
+use crate::path::BezierPath;
+use crate::point::Vec2;
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    /// A required table is missing
+    MissingTable(&'static str),
+    /// A table is truncated or has unsupported contents
+    Malformed(String),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FontError::Io(err) => write!(f, "{}", err),
+            FontError::MissingTable(tag) => write!(f, "missing '{}' table", tag),
+            FontError::Malformed(message) => write!(f, "malformed font: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<io::Error> for FontError {
+    fn from(err: io::Error) -> Self {
+        FontError::Io(err)
+    }
+}
+
+fn malformed<T>(message: &str) -> Result<T, FontError> {
+    Err(FontError::Malformed(String::from(message)))
+}
+
+// glyph flags
+const ON_CURVE: u8 = 0x01;
+const X_SHORT: u8 = 0x02;
+const Y_SHORT: u8 = 0x04;
+const REPEAT: u8 = 0x08;
+const X_SAME: u8 = 0x10;
+const Y_SAME: u8 = 0x20;
+
+// composite glyph flags
+const ARGS_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY: u16 = 0x0002;
+const HAVE_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const HAVE_XY_SCALE: u16 = 0x0040;
+const HAVE_TWO_BY_TWO: u16 = 0x0080;
+
+/// Components may nest; deeper nesting is treated as a broken font
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+/// Quadratic contour: points and their on-curve flags
+type Contour = Vec<(Vec2<f64>, bool)>;
+
+/// Parsed TrueType font (glyf outlines)
+#[derive(Debug, Clone)]
+pub struct Font {
+    data: Vec<u8>,
+    units_per_em: u16,
+    long_loca: bool,
+    num_glyphs: u16,
+    num_h_metrics: u16,
+    cmap: usize,
+    loca: usize,
+    glyf: usize,
+    hmtx: usize,
+}
+
+impl Font {
+    pub fn parse(data: Vec<u8>) -> Result<Font, FontError> {
+        let table = |tag: &'static str| -> Result<usize, FontError> {
+            let num_tables = read_u16(&data, 4)? as usize;
+            for i in 0..num_tables {
+                let record = 12 + 16 * i;
+                if data.get(record..record + 4) == Some(tag.as_bytes()) {
+                    return Ok(read_u32(&data, record + 8)? as usize);
+                }
+            }
+            Err(FontError::MissingTable(tag))
+        };
+
+        let head = table("head")?;
+        let maxp = table("maxp")?;
+        let hhea = table("hhea")?;
+        let mut font = Font {
+            units_per_em: read_u16(&data, head + 18)?,
+            long_loca: read_u16(&data, head + 50)? == 1,
+            num_glyphs: read_u16(&data, maxp + 4)?,
+            num_h_metrics: read_u16(&data, hhea + 34)?,
+            cmap: table("cmap")?,
+            loca: table("loca")?,
+            glyf: table("glyf")?,
+            hmtx: table("hmtx")?,
+            data: Vec::new(),
+        };
+        if font.units_per_em == 0 {
+            return malformed("zero units per em");
+        }
+        font.data = data;
+        Ok(font)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Font, FontError> {
+        Font::parse(std::fs::read(path)?)
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    /// Glyph of `ch` from the Unicode cmap, `None` if the font has none
+    pub fn glyph_index(&self, ch: char) -> Option<u16> {
+        let data = &self.data;
+        let num_tables = read_u16(data, self.cmap + 2).ok()?;
+        // prefer the full-range format 12 subtable over the BMP-only format 4
+        let mut best = None;
+        for i in 0..num_tables as usize {
+            let record = self.cmap + 4 + 8 * i;
+            let platform = read_u16(data, record).ok()?;
+            let encoding = read_u16(data, record + 2).ok()?;
+            let offset = self.cmap + read_u32(data, record + 4).ok()? as usize;
+            let unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+            if !unicode {
+                continue;
+            }
+            match read_u16(data, offset).ok()? {
+                12 => best = Some((12, offset)),
+                4 if best.is_none() => best = Some((4, offset)),
+                _ => {}
+            }
+        }
+
+        let (format, offset) = best?;
+        let glyph = if format == 12 {
+            cmap_format12(data, offset, ch as u32)
+        } else {
+            cmap_format4(data, offset, ch as u32)
+        };
+        glyph.filter(|&glyph| glyph != 0 && glyph < self.num_glyphs)
+    }
+
+    /// Horizontal advance in font units
+    pub fn advance_width(&self, glyph: u16) -> u16 {
+        // glyphs after the last metric share its advance
+        let index = glyph.min(self.num_h_metrics.saturating_sub(1)) as usize;
+        read_u16(&self.data, self.hmtx + 4 * index).unwrap_or(0)
+    }
+
+    /// Contours of `glyph` as closed paths, in font units (y-up, origin on the baseline)
+    pub fn glyph_outline(&self, glyph: u16) -> Result<Vec<BezierPath<f64>>, FontError> {
+        let mut paths = Vec::new();
+        for contour in self.contours(glyph, 0)? {
+            if let Some(path) = contour_path(&contour) {
+                paths.push(path);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Outlines of `text` on one line, `size` canvas units per em.
+    /// The baseline starts at `origin` and y points down as on the canvas.
+    pub fn text_outline(
+        &self,
+        text: &str,
+        size: f64,
+        origin: Vec2<f64>,
+    ) -> Result<Vec<BezierPath<f64>>, FontError> {
+        let scale = size / self.units_per_em as f64;
+        let mut pen = origin.x;
+        let mut paths = Vec::new();
+        for ch in text.chars() {
+            // missing characters show the .notdef box
+            let glyph = self.glyph_index(ch).unwrap_or(0);
+            for mut path in self.glyph_outline(glyph)? {
+                for segment in path.segments.iter_mut() {
+                    for p in segment.control_pts.iter_mut() {
+                        *p = Vec2::new(pen + p.x * scale, origin.y - p.y * scale);
+                    }
+                }
+                paths.push(path);
+            }
+            pen += self.advance_width(glyph) as f64 * scale;
+        }
+        Ok(paths)
+    }
+
+    /// Contours as (point, on-curve) lists, components resolved
+    fn contours(&self, glyph: u16, depth: usize) -> Result<Vec<Contour>, FontError> {
+        if glyph >= self.num_glyphs {
+            return malformed("glyph index out of range");
+        }
+        if depth > MAX_COMPONENT_DEPTH {
+            return malformed("components nested too deep");
+        }
+
+        let data = &self.data;
+        let (start, end) = if self.long_loca {
+            let at = self.loca + 4 * glyph as usize;
+            (
+                read_u32(data, at)? as usize,
+                read_u32(data, at + 4)? as usize,
+            )
+        } else {
+            let at = self.loca + 2 * glyph as usize;
+            (
+                read_u16(data, at)? as usize * 2,
+                read_u16(data, at + 2)? as usize * 2,
+            )
+        };
+        // no outline, e.g. space
+        if end <= start {
+            return Ok(Vec::new());
+        }
+
+        let offset = self.glyf + start;
+        let num_contours = read_u16(data, offset)? as i16;
+        if num_contours >= 0 {
+            simple_contours(data, offset + 10, num_contours as usize)
+        } else {
+            self.composite_contours(offset + 10, depth)
+        }
+    }
+
+    fn composite_contours(&self, mut at: usize, depth: usize) -> Result<Vec<Contour>, FontError> {
+        let data = &self.data;
+        let f2dot14 = |at: usize| -> Result<f64, FontError> {
+            Ok(read_u16(data, at)? as i16 as f64 / 16384.0)
+        };
+
+        let mut contours = Vec::new();
+        loop {
+            let flags = read_u16(data, at)?;
+            let component = read_u16(data, at + 2)?;
+            at += 4;
+            let (dx, dy) = if flags & ARGS_ARE_WORDS != 0 {
+                at += 4;
+                (
+                    read_u16(data, at - 4)? as i16 as f64,
+                    read_u16(data, at - 2)? as i16 as f64,
+                )
+            } else {
+                at += 2;
+                (
+                    read_u8(data, at - 2)? as i8 as f64,
+                    read_u8(data, at - 1)? as i8 as f64,
+                )
+            };
+            if flags & ARGS_ARE_XY == 0 {
+                return malformed("point-matched components are not supported");
+            }
+
+            // [a c; b d] as in the spec
+            let (mut a, mut b, mut c, mut d) = (1.0, 0.0, 0.0, 1.0);
+            if flags & HAVE_SCALE != 0 {
+                a = f2dot14(at)?;
+                d = a;
+                at += 2;
+            } else if flags & HAVE_XY_SCALE != 0 {
+                a = f2dot14(at)?;
+                d = f2dot14(at + 2)?;
+                at += 4;
+            } else if flags & HAVE_TWO_BY_TWO != 0 {
+                a = f2dot14(at)?;
+                b = f2dot14(at + 2)?;
+                c = f2dot14(at + 4)?;
+                d = f2dot14(at + 6)?;
+                at += 8;
+            }
+
+            for contour in self.contours(component, depth + 1)? {
+                contours.push(
+                    contour
+                        .into_iter()
+                        .map(|(p, on)| {
+                            (
+                                Vec2::new(a * p.x + c * p.y + dx, b * p.x + d * p.y + dy),
+                                on,
+                            )
+                        })
+                        .collect(),
+                );
+            }
+
+            if flags & MORE_COMPONENTS == 0 {
+                return Ok(contours);
+            }
+        }
+    }
+}
+
+fn simple_contours(
+    data: &[u8],
+    mut at: usize,
+    num_contours: usize,
+) -> Result<Vec<Contour>, FontError> {
+    let mut ends = Vec::with_capacity(num_contours);
+    for i in 0..num_contours {
+        ends.push(read_u16(data, at + 2 * i)? as usize);
+    }
+    at += 2 * num_contours;
+    let num_points = match ends.last() {
+        Some(&last) => last + 1,
+        None => return Ok(Vec::new()),
+    };
+    // skip the hinting instructions
+    at += 2 + read_u16(data, at)? as usize;
+
+    let mut flags = Vec::with_capacity(num_points);
+    while flags.len() < num_points {
+        let flag = read_u8(data, at)?;
+        at += 1;
+        flags.push(flag);
+        if flag & REPEAT != 0 {
+            let count = read_u8(data, at)?;
+            at += 1;
+            for _ in 0..count {
+                flags.push(flag);
+            }
+        }
+    }
+    flags.truncate(num_points);
+
+    // coordinates are deltas; short ones carry their sign in the SAME bit
+    let mut read_coords = |short: u8, same: u8| -> Result<Vec<f64>, FontError> {
+        let mut value = 0i32;
+        let mut coords = Vec::with_capacity(num_points);
+        for &flag in flags.iter() {
+            if flag & short != 0 {
+                let delta = read_u8(data, at)? as i32;
+                at += 1;
+                value += if flag & same != 0 { delta } else { -delta };
+            } else if flag & same == 0 {
+                value += read_u16(data, at)? as i16 as i32;
+                at += 2;
+            }
+            coords.push(value as f64);
+        }
+        Ok(coords)
+    };
+    let xs = read_coords(X_SHORT, X_SAME)?;
+    let ys = read_coords(Y_SHORT, Y_SAME)?;
+
+    let mut contours = Vec::with_capacity(num_contours);
+    let mut first = 0;
+    for &end in ends.iter() {
+        if end < first || end >= num_points {
+            return malformed("contour end points out of order");
+        }
+        contours.push(
+            (first..=end)
+                .map(|i| (Vec2::new(xs[i], ys[i]), flags[i] & ON_CURVE != 0))
+                .collect(),
+        );
+        first = end + 1;
+    }
+    Ok(contours)
+}
+
+/// Closed path through a quadratic contour, `None` if it has no area to trace
+fn contour_path(points: &[(Vec2<f64>, bool)]) -> Option<BezierPath<f64>> {
+    let n = points.len();
+    if n < 2 {
+        return None;
+    }
+    // start on an on-curve point, else on the one implied before the first point
+    let (start, order): (Vec2<f64>, Vec<usize>) = match points.iter().position(|(_, on)| *on) {
+        Some(s) => (points[s].0, (1..=n).map(|k| (s + k) % n).collect()),
+        None => (points[n - 1].0.lerp(&points[0].0, 0.5), (0..n).collect()),
+    };
+
+    let mut segments = Vec::new();
+    let mut current = start;
+    let mut control: Option<Vec2<f64>> = None;
+    let sequence = order
+        .iter()
+        .map(|&i| points[i])
+        .chain(std::iter::once((start, true)));
+    for (p, on) in sequence {
+        match (on, control) {
+            (true, Some(c)) => {
+                segments.push(BezierCurve::quadratic(current, c, p));
+                current = p;
+                control = None;
+            }
+            (true, None) => {
+                if p != current {
+                    segments.push(BezierCurve::line(current, p));
+                }
+                current = p;
+            }
+            (false, Some(c)) => {
+                let mid = c.lerp(&p, 0.5);
+                segments.push(BezierCurve::quadratic(current, c, mid));
+                current = mid;
+                control = Some(p);
+            }
+            (false, None) => control = Some(p),
+        }
+    }
+
+    if segments.is_empty() {
+        None
+    } else {
+        Some(BezierPath {
+            segments,
+            closed: true,
+        })
+    }
+}
+
+fn cmap_format4(data: &[u8], offset: usize, code: u32) -> Option<u16> {
+    if code > 0xffff {
+        return None;
+    }
+    let seg_count = read_u16(data, offset + 6).ok()? as usize / 2;
+    let ends = offset + 14;
+    let starts = ends + 2 * seg_count + 2;
+    let deltas = starts + 2 * seg_count;
+    let range_offsets = deltas + 2 * seg_count;
+    for i in 0..seg_count {
+        let end = read_u16(data, ends + 2 * i).ok()? as u32;
+        if code > end {
+            continue;
+        }
+        let start = read_u16(data, starts + 2 * i).ok()? as u32;
+        if code < start {
+            return None;
+        }
+        let delta = read_u16(data, deltas + 2 * i).ok()?;
+        let range_offset = read_u16(data, range_offsets + 2 * i).ok()? as usize;
+        if range_offset == 0 {
+            return Some((code as u16).wrapping_add(delta));
+        }
+        // offset from the range offset entry itself into the glyph id array
+        let at = range_offsets + 2 * i + range_offset + 2 * (code - start) as usize;
+        let glyph = read_u16(data, at).ok()?;
+        return if glyph == 0 {
+            None
+        } else {
+            Some(glyph.wrapping_add(delta))
+        };
+    }
+    None
+}
+
+fn cmap_format12(data: &[u8], offset: usize, code: u32) -> Option<u16> {
+    let num_groups = read_u32(data, offset + 12).ok()? as usize;
+    for i in 0..num_groups {
+        let group = offset + 16 + 12 * i;
+        let start = read_u32(data, group).ok()?;
+        let end = read_u32(data, group + 4).ok()?;
+        if (start..=end).contains(&code) {
+            let glyph = read_u32(data, group + 8).ok()? + (code - start);
+            return Some(glyph as u16);
+        }
+    }
+    None
+}
+
+fn read_u8(data: &[u8], at: usize) -> Result<u8, FontError> {
+    match data.get(at) {
+        Some(&byte) => Ok(byte),
+        None => malformed("unexpected end of data"),
+    }
+}
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16, FontError> {
+    Ok(u16::from_be_bytes([
+        read_u8(data, at)?,
+        read_u8(data, at + 1)?,
+    ]))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Result<u32, FontError> {
+    Ok(u32::from_be_bytes([
+        read_u8(data, at)?,
+        read_u8(data, at + 1)?,
+        read_u8(data, at + 2)?,
+        read_u8(data, at + 3)?,
+    ]))
+}
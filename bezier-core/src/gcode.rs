@@ -0,0 +1,146 @@
+// G-code export of biarc chains
+//
+// Every leaf arc becomes a single G2 / G3 move with I / J center offsets.
+// Arcs flatter than `max_radius` (straight parts of the curve) are written as G1.
+
+use std::fmt::Write;
+
+use crate::biarc::ArcData;
+use crate::point::Vec2;
+use crate::svg::format_num;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    Millimeters,
+    Inches,
+}
+
+#[derive(Debug, Clone)]
+pub struct GcodeOptions {
+    pub units: Units,
+    /// Machine units per drawing unit
+    pub scale: f64,
+    /// Feed rate of cutting moves, in units per minute
+    pub feed_rate: f64,
+    /// Feed rate of the plunge
+    pub plunge_rate: f64,
+    /// Z height of rapid moves between chains
+    pub safe_z: f64,
+    /// Z height while cutting
+    pub cut_z: f64,
+    /// Digits after the decimal point
+    pub precision: usize,
+    /// Mirror y, for y-down drawings such as imported SVG or glyph outlines;
+    /// the editor's world is y-up like the machine and exports with it off
+    pub flip_y: bool,
+    /// Offset to the left of the travel direction (negative: right), in drawing units
+    pub offset: f64,
+    /// Radius above which an arc is written as a straight move, in drawing units
+    pub max_radius: f64,
+}
+
+impl Default for GcodeOptions {
+    fn default() -> Self {
+        GcodeOptions {
+            units: Units::Millimeters,
+            scale: 1.0,
+            feed_rate: 600.0,
+            plunge_rate: 100.0,
+            safe_z: 5.0,
+            cut_z: -1.0,
+            precision: 3,
+            flip_y: true,
+            offset: 0.0,
+            max_radius: 1e5,
+        }
+    }
+}
+
+/// Offset arcs of a G1-continuous chain; the joints stay tangent continuous.
+/// Arcs whose radius would collapse are dropped.
+pub fn offset_chain(arcs: &[ArcData<f64>], distance: f64) -> Vec<ArcData<f64>> {
+    arcs.iter().filter_map(|arc| arc.offset(distance)).collect()
+}
+
+/// G-code program cutting every chain: rapid to its start, plunge, cut, retract.
+pub fn export_gcode(chains: &[Vec<ArcData<f64>>], options: &GcodeOptions) -> String {
+    let num = |v: f64| format_num(v, options.precision);
+    let map = |p: &Vec2<f64>| {
+        let y = if options.flip_y { -p.y } else { p.y };
+        Vec2::new(p.x * options.scale, y * options.scale)
+    };
+    // gaps left by dropped offset arcs are closed with a straight move
+    let gap = 10f64.powi(-(options.precision as i32));
+
+    let mut gcode = String::new();
+    let (units, unit_name) = match options.units {
+        Units::Millimeters => ("G21", "millimeters"),
+        Units::Inches => ("G20", "inches"),
+    };
+    writeln!(gcode, "{} ; {}", units, unit_name).unwrap();
+    writeln!(gcode, "G90 ; absolute coordinates").unwrap();
+    writeln!(gcode, "G17 ; xy plane").unwrap();
+    writeln!(gcode, "G0 Z{}", num(options.safe_z)).unwrap();
+
+    for chain in chains.iter() {
+        let chain = if options.offset != 0.0 {
+            offset_chain(chain, options.offset)
+        } else {
+            chain.clone()
+        };
+        let first = match chain.first() {
+            Some(arc) => arc,
+            None => continue,
+        };
+
+        let start = map(&first.start_point());
+        writeln!(gcode, "G0 X{} Y{}", num(start.x), num(start.y)).unwrap();
+        writeln!(
+            gcode,
+            "G1 Z{} F{}",
+            num(options.cut_z),
+            num(options.plunge_rate)
+        )
+        .unwrap();
+
+        let mut position = start;
+        for arc in chain.iter() {
+            let from = map(&arc.start_point());
+            let to = map(&arc.end_point());
+            if (from - position).dot(&(from - position)).sqrt() > gap {
+                writeln!(gcode, "G1 X{} Y{}", num(from.x), num(from.y)).unwrap();
+            }
+
+            if arc.radius > options.max_radius {
+                writeln!(
+                    gcode,
+                    "G1 X{} Y{} F{}",
+                    num(to.x),
+                    num(to.y),
+                    num(options.feed_rate)
+                )
+                .unwrap();
+            } else {
+                // mirroring reverses the turning direction
+                let ccw = arc.is_ccw() != options.flip_y;
+                let center = map(&arc.center) - from;
+                writeln!(
+                    gcode,
+                    "{} X{} Y{} I{} J{} F{}",
+                    if ccw { "G3" } else { "G2" },
+                    num(to.x),
+                    num(to.y),
+                    num(center.x),
+                    num(center.y),
+                    num(options.feed_rate)
+                )
+                .unwrap();
+            }
+            position = to;
+        }
+        writeln!(gcode, "G0 Z{}", num(options.safe_z)).unwrap();
+    }
+
+    writeln!(gcode, "M2 ; end of program").unwrap();
+    gcode
+}
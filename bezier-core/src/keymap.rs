@@ -0,0 +1,332 @@
+// Keyboard shortcuts shared by the front-ends
+//
+// Keys are named as winit / iced name their key codes ("A", "F1", "PageUp", "Minus"),
+// with the digit row as "1" to "0". A keymap file has one binding per line:
+//
+//     # comment
+//     Ctrl+Shift+Z = redo
+//     R = none
+//
+// Bindings of a file are applied over the default keymap; `none` removes one.
+
+use std::fmt;
+use std::path::Path;
+
+/// What a shortcut does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Initialize,
+    ToggleDotted,
+    ToggleMesh,
+    ToggleCircles,
+    ToggleJointCircles,
+    ToggleTangents,
+    /// Split each cubic `2^n` times
+    SetSplit(usize),
+    SplitUp,
+    SplitDown,
+    DepthUp,
+    DepthDown,
+    /// Show or hide the boxes of every depth
+    ToggleBoxes,
+    Undo,
+    Redo,
+    ZoomIn,
+    ZoomOut,
+    FitView,
+    SelectAll,
+    Delete,
+    Duplicate,
+    Raise,
+    Lower,
+    ToggleAnimation,
+    NextTheme,
+    ToggleHelp,
+}
+
+const ACTIONS: [(Action, &str, &str); 29] = [
+    (
+        Action::Initialize,
+        "initialize",
+        "Initialize all control points",
+    ),
+    (Action::ToggleDotted, "dotted", "Make the line dotted"),
+    (Action::ToggleMesh, "mesh", "Draw control mesh"),
+    (
+        Action::ToggleCircles,
+        "circles",
+        "Draw full control circles",
+    ),
+    (
+        Action::ToggleJointCircles,
+        "joint-circles",
+        "Draw joint circles",
+    ),
+    (
+        Action::ToggleTangents,
+        "tangents",
+        "Draw tangents at biarc ends",
+    ),
+    (Action::SetSplit(1), "split-1", "Draw 2 biarcs"),
+    (Action::SetSplit(2), "split-2", "Draw 4 biarcs"),
+    (Action::SetSplit(3), "split-3", "Draw 8 biarcs"),
+    (Action::SetSplit(4), "split-4", "Draw 16 biarcs"),
+    (Action::SetSplit(5), "split-5", "Draw 32 biarcs"),
+    (Action::SplitUp, "split-up", "Split once more"),
+    (Action::SplitDown, "split-down", "Split once less"),
+    (Action::DepthUp, "depth-up", "Show one more level of boxes"),
+    (
+        Action::DepthDown,
+        "depth-down",
+        "Show one less level of boxes",
+    ),
+    (Action::ToggleBoxes, "boxes", "Show or hide all boxes"),
+    (Action::Undo, "undo", "Undo"),
+    (Action::Redo, "redo", "Redo"),
+    (Action::ZoomIn, "zoom-in", "Zoom in"),
+    (Action::ZoomOut, "zoom-out", "Zoom out"),
+    (Action::FitView, "fit-view", "Fit the view to the curves"),
+    (Action::SelectAll, "select-all", "Select all curves"),
+    (Action::Delete, "delete", "Delete the selected curves"),
+    (
+        Action::Duplicate,
+        "duplicate",
+        "Duplicate the selected curves",
+    ),
+    (Action::Raise, "raise", "Raise the selected curves"),
+    (Action::Lower, "lower", "Lower the selected curves"),
+    (
+        Action::ToggleAnimation,
+        "animate",
+        "Play or pause the construction",
+    ),
+    (Action::NextTheme, "next-theme", "Switch to the next theme"),
+    (Action::ToggleHelp, "help", "Show or hide this help"),
+];
+
+const DEFAULT_KEYMAP: &str = "\
+I = initialize
+L = dotted
+C = mesh
+R = circles
+J = joint-circles
+T = tangents
+1 = split-1
+2 = split-2
+3 = split-3
+4 = split-4
+S = split-up
+Shift+S = split-down
+D = depth-up
+Shift+D = depth-down
+B = boxes
+Ctrl+Z = undo
+Ctrl+Shift+Z = redo
+Ctrl+Y = redo
+Plus = zoom-in
+Equals = zoom-in
+Shift+Equals = zoom-in
+Minus = zoom-out
+F = fit-view
+Ctrl+A = select-all
+Delete = delete
+Ctrl+D = duplicate
+PageUp = raise
+PageDown = lower
+Space = animate
+N = next-theme
+H = help
+F1 = help
+";
+
+impl Action {
+    /// Name in keymap files
+    pub fn name(self) -> &'static str {
+        ACTIONS.iter().find(|a| a.0 == self).map_or("", |a| a.1)
+    }
+
+    /// Line of the help overlay
+    pub fn description(self) -> &'static str {
+        ACTIONS.iter().find(|a| a.0 == self).map_or("", |a| a.2)
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|a| a.1 == name).map(|a| a.0)
+    }
+}
+
+/// A key with the modifiers held, Ctrl standing for Cmd on macOS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyChord {
+    /// Chord of a key code name as printed by `Debug` in winit and iced
+    pub fn new(key: &str, ctrl: bool, shift: bool, alt: bool) -> KeyChord {
+        // digit row keys are `Key1` to `Key0`
+        let key = match key.strip_prefix("Key") {
+            Some(digit) if digit.len() == 1 => digit,
+            _ => key,
+        };
+        KeyChord {
+            key: key.to_ascii_uppercase(),
+            ctrl,
+            shift,
+            alt,
+        }
+    }
+
+    /// Parse `Ctrl+Shift+Z`; modifiers and key are case insensitive
+    pub fn parse(text: &str) -> Option<KeyChord> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        // `Ctrl++` and `+` name the plus key
+        if text.trim().ends_with("++") || text.trim() == "+" {
+            parts.retain(|part| !part.is_empty());
+            parts.push("Plus");
+        }
+        let key = parts.pop().filter(|key| !key.is_empty())?;
+        let mut chord = KeyChord::new(key, false, false, false);
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "cmd" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                _ => return None,
+            }
+        }
+        Some(chord)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[derive(Debug)]
+pub enum KeymapError {
+    Io(std::io::Error),
+    /// Malformed binding at a 1-based line
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeymapError::Io(err) => write!(f, "{}", err),
+            KeymapError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+impl From<std::io::Error> for KeymapError {
+    fn from(err: std::io::Error) -> Self {
+        KeymapError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(KeyChord, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Keymap {
+            bindings: Vec::new(),
+        };
+        keymap.apply(DEFAULT_KEYMAP).unwrap();
+        keymap
+    }
+}
+
+impl Keymap {
+    /// Default keymap with the bindings of `text` applied over it
+    pub fn parse(text: &str) -> Result<Keymap, KeymapError> {
+        let mut keymap = Keymap::default();
+        keymap.apply(text)?;
+        Ok(keymap)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Keymap, KeymapError> {
+        Keymap::parse(&std::fs::read_to_string(path)?)
+    }
+
+    fn apply(&mut self, text: &str) -> Result<(), KeymapError> {
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: String| KeymapError::Parse {
+                line: i + 1,
+                message,
+            };
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (keys, action) = match line.rfind('=') {
+                Some(at) => (line[..at].trim(), line[at + 1..].trim()),
+                None => return Err(error(format!("expected `key = action` in `{}`", line))),
+            };
+            let chord =
+                KeyChord::parse(keys).ok_or_else(|| error(format!("unknown key `{}`", keys)))?;
+            self.bindings.retain(|(bound, _)| *bound != chord);
+            if action != "none" {
+                let action = Action::from_name(action)
+                    .ok_or_else(|| error(format!("unknown action `{}`", action)))?;
+                self.bindings.push((chord, action));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn action(&self, chord: &KeyChord) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| bound == chord)
+            .map(|(_, action)| *action)
+    }
+
+    /// Lines of the help overlay: the keys of each bound action, in the order of `Action`
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        self.help_for(|_| true)
+    }
+
+    /// Lines of the help overlay for the actions a front-end handles
+    pub fn help_for<F: Fn(Action) -> bool>(&self, handles: F) -> Vec<(String, &'static str)> {
+        ACTIONS
+            .iter()
+            .filter(|&&(action, _, _)| handles(action))
+            .filter_map(|&(action, _, description)| {
+                let keys: Vec<String> = self
+                    .bindings
+                    .iter()
+                    .filter(|(_, bound)| *bound == action)
+                    .map(|(chord, _)| chord.to_string())
+                    .collect();
+                if keys.is_empty() {
+                    None
+                } else {
+                    Some((keys.join(", "), description))
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,27 @@
+//! Geometry kernel shared by the Bezier / biarc front-ends.
+//!
+//! Every type is generic over [`Scalar`](scalar::Scalar), so the whole
+//! biarc / AABB pipeline can run in `f64` while the renderer stays in `f32`.
+
+pub mod bezier;
+pub mod biarc;
+pub mod dxf;
+pub mod font;
+pub mod gcode;
+pub mod keymap;
+pub mod overlay;
+pub mod path;
+pub mod png;
+pub mod point;
+pub mod predicates;
+#[cfg(feature = "project")]
+pub mod project;
+pub mod raster;
+pub mod samples;
+pub mod scalar;
+pub mod snap;
+pub mod svg;
+pub mod svg_path;
+pub mod theme;
+pub mod tree;
+pub mod util;
@@ -0,0 +1,35 @@
+// Construction overlay of the biarcs
+//
+// Each element can be shown on its own: the full circles of both arcs, the joint
+// circle the joint is picked on, and the curve tangents at the ends of each biarc.
+
+use crate::keymap::Action;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Overlay {
+    /// Full circle of every arc
+    pub arc_circles: bool,
+    /// Joint circle of every biarc
+    pub joint_circles: bool,
+    /// Tangent lines at both ends of every biarc
+    pub tangents: bool,
+}
+
+impl Overlay {
+    /// Whether the constructions are needed at all
+    pub fn needs_constructions(&self) -> bool {
+        self.joint_circles || self.tangents
+    }
+
+    /// Flip the element `action` toggles; false for any other action
+    pub fn toggle(&mut self, action: Action) -> bool {
+        let shown = match action {
+            Action::ToggleCircles => &mut self.arc_circles,
+            Action::ToggleJointCircles => &mut self.joint_circles,
+            Action::ToggleTangents => &mut self.tangents,
+            _ => return false,
+        };
+        *shown = !*shown;
+        true
+    }
+}
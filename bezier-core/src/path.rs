@@ -0,0 +1,319 @@
+// Composite curve: cubic segments joined end to start
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::bezier::{BezierCurve, BiarcConstruction, JointStrategy};
+use crate::biarc::*;
+use crate::point::Vec2;
+use crate::predicates::Tolerance;
+use crate::scalar::Scalar;
+use crate::tree::{complete_depth, Tree};
+
+/// Cubic segments where each segment starts at the end of the previous one.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BezierPath<S> {
+    pub segments: Vec<BezierCurve<S>>,
+    /// The last segment ends at the start of the first
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub closed: bool,
+}
+
+impl<S: Scalar> BezierPath<S> {
+    pub fn from_curve(curve: BezierCurve<S>) -> Self {
+        BezierPath {
+            segments: vec![curve],
+            closed: false,
+        }
+    }
+
+    pub fn start(&self) -> Option<Vec2<S>> {
+        self.segments.first().map(|segment| segment.control_pts[0])
+    }
+
+    pub fn end(&self) -> Option<Vec2<S>> {
+        self.segments.last().map(|segment| segment.control_pts[3])
+    }
+
+    /// Move control point `index` (0-3) of `segment`.
+    /// A joint is shared by two segments, so both are moved.
+    pub fn set_point(&mut self, segment: usize, index: usize, point: Vec2<S>) {
+        let n = self.segments.len();
+        self.segments[segment].control_pts[index] = point;
+
+        if index == 0 && (segment > 0 || self.closed) {
+            let prev = (segment + n - 1) % n;
+            self.segments[prev].control_pts[3] = point;
+        } else if index == 3 && (segment + 1 < n || self.closed) {
+            let next = (segment + 1) % n;
+            self.segments[next].control_pts[0] = point;
+        }
+    }
+}
+
+/// Mirror every control point across the horizontal middle of their common bounding box.
+/// Converts between y-down (canvas, SVG) and y-up coordinates while keeping the y range.
+pub fn mirror_y<S: Scalar>(paths: &mut [BezierPath<S>]) {
+    let points = || {
+        paths
+            .iter()
+            .flat_map(|path| path.segments.iter())
+            .flat_map(|segment| segment.control_pts.iter())
+    };
+    let (min, max) = match points().next() {
+        Some(first) => points().fold((first.y, first.y), |(min, max), p| {
+            (min.min(p.y), max.max(p.y))
+        }),
+        None => return,
+    };
+    for path in paths.iter_mut() {
+        for segment in path.segments.iter_mut() {
+            for p in segment.control_pts.iter_mut() {
+                p.y = min + max - p.y;
+            }
+        }
+    }
+}
+
+impl<S: Scalar> Default for BezierPath<S> {
+    fn default() -> Self {
+        BezierPath::from_curve(BezierCurve::default())
+    }
+}
+
+/// Distance of the curve samples from the fitted arcs
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Deviation<S> {
+    pub max: S,
+    pub mean: S,
+    pub rms: S,
+    pub samples: usize,
+}
+
+impl<S: Scalar> Deviation<S> {
+    /// Statistics over the samples of both
+    pub fn merge(&self, other: &Deviation<S>) -> Deviation<S> {
+        let samples = self.samples + other.samples;
+        if samples == 0 {
+            return Deviation::default();
+        }
+        let (n0, n1) = (S::from_usize(self.samples), S::from_usize(other.samples));
+        let n = S::from_usize(samples);
+        Deviation {
+            max: self.max.max(other.max),
+            mean: (self.mean * n0 + other.mean * n1) / n,
+            rms: ((self.rms * self.rms * n0 + other.rms * other.rms * n1) / n).sqrt(),
+            samples,
+        }
+    }
+}
+
+/// Boxes of one tree level
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BoxLevel<S> {
+    /// Levels from the root, which is 0
+    pub depth: usize,
+    pub count: usize,
+    /// Sum of the rounded box areas
+    pub area: S,
+}
+
+/// Levels of both, summed where the depths match, by depth
+pub fn merge_box_levels<S: Scalar>(a: &[BoxLevel<S>], b: &[BoxLevel<S>]) -> Vec<BoxLevel<S>> {
+    let mut levels = a.to_vec();
+    for level in b.iter() {
+        match levels.iter_mut().find(|l| l.depth == level.depth) {
+            Some(merged) => {
+                merged.count += level.count;
+                merged.area += level.area;
+            }
+            None => levels.push(*level),
+        }
+    }
+    levels.sort_by_key(|level| level.depth);
+    levels
+}
+
+/// Display settings of a single path in the SVG and PNG exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathStyle {
+    pub is_dotted: bool,
+    /// Control mesh, as the editor's mesh toggle
+    pub is_meshed: bool,
+    /// Boxes of this many lowest tree levels
+    pub aabb_depth: usize,
+}
+
+/// Path with the biarc tree of every segment
+#[derive(Debug)]
+pub struct FittedPath<S> {
+    pub path: BezierPath<S>,
+    pub arcs: Vec<ArcTree<S>>,
+    /// Used by `rebuild`
+    pub joint: JointStrategy,
+}
+
+impl<S: Scalar + 'static> FittedPath<S> {
+    pub fn new(
+        path: BezierPath<S>,
+        split_num: usize,
+        use_bezier_aabb: bool,
+        tol: &Tolerance<S>,
+    ) -> Self {
+        FittedPath::with_joint(
+            path,
+            split_num,
+            use_bezier_aabb,
+            JointStrategy::default(),
+            tol,
+        )
+    }
+
+    /// Fitted with biarc joints placed by `joint`
+    pub fn with_joint(
+        path: BezierPath<S>,
+        split_num: usize,
+        use_bezier_aabb: bool,
+        joint: JointStrategy,
+        tol: &Tolerance<S>,
+    ) -> Self {
+        let mut fitted = FittedPath {
+            path,
+            arcs: Vec::new(),
+            joint,
+        };
+        fitted.rebuild(split_num, use_bezier_aabb, tol);
+        fitted
+    }
+
+    pub fn rebuild(&mut self, split_num: usize, use_bezier_aabb: bool, tol: &Tolerance<S>) {
+        let depth = split_num + 1;
+        self.arcs.truncate(self.path.segments.len());
+        while self.arcs.len() < self.path.segments.len() {
+            self.arcs.push(Rc::new(RefCell::new(Tree::new_complete(
+                depth,
+                ArcBox::arc_builder(depth),
+            ))));
+        }
+
+        for (segment, arcs) in self.path.segments.iter().zip(self.arcs.iter()) {
+            segment.build_biarc(arcs.clone(), split_num, use_bezier_aabb, self.joint, tol);
+        }
+    }
+
+    /// Boxes and rounding radii of the `aabb_depth` lowest tree levels
+    /// (the editor's AABB depth), deepest first.
+    pub fn boxes(&self, aabb_depth: usize) -> Vec<(AABB<S>, S)> {
+        let mut boxes = Vec::new();
+        for arcs in self.arcs.iter() {
+            let tree = arcs.borrow();
+            if tree.is_empty() {
+                continue;
+            }
+            let tree_depth = complete_depth(tree.len() - 1);
+            for i in (0..tree.len()).rev() {
+                if complete_depth(i) + aabb_depth > tree_depth {
+                    let node = tree.get(i).unwrap();
+                    boxes.push((node.aabb.clone(), node.radius));
+                }
+            }
+        }
+        boxes
+    }
+
+    /// Box count and area of each of the `aabb_depth` lowest tree levels, by depth
+    pub fn box_levels(&self, aabb_depth: usize) -> Vec<BoxLevel<S>> {
+        let mut levels: Vec<BoxLevel<S>> = Vec::new();
+        for arcs in self.arcs.iter() {
+            let tree = arcs.borrow();
+            if tree.is_empty() {
+                continue;
+            }
+            let tree_depth = complete_depth(tree.len() - 1);
+            let mut segment = Vec::new();
+            for depth in (tree_depth + 1).saturating_sub(aabb_depth)..=tree_depth {
+                let first = (1usize << depth) - 1;
+                let nodes = (first..(2 * first + 1).min(tree.len())).map(|i| tree.get(i).unwrap());
+                let mut level = BoxLevel {
+                    depth,
+                    ..BoxLevel::default()
+                };
+                for node in nodes {
+                    level.count += 1;
+                    level.area += node.aabb.rounded_area(node.radius);
+                }
+                segment.push(level);
+            }
+            levels = merge_box_levels(&levels, &segment);
+        }
+        levels
+    }
+
+    /// Deviation of `samples` uniform-t points per segment
+    /// from the nearest leaf arc of that segment.
+    pub fn deviation(&self, samples: usize) -> Deviation<S> {
+        let mut deviation: Deviation<S> = Deviation::default();
+        let mut sum = S::ZERO;
+        let mut sum_sq = S::ZERO;
+        let mut point = Vec2::zero();
+        for (index, segment) in self.path.segments.iter().enumerate() {
+            let leaves = self.segment_leaves(index);
+            if leaves.is_empty() {
+                continue;
+            }
+            for i in 0..=samples {
+                segment
+                    .cubic_curve_to(&mut point, S::from_usize(i) / S::from_usize(samples.max(1)));
+                let d = nearest_distance(&leaves, &point);
+                deviation.max = deviation.max.max(d);
+                sum += d;
+                sum_sq += d * d;
+                deviation.samples += 1;
+            }
+        }
+        if deviation.samples > 0 {
+            let n = S::from_usize(deviation.samples);
+            deviation.mean = sum / n;
+            deviation.rms = (sum_sq / n).sqrt();
+        }
+        deviation
+    }
+
+    /// Leaf arcs of one segment, from its start to its end
+    pub fn segment_leaves(&self, segment: usize) -> Vec<ArcData<S>> {
+        let tree = self.arcs[segment].borrow();
+        (0..tree.len())
+            .filter_map(|i| tree.get(i).unwrap().arc.clone())
+            .collect()
+    }
+
+    /// Leaf arcs of all segments, from start to end
+    pub fn leaves(&self) -> Vec<ArcData<S>> {
+        (0..self.arcs.len())
+            .flat_map(|segment| self.segment_leaves(segment))
+            .collect()
+    }
+
+    /// Construction of every biarc of the trees, from start to end
+    pub fn constructions(&self, tol: &Tolerance<S>) -> Vec<BiarcConstruction<S>> {
+        let mut constructions = Vec::new();
+        for (segment, arcs) in self.path.segments.iter().zip(self.arcs.iter()) {
+            // two leaves per biarc
+            let biarc_n = (arcs.borrow().len() + 1) / 4;
+            let delta = S::ONE / S::from_usize(biarc_n.max(1));
+            for i in 0..biarc_n {
+                let t = delta * S::from_usize(i);
+                constructions.push(segment.biarc_construction(t, t + delta, self.joint, tol));
+            }
+        }
+        constructions
+    }
+}
+
+/// Distance from `p` to the nearest of `arcs` (which must not be empty)
+pub fn nearest_distance<S: Scalar>(arcs: &[ArcData<S>], p: &Vec2<S>) -> S {
+    arcs.iter()
+        .map(|arc| arc.distance(p))
+        .fold(arcs[0].distance(p), S::min)
+}
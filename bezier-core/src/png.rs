@@ -0,0 +1,499 @@
+// Minimal PNG codec for 8-bit RGBA images
+//
+// The encoder writes one IDAT with fixed-Huffman deflate, coding byte runs as
+// distance-1 matches; with the Sub filter flat areas become runs of zeros.
+// The decoder reads any non-interlaced 8-bit RGBA image (stored, fixed and
+// dynamic deflate blocks, all five filters), which is enough for golden images.
+
+use std::fmt;
+
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PngError(pub String);
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid png: {}", self.0)
+    }
+}
+
+impl std::error::Error for PngError {}
+
+fn error<T>(message: &str) -> Result<T, PngError> {
+    Err(PngError(String::from(message)))
+}
+
+/// Decoded image, rows of RGBA bytes from the top
+#[derive(Debug, Clone, PartialEq)]
+pub struct PngImage {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+    /// Pixels per inch from the pHYs chunk
+    pub dpi: Option<f64>,
+}
+
+/// Encode `data` (rows of RGBA bytes from the top) as PNG.
+pub fn encode_png(width: usize, height: usize, data: &[u8], dpi: f64) -> Vec<u8> {
+    assert_eq!(data.len(), width * height * 4);
+    let mut png = SIGNATURE.to_vec();
+
+    let mut ihdr = Vec::new();
+    ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+    // 8 bit, RGBA, deflate, adaptive filters, no interlace
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+    chunk(&mut png, b"IHDR", &ihdr);
+
+    // pixels per meter
+    let ppm = (dpi / 0.0254).round() as u32;
+    let mut phys = Vec::new();
+    phys.extend_from_slice(&ppm.to_be_bytes());
+    phys.extend_from_slice(&ppm.to_be_bytes());
+    phys.push(1);
+    chunk(&mut png, b"pHYs", &phys);
+
+    // every row with the Sub filter
+    let stride = width * 4;
+    let mut filtered = Vec::with_capacity((stride + 1) * height);
+    for row in data.chunks(stride.max(1)).take(height) {
+        filtered.push(1);
+        for (i, &byte) in row.iter().enumerate() {
+            let left = if i >= 4 { row[i - 4] } else { 0 };
+            filtered.push(byte.wrapping_sub(left));
+        }
+    }
+    chunk(&mut png, b"IDAT", &zlib_compress(&filtered));
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Decode an 8-bit RGBA PNG.
+pub fn decode_png(png: &[u8]) -> Result<PngImage, PngError> {
+    if png.len() < 8 || png[..8] != SIGNATURE {
+        return error("missing signature");
+    }
+
+    let mut pos = 8;
+    let (mut width, mut height, mut dpi) = (0, 0, None);
+    let mut idat = Vec::new();
+    while pos + 8 <= png.len() {
+        let len = read_u32(&png[pos..]) as usize;
+        let kind = &png[pos + 4..pos + 8];
+        if pos + 12 + len > png.len() {
+            return error("truncated chunk");
+        }
+        let body = &png[pos + 8..pos + 8 + len];
+        match kind {
+            b"IHDR" => {
+                if len < 13 {
+                    return error("short IHDR");
+                }
+                width = read_u32(body) as usize;
+                height = read_u32(&body[4..]) as usize;
+                if body[8..13] != [8, 6, 0, 0, 0] {
+                    return error("only 8-bit non-interlaced RGBA is supported");
+                }
+            }
+            b"pHYs" if len >= 9 && body[8] == 1 => {
+                dpi = Some(read_u32(body) as f64 * 0.0254);
+            }
+            b"IDAT" => idat.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+    if width == 0 || height == 0 {
+        return error("missing IHDR");
+    }
+
+    if idat.len() < 2 {
+        return error("missing IDAT");
+    }
+    let filtered = inflate(&idat[2..])?;
+    let stride = width * 4;
+    if filtered.len() < (stride + 1) * height {
+        return error("short image data");
+    }
+
+    let mut data = vec![0u8; stride * height];
+    for y in 0..height {
+        let filter = filtered[y * (stride + 1)];
+        let src = &filtered[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        for i in 0..stride {
+            let a = if i >= 4 { data[y * stride + i - 4] } else { 0 };
+            let b = if y > 0 { data[(y - 1) * stride + i] } else { 0 };
+            let c = if y > 0 && i >= 4 {
+                data[(y - 1) * stride + i - 4]
+            } else {
+                0
+            };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return error("unknown filter"),
+            };
+            data[y * stride + i] = src[i].wrapping_add(predicted);
+        }
+    }
+
+    Ok(PngImage {
+        width,
+        height,
+        data,
+        dpi,
+    })
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(body);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// deflate length codes 257..285: base length and extra bits
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+// distance codes 0..29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+struct BitWriter {
+    out: Vec<u8>,
+    bits: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // Huffman codes go most significant bit first
+    fn write_code(&mut self, code: u32, count: u32) {
+        let mut reversed = 0;
+        for i in 0..count {
+            reversed |= ((code >> i) & 1) << (count - 1 - i);
+        }
+        self.write(reversed, count);
+    }
+
+    fn literal(&mut self, symbol: u32) {
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.bits as u8);
+        }
+        self.out
+    }
+}
+
+fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter {
+        out: vec![0x78, 0x01],
+        bits: 0,
+        count: 0,
+    };
+    // single final block, fixed Huffman codes
+    writer.write(1, 1);
+    writer.write(1, 2);
+
+    let mut i = 0;
+    while i < data.len() {
+        writer.literal(data[i] as u32);
+        let mut run = 0;
+        while i + 1 + run < data.len() && data[i + 1 + run] == data[i] && run < 258 {
+            run += 1;
+        }
+        if run >= 3 {
+            // repeat the previous byte: distance 1 is code 0 without extra bits
+            let code = LENGTH_BASE
+                .iter()
+                .rposition(|&base| base as usize <= run)
+                .unwrap();
+            writer.literal(257 + code as u32);
+            writer.write(
+                (run - LENGTH_BASE[code] as usize) as u32,
+                LENGTH_EXTRA[code] as u32,
+            );
+            writer.write_code(0, 5);
+            i += 1 + run;
+        } else {
+            i += 1;
+        }
+    }
+    writer.literal(256);
+
+    let mut out = writer.finish();
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn bits(&mut self, count: u32) -> Result<u32, PngError> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = match self.data.get(self.pos) {
+                Some(byte) => *byte,
+                None => return error("truncated deflate stream"),
+            };
+            value |= (((byte >> self.bit) & 1) as u32) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn align(&mut self) {
+        if self.bit > 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+    }
+}
+
+/// Canonical Huffman code: symbol counts per length and symbols by code
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        for len in 1..16 {
+            offsets[len] = offsets[len - 1] + counts[len - 1];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len > 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, PngError> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        error("invalid Huffman code")
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    for (symbol, len) in lengths.iter_mut().enumerate() {
+        *len = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), PngError> {
+    const ORDER: [usize; 19] = [
+        16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+    ];
+    let nlen = reader.bits(5)? as usize + 257;
+    let ndist = reader.bits(5)? as usize + 1;
+    let ncode = reader.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &symbol in ORDER.iter().take(ncode) {
+        code_lengths[symbol] = reader.bits(3)? as u8;
+    }
+    let code = Huffman::new(&code_lengths);
+
+    let mut lengths = Vec::with_capacity(nlen + ndist);
+    while lengths.len() < nlen + ndist {
+        let symbol = code.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => match lengths.last() {
+                Some(&last) => (last, 3 + reader.bits(2)?),
+                None => return error("repeat without a length"),
+            },
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?),
+        };
+        for _ in 0..repeat {
+            lengths.push(value);
+        }
+    }
+    if lengths.len() > nlen + ndist {
+        return error("too many code lengths");
+    }
+    Ok((
+        Huffman::new(&lengths[..nlen]),
+        Huffman::new(&lengths[nlen..]),
+    ))
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>, PngError> {
+    let mut reader = BitReader {
+        data,
+        pos: 0,
+        bit: 0,
+    };
+    let mut out: Vec<u8> = Vec::new();
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let pos = reader.pos;
+                if pos + 4 > data.len() {
+                    return error("truncated stored block");
+                }
+                let len = u16::from_le_bytes([data[pos], data[pos + 1]]) as usize;
+                if pos + 4 + len > data.len() {
+                    return error("truncated stored block");
+                }
+                out.extend_from_slice(&data[pos + 4..pos + 4 + len]);
+                reader.pos = pos + 4 + len;
+            }
+            kind @ 1..=2 => {
+                let (lit, dist) = if kind == 1 {
+                    fixed_codes()
+                } else {
+                    dynamic_codes(&mut reader)?
+                };
+                loop {
+                    let symbol = lit.decode(&mut reader)? as usize;
+                    if symbol < 256 {
+                        out.push(symbol as u8);
+                        continue;
+                    }
+                    if symbol == 256 {
+                        break;
+                    }
+                    let code = symbol - 257;
+                    if code >= LENGTH_BASE.len() {
+                        return error("invalid length code");
+                    }
+                    let len = LENGTH_BASE[code] as usize
+                        + reader.bits(LENGTH_EXTRA[code] as u32)? as usize;
+                    let code = dist.decode(&mut reader)? as usize;
+                    if code >= DIST_BASE.len() {
+                        return error("invalid distance code");
+                    }
+                    let distance =
+                        DIST_BASE[code] as usize + reader.bits(DIST_EXTRA[code] as u32)? as usize;
+                    if distance > out.len() {
+                        return error("distance before the start");
+                    }
+                    for _ in 0..len {
+                        out.push(out[out.len() - distance]);
+                    }
+                }
+            }
+            _ => return error("invalid block type"),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
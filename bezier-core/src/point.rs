@@ -0,0 +1,103 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::scalar::Scalar;
+
+/// 2D point / vector, generic over the kernel scalar.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec2<S> {
+    pub x: S,
+    pub y: S,
+}
+
+impl<S: Scalar> Vec2<S> {
+    pub fn new(x: S, y: S) -> Self {
+        Vec2 { x, y }
+    }
+
+    pub fn zero() -> Self {
+        Vec2 {
+            x: S::ZERO,
+            y: S::ZERO,
+        }
+    }
+
+    /// Unit vector of the given angle aligned to +x-axis
+    pub fn from_angle(angle: S) -> Self {
+        Vec2 {
+            x: angle.cos(),
+            y: angle.sin(),
+        }
+    }
+
+    pub fn dot(&self, other: &Vec2<S>) -> S {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// z-component of the 3D cross product
+    pub fn cross(&self, other: &Vec2<S>) -> S {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Rotate by +90 degree
+    pub fn perp(&self) -> Self {
+        Vec2 {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    pub fn lerp(&self, other: &Vec2<S>, t: S) -> Self {
+        Vec2 {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+
+    pub fn cast<T: Scalar>(&self) -> Vec2<T> {
+        Vec2 {
+            x: self.x.cast(),
+            y: self.y.cast(),
+        }
+    }
+}
+
+impl<S: Scalar> Add for Vec2<S> {
+    type Output = Vec2<S>;
+    fn add(self, other: Vec2<S>) -> Vec2<S> {
+        Vec2 {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<S: Scalar> Sub for Vec2<S> {
+    type Output = Vec2<S>;
+    fn sub(self, other: Vec2<S>) -> Vec2<S> {
+        Vec2 {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl<S: Scalar> Mul<S> for Vec2<S> {
+    type Output = Vec2<S>;
+    fn mul(self, scale: S) -> Vec2<S> {
+        Vec2 {
+            x: self.x * scale,
+            y: self.y * scale,
+        }
+    }
+}
+
+impl<S: Scalar> Neg for Vec2<S> {
+    type Output = Vec2<S>;
+    fn neg(self) -> Vec2<S> {
+        Vec2 {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// Robust geometric predicates
+//
+// `orient2d` follows Shewchuk's adaptive scheme: a floating-point filter first,
+// and an exact expansion-arithmetic evaluation only when the filter is not conclusive.
+// https://www.cs.cmu.edu/~quake/robust.html
+
+use crate::point::Vec2;
+use crate::scalar::Scalar;
+
+/// Tolerances used to classify degenerate configurations in `build_biarc`.
+/// Exactly parallel and collinear cases are found by the exact predicates even
+/// with zero tolerances.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance<S> {
+    /// Sine of the angle below which two directions are treated as parallel
+    pub parallel: S,
+    /// Distances below this are treated as zero
+    pub distance: S,
+    /// Radius of the arc used in place of a straight segment
+    pub max_radius: S,
+}
+
+impl<S: Scalar> Default for Tolerance<S> {
+    fn default() -> Self {
+        let eps = S::EPSILON.sqrt();
+        Tolerance {
+            parallel: eps,
+            distance: eps,
+            max_radius: S::from_f64(1e6),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    Collinear,
+}
+
+/// Result of intersecting the lines `p0 + s * v0` and `p1 + t * v1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineIntersection<S> {
+    /// Single intersection point, with the line parameters `s` and `t`
+    Point(Vec2<S>, S, S),
+    /// Parallel lines without common points
+    Parallel,
+    /// Both lines are the same
+    Coincident,
+}
+
+impl<S: Scalar> LineIntersection<S> {
+    pub fn point(&self) -> Option<Vec2<S>> {
+        match self {
+            LineIntersection::Point(point, _, _) => Some(*point),
+            _ => None,
+        }
+    }
+}
+
+/// Intersect the line `p0 + s * v0` with `p1 + t * v1`.
+/// # Arguments
+/// * `p0`, `p1`: Initial points
+/// * `v0`, `v1`: Vector; No needs to be a unit vector.
+pub fn line_intersection<S: Scalar>(
+    p0: &Vec2<S>,
+    v0: &Vec2<S>,
+    p1: &Vec2<S>,
+    v1: &Vec2<S>,
+    tol: &Tolerance<S>,
+) -> LineIntersection<S> {
+    let len0 = v0.dot(v0).sqrt();
+    let len1 = v1.dot(v1).sqrt();
+    let diff = *p1 - *p0;
+
+    // a zero vector does not define a direction
+    if len0 <= tol.distance || len1 <= tol.distance {
+        return LineIntersection::Parallel;
+    }
+
+    // exact sign, so exactly parallel directions are never divided by a rounded non-zero
+    let determinant = S::from_f64(cross_exact(v0, v1));
+    if determinant == S::ZERO || determinant.abs() <= tol.parallel * len0 * len1 {
+        // distance from p1 to the first line
+        let offset = cross_exact(&diff, v0);
+        if offset == 0.0 || S::from_f64(offset).abs() <= tol.distance * len0 {
+            return LineIntersection::Coincident;
+        }
+        return LineIntersection::Parallel;
+    }
+
+    let s = diff.cross(v1) / determinant;
+    let t = diff.cross(v0) / determinant;
+    LineIntersection::Point(*p0 + *v0 * s, s, t)
+}
+
+/// Orientation of the triangle (a, b, c).
+/// The sign is exact for any finite input.
+pub fn orientation<S: Scalar>(a: &Vec2<S>, b: &Vec2<S>, c: &Vec2<S>) -> Orientation {
+    let det = orient2d(a, b, c);
+    if det > 0.0 {
+        Orientation::CounterClockwise
+    } else if det < 0.0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+/// `a.cross(b)` with a correct sign, zero only if `a` and `b` are exactly parallel
+pub fn cross_exact<S: Scalar>(a: &Vec2<S>, b: &Vec2<S>) -> f64 {
+    orient2d(a, b, &Vec2::zero())
+}
+
+/// Twice the signed area of the triangle (a, b, c), positive if counter-clockwise
+/// (in y-up coordinates). Evaluated in `f64`; the sign is always correct.
+pub fn orient2d<S: Scalar>(a: &Vec2<S>, b: &Vec2<S>, c: &Vec2<S>) -> f64 {
+    let (ax, ay) = (a.x.to_f64(), a.y.to_f64());
+    let (bx, by) = (b.x.to_f64(), b.y.to_f64());
+    let (cx, cy) = (c.x.to_f64(), c.y.to_f64());
+
+    let det_left = (ax - cx) * (by - cy);
+    let det_right = (ay - cy) * (bx - cx);
+    let det = det_left - det_right;
+
+    let det_sum = if det_left > 0.0 {
+        if det_right <= 0.0 {
+            return det;
+        }
+        det_left + det_right
+    } else if det_left < 0.0 {
+        if det_right >= 0.0 {
+            return det;
+        }
+        -det_left - det_right
+    } else {
+        return det;
+    };
+
+    // error bound of the floating-point evaluation
+    let err_bound = CCW_ERR_BOUND_A * det_sum;
+    if det >= err_bound || -det >= err_bound {
+        return det;
+    }
+
+    orient2d_exact(ax, ay, bx, by, cx, cy)
+}
+
+const EPS: f64 = f64::EPSILON * 0.5;
+const CCW_ERR_BOUND_A: f64 = (3.0 + 16.0 * EPS) * EPS;
+
+// ax * by - ay * bx + bx * cy - by * cx + cx * ay - cy * ax, without rounding
+fn orient2d_exact(ax: f64, ay: f64, bx: f64, by: f64, cx: f64, cy: f64) -> f64 {
+    let mut expansion: Vec<f64> = Vec::with_capacity(12);
+    for &(p, q, negate) in &[
+        (ax, by, false),
+        (ay, bx, true),
+        (bx, cy, false),
+        (by, cx, true),
+        (cx, ay, false),
+        (cy, ax, true),
+    ] {
+        let (hi, lo) = two_product(p, q);
+        let (hi, lo) = if negate { (-hi, -lo) } else { (hi, lo) };
+        grow_expansion(&mut expansion, lo);
+        grow_expansion(&mut expansion, hi);
+    }
+
+    // components are non-overlapping and increasing in magnitude,
+    // so the largest non-zero one has the sign of the exact sum.
+    expansion
+        .iter()
+        .rev()
+        .find(|&&v| v != 0.0)
+        .map_or(0.0, |&v| v)
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    let b_round = b - b_virtual;
+    let a_round = a - a_virtual;
+    (x, a_round + b_round)
+}
+
+fn split(a: f64) -> (f64, f64) {
+    // 2^27 + 1
+    const SPLITTER: f64 = 134_217_729.0;
+    let c = SPLITTER * a;
+    let a_big = c - a;
+    let a_hi = c - a_big;
+    (a_hi, a - a_hi)
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    let (a_hi, a_lo) = split(a);
+    let (b_hi, b_lo) = split(b);
+    let err1 = x - (a_hi * b_hi);
+    let err2 = err1 - (a_lo * b_hi);
+    let err3 = err2 - (a_hi * b_lo);
+    (x, (a_lo * b_lo) - err3)
+}
+
+// add a single value to a non-overlapping expansion, keeping it exact
+fn grow_expansion(expansion: &mut Vec<f64>, value: f64) {
+    let mut q = value;
+    for component in expansion.iter_mut() {
+        let (sum, err) = two_sum(q, *component);
+        *component = err;
+        q = sum;
+    }
+    expansion.push(q);
+}
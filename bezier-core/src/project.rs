@@ -0,0 +1,209 @@
+// Versioned on-disk project document (JSON)
+//
+// Every field has a default, so files written by older versions load with the
+// missing settings filled in, and unknown fields written by newer versions are ignored.
+// `version` is only bumped for changes that cannot be read that way.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::bezier::BezierCurve;
+use crate::path::{mirror_y, BezierPath};
+pub use crate::util::{rgba8, Rgba};
+
+/// Format version written by this build.
+/// Version 2 stores every curve as a path of cubic segments.
+/// Version 3 stores curves in y-up world units instead of y-down canvas pixels.
+pub const FORMAT_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Project {
+    pub version: u32,
+    /// Y-up world coordinates
+    pub curves: Vec<BezierPath<f64>>,
+    /// Settings of new curves, and of curves without an entry in `curve_display`
+    pub display: Display,
+    /// Settings of each curve, in curve order
+    pub curve_display: Vec<Display>,
+    pub colors: Colors,
+}
+
+/// Display toggles and biarc settings of the editor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Display {
+    pub is_dotted: bool,
+    pub is_meshed: bool,
+    pub use_bezier_aabb: bool,
+    pub num_split: usize,
+    pub aabb_depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Colors {
+    pub left_arc: Rgba,
+    pub right_arc: Rgba,
+}
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    /// Written by a newer, incompatible version
+    UnsupportedVersion(u32),
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Project {
+            version: FORMAT_VERSION,
+            curves: vec![BezierPath::default()],
+            display: Display::default(),
+            curve_display: Vec::new(),
+            colors: Colors::default(),
+        }
+    }
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Display {
+            is_dotted: false,
+            is_meshed: true,
+            use_bezier_aabb: false,
+            num_split: 1,
+            aabb_depth: 1,
+        }
+    }
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Colors {
+            left_arc: rgba8(40, 210, 0, 1.0),
+            right_arc: rgba8(30, 0, 210, 1.0),
+        }
+    }
+}
+
+impl Project {
+    /// Settings of curve `index`
+    pub fn curve_display(&self, index: usize) -> &Display {
+        self.curve_display.get(index).unwrap_or(&self.display)
+    }
+
+    pub fn from_json(json: &str) -> Result<Project, ProjectError> {
+        let file: ProjectFile = serde_json::from_str(json)?;
+        if file.version > FORMAT_VERSION {
+            return Err(ProjectError::UnsupportedVersion(file.version));
+        }
+        Ok(file.upgrade())
+    }
+
+    pub fn to_json(&self) -> Result<String, ProjectError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Project, ProjectError> {
+        Project::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ProjectError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+// a document of any format version
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ProjectFile {
+    /// 0 if the file has no version field
+    version: u32,
+    #[serde(deserialize_with = "deserialize_curves")]
+    curves: Option<Vec<BezierPath<f64>>>,
+    display: Display,
+    curve_display: Vec<Display>,
+    colors: Colors,
+}
+
+impl ProjectFile {
+    // migrate to the current version
+    fn upgrade(self) -> Project {
+        // version 0: file without a version field, same layout as version 1
+        // version 1: single cubics, converted by `deserialize_curves`
+        // version 2: y-down canvas pixels, mirrored in place
+        let curves = match self.curves {
+            Some(mut curves) => {
+                if self.version < 3 {
+                    mirror_y(&mut curves);
+                }
+                curves
+            }
+            None => Project::default().curves,
+        };
+        Project {
+            version: FORMAT_VERSION,
+            curves,
+            display: self.display,
+            curve_display: self.curve_display,
+            colors: self.colors,
+        }
+    }
+}
+
+// a curve of any format version
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnyCurve {
+    Path(BezierPath<f64>),
+    Cubic(BezierCurve<f64>),
+}
+
+fn deserialize_curves<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<BezierPath<f64>>>, D::Error> {
+    let curves = Vec::<AnyCurve>::deserialize(deserializer)?;
+    Ok(Some(
+        curves
+            .into_iter()
+            .map(|curve| match curve {
+                AnyCurve::Path(path) => path,
+                AnyCurve::Cubic(cubic) => BezierPath::from_curve(cubic),
+            })
+            .collect(),
+    ))
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProjectError::Io(err) => write!(f, "{}", err),
+            ProjectError::Parse(err) => write!(f, "invalid project file: {}", err),
+            ProjectError::UnsupportedVersion(version) => write!(
+                f,
+                "project format version {} is newer than supported version {}",
+                version, FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl From<io::Error> for ProjectError {
+    fn from(err: io::Error) -> Self {
+        ProjectError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ProjectError {
+    fn from(err: serde_json::Error) -> Self {
+        ProjectError::Parse(err)
+    }
+}
@@ -0,0 +1,414 @@
+// CPU rendering of the editor scene
+//
+// Draws what `State::draw_frame` draws, in the same order, into an RGBA pixmap
+// with anti-aliased strokes; for screenshots without a GPU or a display.
+
+use crate::biarc::*;
+use crate::path::{FittedPath, PathStyle};
+use crate::png::encode_png;
+use crate::point::Vec2;
+use crate::scalar::Scalar;
+use crate::tree::{complete_depth, Node, Tree};
+use crate::util::*;
+
+/// DPI at which one scene unit is one pixel
+pub const BASE_DPI: f64 = 96.0;
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Scene size, in canvas units
+    pub width: f64,
+    pub height: f64,
+    /// Pixels per inch; the image is `width * dpi / 96` pixels wide
+    pub dpi: f64,
+    pub is_dotted: bool,
+    /// Control polygon and biarcs, as the editor's mesh toggle
+    pub is_meshed: bool,
+    /// Draw the boxes of this many lowest tree levels, as the editor's AABB depth
+    pub aabb_depth: usize,
+    pub background: Rgba,
+    pub curve_color: Rgba,
+    pub mesh_color: Rgba,
+    pub point_color: Rgba,
+    pub left_color: Rgba,
+    pub right_color: Rgba,
+    pub aabb_color: Rgba,
+    /// Settings of each path, in path order; paths without an entry use the ones above
+    pub path_styles: Vec<PathStyle>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            width: 720.0,
+            height: 480.0,
+            dpi: BASE_DPI,
+            is_dotted: false,
+            is_meshed: true,
+            aabb_depth: 1,
+            background: rgba8(255, 255, 255, 1.0),
+            curve_color: rgba8(0, 0, 0, 1.0),
+            mesh_color: rgba8(20, 210, 0, 1.0),
+            point_color: rgba8(255, 0, 0, 1.0),
+            left_color: rgba8(40, 210, 0, 1.0),
+            right_color: rgba8(30, 0, 210, 1.0),
+            aabb_color: rgba8(0, 30, 220, 1.0),
+            path_styles: Vec::new(),
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn path_style(&self, index: usize) -> PathStyle {
+        self.path_styles.get(index).copied().unwrap_or(PathStyle {
+            is_dotted: self.is_dotted,
+            is_meshed: self.is_meshed,
+            aabb_depth: self.aabb_depth,
+        })
+    }
+}
+
+/// RGBA image, rows from the top
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pixmap {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+impl Pixmap {
+    pub fn new(width: usize, height: usize, background: &Rgba) -> Self {
+        let pixel = to_rgba8(background);
+        Pixmap {
+            width,
+            height,
+            data: pixel
+                .iter()
+                .copied()
+                .cycle()
+                .take(width * height * 4)
+                .collect(),
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * self.width + x) * 4;
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    /// Stroke polylines (a path of several subpaths) with `width` in pixels.
+    /// Coverage is merged over the whole path, so overlapping pieces do not darken.
+    pub fn stroke(&mut self, subpaths: &[Vec<Vec2<f64>>], width: f64, color: &Rgba) {
+        let half = (width * 0.5).max(0.5);
+        // hairlines fade out instead of getting thinner
+        let weight = width.min(1.0);
+        let segments = subpaths
+            .iter()
+            .flat_map(|points| points.windows(2).map(|pair| (pair[0], pair[1])));
+        self.cover(segments, half, weight, color);
+    }
+
+    pub fn fill_circle(&mut self, center: &Vec2<f64>, radius: f64, color: &Rgba) {
+        // a zero-length segment covers a disc
+        self.cover(std::iter::once((*center, *center)), radius, 1.0, color);
+    }
+
+    fn cover(
+        &mut self,
+        segments: impl Iterator<Item = (Vec2<f64>, Vec2<f64>)>,
+        half: f64,
+        weight: f64,
+        color: &Rgba,
+    ) {
+        let segments: Vec<_> = segments.collect();
+        if segments.is_empty() || self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        // pixel bounds of the path, clipped to the image
+        let (mut x0, mut y0, mut x1, mut y1) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for (a, b) in segments.iter() {
+            x0 = x0.min(a.x.min(b.x));
+            y0 = y0.min(a.y.min(b.y));
+            x1 = x1.max(a.x.max(b.x));
+            y1 = y1.max(a.y.max(b.y));
+        }
+        let clip = |v: f64, max: usize| v.max(0.0).min(max as f64) as usize;
+        let (bx0, by0) = (
+            clip(x0 - half - 1.0, self.width),
+            clip(y0 - half - 1.0, self.height),
+        );
+        let (bx1, by1) = (
+            clip((x1 + half + 1.0).ceil(), self.width),
+            clip((y1 + half + 1.0).ceil(), self.height),
+        );
+        if bx0 >= bx1 || by0 >= by1 {
+            return;
+        }
+
+        let mask_width = bx1 - bx0;
+        let mut mask = vec![0f64; mask_width * (by1 - by0)];
+        for (a, b) in segments.iter() {
+            let sx0 = clip(a.x.min(b.x) - half - 1.0, self.width).max(bx0);
+            let sy0 = clip(a.y.min(b.y) - half - 1.0, self.height).max(by0);
+            let sx1 = clip((a.x.max(b.x) + half + 1.0).ceil(), self.width).min(bx1);
+            let sy1 = clip((a.y.max(b.y) + half + 1.0).ceil(), self.height).min(by1);
+            for y in sy0..sy1 {
+                for x in sx0..sx1 {
+                    let p = Vec2::new(x as f64 + 0.5, y as f64 + 0.5);
+                    let coverage = (half + 0.5 - segment_distance(&p, a, b)).clamp(0.0, 1.0);
+                    let m = &mut mask[(y - by0) * mask_width + x - bx0];
+                    *m = m.max(coverage);
+                }
+            }
+        }
+
+        for y in by0..by1 {
+            for x in bx0..bx1 {
+                let coverage = mask[(y - by0) * mask_width + x - bx0] * weight;
+                if coverage > 0.0 {
+                    self.blend(x, y, color, coverage);
+                }
+            }
+        }
+    }
+
+    // source-over
+    fn blend(&mut self, x: usize, y: usize, color: &Rgba, coverage: f64) {
+        let i = (y * self.width + x) * 4;
+        let src_a = color[3] as f64 * coverage;
+        let dst_a = self.data[i + 3] as f64 / 255.0;
+        let out_a = src_a + dst_a * (1.0 - src_a);
+        if out_a <= 0.0 {
+            return;
+        }
+        for (c, &src) in color.iter().take(3).enumerate() {
+            let src = src as f64;
+            let dst = self.data[i + c] as f64 / 255.0;
+            let value = (src * src_a + dst * dst_a * (1.0 - src_a)) / out_a;
+            self.data[i + c] = (value * 255.0).round() as u8;
+        }
+        self.data[i + 3] = (out_a * 255.0).round() as u8;
+    }
+
+    /// PNG with `dpi` in its physical size
+    pub fn to_png(&self, dpi: f64) -> Vec<u8> {
+        encode_png(self.width, self.height, &self.data, dpi)
+    }
+}
+
+fn to_rgba8(color: &Rgba) -> [u8; 4] {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [
+        channel(color[0]),
+        channel(color[1]),
+        channel(color[2]),
+        channel(color[3]),
+    ]
+}
+
+fn segment_distance(p: &Vec2<f64>, a: &Vec2<f64>, b: &Vec2<f64>) -> f64 {
+    let ab = *b - *a;
+    let len_sq = ab.dot(&ab);
+    let t = if len_sq > 0.0 {
+        ((*p - *a).dot(&ab) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let d = *p - (*a + ab * t);
+    d.dot(&d).sqrt()
+}
+
+struct Scene<'a> {
+    pixmap: Pixmap,
+    options: &'a RenderOptions,
+    scale: f64,
+}
+
+impl<'a> Scene<'a> {
+    fn map<S: Scalar>(&self, p: &Vec2<S>) -> Vec2<f64> {
+        Vec2::new(p.x.to_f64() * self.scale, p.y.to_f64() * self.scale)
+    }
+
+    fn stroke(&mut self, subpaths: &[Vec<Vec2<f64>>], width: f64, color: &Rgba) {
+        let mapped: Vec<Vec<Vec2<f64>>> = subpaths
+            .iter()
+            .map(|points| points.iter().map(|p| self.map(p)).collect())
+            .collect();
+        self.pixmap.stroke(&mapped, width * self.scale, color);
+    }
+
+    fn curve<S: Scalar + 'static>(
+        &mut self,
+        segment: &crate::bezier::BezierCurve<S>,
+        is_dotted: bool,
+    ) {
+        let mut point = Vec2::zero();
+        let points: Vec<Vec2<f64>> = (0..=RESOLUTION)
+            .map(|i| {
+                segment.cubic_curve_to(&mut point, S::from_usize(i) / S::from_usize(RESOLUTION));
+                point.cast()
+            })
+            .collect();
+        let subpaths = if is_dotted {
+            // every other step, as the editor's dotted curve
+            points
+                .windows(2)
+                .step_by(2)
+                .map(|pair| pair.to_vec())
+                .collect()
+        } else {
+            vec![points]
+        };
+        let color = self.options.curve_color;
+        self.stroke(&subpaths, 1.2, &color);
+    }
+
+    fn node<S: Scalar>(
+        &mut self,
+        tree: &Tree<ArcBox<S>>,
+        node: &Node<ArcBox<S>>,
+        color_idx: &mut i64,
+        depth: usize,
+        tree_depth: usize,
+        aabb_depth: usize,
+    ) {
+        if let Some(left) = tree.left(node) {
+            self.node(tree, left, color_idx, depth + 1, tree_depth, aabb_depth);
+        }
+        if let Some(right) = tree.right(node) {
+            self.node(tree, right, color_idx, depth + 1, tree_depth, aabb_depth);
+        }
+
+        if let Some(arc) = &node.arc {
+            let color = if *color_idx % 2 == 0 {
+                self.options.left_color
+            } else {
+                self.options.right_color
+            };
+            let arc: ArcData<f64> = ArcData {
+                center: arc.center.cast(),
+                radius: arc.radius.to_f64(),
+                start: arc.start.to_f64(),
+                sweep: arc.sweep.to_f64(),
+            };
+            self.stroke(&[arc.sample(RESOLUTION / 2)], 3.0, &color);
+            *color_idx += 1;
+        }
+
+        if depth + aabb_depth > tree_depth {
+            self.aabb(&node.aabb.cast(), node.radius.to_f64());
+        }
+    }
+
+    // box grown by `r` with rounded corners, as a closed outline
+    fn aabb(&mut self, aabb: &AABB<f64>, r: f64) {
+        let AABB { x, y, w, h } = *aabb;
+        let outline = if r <= 0.0 {
+            vec![
+                Vec2::new(x, y),
+                Vec2::new(x + w, y),
+                Vec2::new(x + w, y + h),
+                Vec2::new(x, y + h),
+                Vec2::new(x, y),
+            ]
+        } else {
+            let corners = [
+                (Vec2::new(x + w, y), -f64::FRAC_PI_2),
+                (Vec2::new(x + w, y + h), 0.0),
+                (Vec2::new(x, y + h), f64::FRAC_PI_2),
+                (Vec2::new(x, y), f64::PI),
+            ];
+            let mut outline = Vec::new();
+            for (center, start) in corners.iter() {
+                let corner = ArcData {
+                    center: *center,
+                    radius: r,
+                    start: *start,
+                    sweep: f64::FRAC_PI_2,
+                };
+                outline.extend(corner.sample(RES_4));
+            }
+            outline.push(outline[0]);
+            outline
+        };
+        let color = self.options.aabb_color;
+        self.stroke(&[outline], 2.0, &color);
+    }
+}
+
+/// Draw the paths like the editor canvas: control meshes, curves, frame border,
+/// biarcs with their boxes, control points.
+pub fn render<S: Scalar + 'static>(paths: &[FittedPath<S>], options: &RenderOptions) -> Pixmap {
+    let scale = options.dpi / BASE_DPI;
+    let width = (options.width * scale).round().max(0.0) as usize;
+    let height = (options.height * scale).round().max(0.0) as usize;
+    let mut scene = Scene {
+        pixmap: Pixmap::new(width, height, &options.background),
+        options,
+        scale,
+    };
+    let styles: Vec<PathStyle> = (0..paths.len()).map(|i| options.path_style(i)).collect();
+    let mesh: Vec<Vec<Vec2<f64>>> = paths
+        .iter()
+        .zip(styles.iter())
+        .filter(|(_, style)| style.is_meshed)
+        .flat_map(|(fitted, _)| fitted.path.segments.iter())
+        .map(|segment| segment.control_pts.iter().map(|p| p.cast()).collect())
+        .collect();
+    if !mesh.is_empty() {
+        scene.stroke(&mesh, 2.0, &options.mesh_color);
+    }
+
+    for (fitted, style) in paths.iter().zip(styles.iter()) {
+        for segment in fitted.path.segments.iter() {
+            scene.curve(segment, style.is_dotted);
+        }
+    }
+    let border = vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(options.width, 0.0),
+        Vec2::new(options.width, options.height),
+        Vec2::new(0.0, options.height),
+        Vec2::new(0.0, 0.0),
+    ];
+    scene.stroke(&[border], 1.0, &options.curve_color);
+
+    for (fitted, style) in paths.iter().zip(styles.iter()) {
+        if !style.is_meshed {
+            continue;
+        }
+        let mut color_idx: i64 = 0;
+        for arcs in fitted.arcs.iter() {
+            let tree = arcs.borrow();
+            if let Some(root) = tree.get(0) {
+                let tree_depth = complete_depth(tree.len() - 1);
+                scene.node(&tree, root, &mut color_idx, 0, tree_depth, style.aabb_depth);
+            }
+        }
+    }
+
+    let radius = (PTS_RADIUS * 2.0) as f64 * scale;
+    for segment in paths.iter().flat_map(|fitted| fitted.path.segments.iter()) {
+        for p in segment.control_pts.iter() {
+            let center = scene.map(p);
+            scene
+                .pixmap
+                .fill_circle(&center, radius, &options.point_color);
+        }
+    }
+    scene.pixmap
+}
+
+/// `render` encoded as PNG at `options.dpi`
+pub fn render_png<S: Scalar + 'static>(
+    paths: &[FittedPath<S>],
+    options: &RenderOptions,
+) -> Vec<u8> {
+    render(paths, options).to_png(options.dpi)
+}
@@ -0,0 +1,311 @@
+// Sampled curve data and the leaf arc table, as CSV or JSON for analysis
+//
+// Angles are in radians, as in `ArcData`; lengths are in canvas units.
+
+use std::fmt::Write;
+
+use crate::biarc::*;
+use crate::path::{nearest_distance, FittedPath};
+use crate::point::Vec2;
+use crate::svg::format_num;
+use crate::util::RESOLUTION;
+
+/// Where the samples are taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spacing {
+    /// Equal steps of the curve parameter within each segment
+    Parameter,
+    /// Equal steps of arc length along the whole path
+    ArcLength,
+}
+
+#[derive(Debug, Clone)]
+pub struct SampleOptions {
+    pub spacing: Spacing,
+    /// Steps per segment; a path gets `samples * segments + 1` samples
+    pub samples: usize,
+}
+
+impl Default for SampleOptions {
+    fn default() -> Self {
+        SampleOptions {
+            spacing: Spacing::Parameter,
+            samples: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub curve: usize,
+    pub segment: usize,
+    /// Parameter within the segment
+    pub t: f64,
+    /// Arc length from the start of the path
+    pub length: f64,
+    pub position: Vec2<f64>,
+    /// Unit tangent, zero where the curve stops
+    pub tangent: Vec2<f64>,
+    /// Signed curvature, not finite where the curve stops
+    pub curvature: f64,
+    /// Distance to the nearest leaf arc of the segment
+    pub deviation: f64,
+}
+
+/// One leaf of a biarc tree
+#[derive(Debug, Clone)]
+pub struct LeafRow {
+    pub curve: usize,
+    pub segment: usize,
+    /// Index along the segment
+    pub leaf: usize,
+    pub arc: ArcData<f64>,
+    pub aabb: AABB<f64>,
+    /// Rounding radius of the box
+    pub rounding: f64,
+}
+
+/// Cumulative chord lengths of a segment at `t = j / RESOLUTION`
+fn length_table(fitted: &FittedPath<f64>, segment: usize) -> Vec<f64> {
+    let curve = &fitted.path.segments[segment];
+    let mut table = Vec::with_capacity(RESOLUTION + 1);
+    let mut prev = curve.control_pts[0];
+    let mut point = Vec2::zero();
+    let mut length = 0.0;
+    for j in 0..=RESOLUTION {
+        curve.cubic_curve_to(&mut point, j as f64 / RESOLUTION as f64);
+        let d = point - prev;
+        length += d.dot(&d).sqrt();
+        table.push(length);
+        prev = point;
+    }
+    table
+}
+
+fn length_at(table: &[f64], t: f64) -> f64 {
+    let x = t.clamp(0.0, 1.0) * RESOLUTION as f64;
+    let j = (x.floor() as usize).min(RESOLUTION - 1);
+    table[j] + (table[j + 1] - table[j]) * (x - j as f64)
+}
+
+fn t_at(table: &[f64], length: f64) -> f64 {
+    let j = match table.iter().position(|&l| l >= length) {
+        Some(0) => return 0.0,
+        Some(j) => j - 1,
+        None => return 1.0,
+    };
+    let span = table[j + 1] - table[j];
+    let fraction = if span > 0.0 {
+        (length - table[j]) / span
+    } else {
+        0.0
+    };
+    (j as f64 + fraction) / RESOLUTION as f64
+}
+
+/// Samples of every path, in path order
+pub fn sample_paths(paths: &[FittedPath<f64>], options: &SampleOptions) -> Vec<Sample> {
+    let steps = options.samples.max(1);
+    let mut samples = Vec::new();
+    for (curve, fitted) in paths.iter().enumerate() {
+        let segments = fitted.path.segments.len();
+        if segments == 0 {
+            continue;
+        }
+        let tables: Vec<Vec<f64>> = (0..segments).map(|i| length_table(fitted, i)).collect();
+        let mut offsets = vec![0.0];
+        for table in tables.iter() {
+            offsets.push(offsets.last().unwrap() + table[RESOLUTION]);
+        }
+        let leaves: Vec<Vec<ArcData<f64>>> =
+            (0..segments).map(|i| fitted.segment_leaves(i)).collect();
+
+        // (segment, t) of every sample
+        let n = steps * segments;
+        let positions: Vec<(usize, f64)> = match options.spacing {
+            Spacing::Parameter => (0..=n)
+                .map(|k| {
+                    // a joint ends the previous segment
+                    let segment = (k.saturating_sub(1) / steps).min(segments - 1);
+                    (segment, (k - segment * steps) as f64 / steps as f64)
+                })
+                .collect(),
+            Spacing::ArcLength => (0..=n)
+                .map(|k| {
+                    let length = offsets[segments] * k as f64 / n.max(1) as f64;
+                    let segment = (0..segments)
+                        .find(|&i| length <= offsets[i + 1])
+                        .unwrap_or(segments - 1);
+                    (segment, t_at(&tables[segment], length - offsets[segment]))
+                })
+                .collect(),
+        };
+
+        for (segment, t) in positions {
+            let bezier = &fitted.path.segments[segment];
+            let mut position = Vec2::zero();
+            let mut tangent = Vec2::zero();
+            bezier.cubic_curve_to(&mut position, t);
+            bezier.cubic_deriv_to(&mut tangent, t);
+            let speed = tangent.dot(&tangent).sqrt();
+            if speed > 0.0 {
+                tangent = tangent * (1.0 / speed);
+            }
+            let deviation = if leaves[segment].is_empty() {
+                0.0
+            } else {
+                nearest_distance(&leaves[segment], &position)
+            };
+            samples.push(Sample {
+                curve,
+                segment,
+                t,
+                length: offsets[segment] + length_at(&tables[segment], t),
+                position,
+                tangent,
+                curvature: bezier.curvature(t),
+                deviation,
+            });
+        }
+    }
+    samples
+}
+
+/// Leaf arcs of every path with their boxes, in path order
+pub fn leaf_table(paths: &[FittedPath<f64>]) -> Vec<LeafRow> {
+    let mut rows = Vec::new();
+    for (curve, fitted) in paths.iter().enumerate() {
+        for (segment, arcs) in fitted.arcs.iter().enumerate() {
+            let tree = arcs.borrow();
+            let leaves = (0..tree.len()).filter_map(|i| {
+                let node = tree.get(i).unwrap();
+                node.arc.as_ref().map(|arc| (arc, node))
+            });
+            for (leaf, (arc, node)) in leaves.enumerate() {
+                rows.push(LeafRow {
+                    curve,
+                    segment,
+                    leaf,
+                    arc: arc.clone(),
+                    aabb: node.aabb.clone(),
+                    rounding: node.radius,
+                });
+            }
+        }
+    }
+    rows
+}
+
+// empty in CSV, null in JSON
+fn number(value: f64, precision: usize, missing: &str) -> String {
+    if value.is_finite() {
+        format_num(value, precision)
+    } else {
+        String::from(missing)
+    }
+}
+
+/// `precision`: digits after the decimal point
+pub fn samples_csv(samples: &[Sample], precision: usize) -> String {
+    let num = |v: f64| number(v, precision, "");
+    let mut csv =
+        String::from("curve,segment,t,length,x,y,tangent_x,tangent_y,curvature,deviation\n");
+    for s in samples.iter() {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{},{}",
+            s.curve,
+            s.segment,
+            num(s.t),
+            num(s.length),
+            num(s.position.x),
+            num(s.position.y),
+            num(s.tangent.x),
+            num(s.tangent.y),
+            num(s.curvature),
+            num(s.deviation)
+        )
+        .unwrap();
+    }
+    csv
+}
+
+pub fn leaves_csv(rows: &[LeafRow], precision: usize) -> String {
+    let num = |v: f64| number(v, precision, "");
+    let mut csv = String::from(
+        "curve,segment,leaf,center_x,center_y,radius,start,sweep,end,aabb_x,aabb_y,aabb_w,aabb_h,rounding\n",
+    );
+    for row in rows.iter() {
+        let arc = &row.arc;
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            row.curve,
+            row.segment,
+            row.leaf,
+            num(arc.center.x),
+            num(arc.center.y),
+            num(arc.radius),
+            num(arc.start),
+            num(arc.sweep),
+            num(arc.end()),
+            num(row.aabb.x),
+            num(row.aabb.y),
+            num(row.aabb.w),
+            num(row.aabb.h),
+            num(row.rounding)
+        )
+        .unwrap();
+    }
+    csv
+}
+
+/// Both tables in one document: `{"samples": [...], "leaves": [...]}`
+pub fn export_json(samples: &[Sample], rows: &[LeafRow], precision: usize) -> String {
+    let num = |v: f64| number(v, precision, "null");
+    let mut json = String::from("{\n  \"samples\": [");
+    for (i, s) in samples.iter().enumerate() {
+        write!(
+            json,
+            "{}\n    {{\"curve\": {}, \"segment\": {}, \"t\": {}, \"length\": {}, \"position\": [{}, {}], \"tangent\": [{}, {}], \"curvature\": {}, \"deviation\": {}}}",
+            if i > 0 { "," } else { "" },
+            s.curve,
+            s.segment,
+            num(s.t),
+            num(s.length),
+            num(s.position.x),
+            num(s.position.y),
+            num(s.tangent.x),
+            num(s.tangent.y),
+            num(s.curvature),
+            num(s.deviation)
+        )
+        .unwrap();
+    }
+    json.push_str("\n  ],\n  \"leaves\": [");
+    for (i, row) in rows.iter().enumerate() {
+        let arc = &row.arc;
+        write!(
+            json,
+            "{}\n    {{\"curve\": {}, \"segment\": {}, \"leaf\": {}, \"center\": [{}, {}], \"radius\": {}, \"start\": {}, \"sweep\": {}, \"end\": {}, \"aabb\": [{}, {}, {}, {}], \"rounding\": {}}}",
+            if i > 0 { "," } else { "" },
+            row.curve,
+            row.segment,
+            row.leaf,
+            num(arc.center.x),
+            num(arc.center.y),
+            num(arc.radius),
+            num(arc.start),
+            num(arc.sweep),
+            num(arc.end()),
+            num(row.aabb.x),
+            num(row.aabb.y),
+            num(row.aabb.w),
+            num(row.aabb.h),
+            num(row.rounding)
+        )
+        .unwrap();
+    }
+    json.push_str("\n  ]\n}\n");
+    json
+}
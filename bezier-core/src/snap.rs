@@ -0,0 +1,98 @@
+// Snapping of dragged points to a grid, other control points and curves
+//
+// Every distance is in world units; front-ends convert their pick radius from pixels.
+
+use crate::bezier::BezierCurve;
+use crate::point::Vec2;
+use crate::util::distance;
+
+#[derive(Debug, Clone)]
+pub struct SnapOptions {
+    /// Grid spacing, `None` for no grid snapping
+    pub grid: Option<f64>,
+    pub points: bool,
+    pub curves: bool,
+    /// Points and curves farther than this do not attract
+    pub radius: f64,
+}
+
+impl Default for SnapOptions {
+    fn default() -> Self {
+        SnapOptions {
+            grid: None,
+            points: true,
+            curves: false,
+            radius: 8.0,
+        }
+    }
+}
+
+/// What a snapped point was attracted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapTarget {
+    Free,
+    Point,
+    Curve,
+    Grid,
+}
+
+/// Nearest grid crossing
+pub fn snap_to_grid(p: &Vec2<f64>, spacing: f64) -> Vec2<f64> {
+    if spacing <= 0.0 {
+        return *p;
+    }
+    Vec2::new(
+        (p.x / spacing).round() * spacing,
+        (p.y / spacing).round() * spacing,
+    )
+}
+
+/// Snap `p` to the nearest of `points` in reach, else the nearest point of `curves`
+/// in reach, else the grid.
+pub fn snap(
+    p: &Vec2<f64>,
+    options: &SnapOptions,
+    points: &[Vec2<f64>],
+    curves: &[BezierCurve<f64>],
+) -> (Vec2<f64>, SnapTarget) {
+    let nearest = |candidates: &mut dyn Iterator<Item = Vec2<f64>>| {
+        candidates
+            .map(|q| (distance(p, &q), q))
+            .filter(|(d, _)| *d <= options.radius)
+            .fold(None, |best: Option<(f64, Vec2<f64>)>, (d, q)| match best {
+                Some((best_d, _)) if best_d <= d => best,
+                _ => Some((d, q)),
+            })
+    };
+
+    if options.points {
+        if let Some((_, q)) = nearest(&mut points.iter().copied()) {
+            return (q, SnapTarget::Point);
+        }
+    }
+    if options.curves {
+        let mut on_curves = curves.iter().map(|curve| {
+            let mut q = Vec2::zero();
+            curve.cubic_curve_to(&mut q, curve.nearest_t(p));
+            q
+        });
+        if let Some((_, q)) = nearest(&mut on_curves) {
+            return (q, SnapTarget::Curve);
+        }
+    }
+    match options.grid {
+        Some(spacing) => (snap_to_grid(p, spacing), SnapTarget::Grid),
+        None => (*p, SnapTarget::Free),
+    }
+}
+
+/// Project `p` onto the nearest ray from `anchor` at a multiple of `step` radians
+pub fn constrain_angle(anchor: &Vec2<f64>, p: &Vec2<f64>, step: f64) -> Vec2<f64> {
+    let offset = *p - *anchor;
+    if step <= 0.0 || offset.dot(&offset) == 0.0 {
+        return *p;
+    }
+    let angle = (offset.y.atan2(offset.x) / step).round() * step;
+    let dir = Vec2::from_angle(angle);
+    *anchor + dir * offset.dot(&dir)
+}
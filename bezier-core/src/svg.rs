@@ -0,0 +1,310 @@
+// SVG export of the scene drawn by the editor
+//
+// Coordinates are written unchanged, so callers pass paths fitted in y-down
+// coordinates (the editor mirrors its y-up world first). A positive sweep, from the
+// +x-axis towards the +y-axis, then turns the way of SVG's sweep-flag 1.
+
+use std::fmt::Write;
+
+use crate::biarc::*;
+use crate::path::{FittedPath, PathStyle};
+use crate::point::Vec2;
+use crate::scalar::Scalar;
+use crate::util::*;
+
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// Top-left corner of the `viewBox`, in drawing units
+    pub origin: Vec2<f64>,
+    pub width: f64,
+    pub height: f64,
+    /// Digits after the decimal point
+    pub precision: usize,
+    pub is_dotted: bool,
+    /// Control polygon and control points
+    pub control_mesh: bool,
+    /// Draw the boxes of this many lowest tree levels, as the editor's AABB depth
+    pub aabb_depth: usize,
+    pub curve_color: Rgba,
+    pub mesh_color: Rgba,
+    pub point_color: Rgba,
+    pub left_color: Rgba,
+    pub right_color: Rgba,
+    pub aabb_color: Rgba,
+    /// Settings of each path, in path order; paths without an entry use the ones above
+    pub path_styles: Vec<PathStyle>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            origin: Vec2::zero(),
+            width: 720.0,
+            height: 480.0,
+            precision: 3,
+            is_dotted: false,
+            control_mesh: true,
+            aabb_depth: 1,
+            curve_color: rgba8(0, 0, 0, 1.0),
+            mesh_color: rgba8(20, 210, 0, 1.0),
+            point_color: rgba8(255, 0, 0, 1.0),
+            left_color: rgba8(40, 210, 0, 1.0),
+            right_color: rgba8(30, 0, 210, 1.0),
+            aabb_color: rgba8(0, 30, 220, 1.0),
+            path_styles: Vec::new(),
+        }
+    }
+}
+
+impl SvgOptions {
+    pub fn path_style(&self, index: usize) -> PathStyle {
+        self.path_styles.get(index).copied().unwrap_or(PathStyle {
+            is_dotted: self.is_dotted,
+            is_meshed: self.control_mesh,
+            aabb_depth: self.aabb_depth,
+        })
+    }
+}
+
+/// Write the paths, their biarcs and (optionally) the control meshes and bounding boxes
+/// as a standalone SVG document.
+pub fn export_svg<S: Scalar + 'static>(paths: &[FittedPath<S>], options: &SvgOptions) -> String {
+    let num = |v: S| format_num(v.to_f64(), options.precision);
+    let pt = |p: &Vec2<S>| format!("{},{}", num(p.x), num(p.y));
+    let styles: Vec<PathStyle> = (0..paths.len()).map(|i| options.path_style(i)).collect();
+    let meshed = || {
+        paths
+            .iter()
+            .zip(styles.iter())
+            .filter(|(_, style)| style.is_meshed)
+            .flat_map(|(fitted, _)| fitted.path.segments.iter())
+    };
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{x} {y} {w} {h}">"#,
+        x = format_num(options.origin.x, options.precision),
+        y = format_num(options.origin.y, options.precision),
+        w = format_num(options.width, options.precision),
+        h = format_num(options.height, options.precision),
+    )
+    .unwrap();
+
+    // bounding volumes, lowest level first like `draw_node`
+    if styles.iter().any(|style| style.aabb_depth > 0) {
+        writeln!(
+            svg,
+            r#"  <g id="aabb" fill="none" stroke-width="2"{}>"#,
+            stroke(&options.aabb_color)
+        )
+        .unwrap();
+        for (aabb, r) in paths
+            .iter()
+            .zip(styles.iter())
+            .flat_map(|(fitted, style)| fitted.boxes(style.aabb_depth))
+        {
+            let AABB { x, y, h, w } = aabb;
+            if r <= S::ZERO {
+                writeln!(
+                    svg,
+                    r#"    <rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                    num(x),
+                    num(y),
+                    num(w),
+                    num(h)
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    svg,
+                    r#"    <rect x="{}" y="{}" width="{}" height="{}" rx="{r}" ry="{r}"/>"#,
+                    num(x - r),
+                    num(y - r),
+                    num(w + r + r),
+                    num(h + r + r),
+                    r = num(r)
+                )
+                .unwrap();
+            }
+        }
+        writeln!(svg, "  </g>").unwrap();
+    }
+
+    if styles.iter().any(|style| style.is_meshed) {
+        writeln!(
+            svg,
+            r#"  <g id="mesh" fill="none" stroke-width="2"{}>"#,
+            stroke(&options.mesh_color)
+        )
+        .unwrap();
+        for segment in meshed() {
+            let pts = &segment.control_pts;
+            writeln!(
+                svg,
+                r#"    <path d="M {} L {} {} {}"/>"#,
+                pt(&pts[0]),
+                pt(&pts[1]),
+                pt(&pts[2]),
+                pt(&pts[3])
+            )
+            .unwrap();
+        }
+        writeln!(svg, "  </g>").unwrap();
+    }
+
+    writeln!(
+        svg,
+        r#"  <g id="curves" fill="none" stroke-width="1.2"{}{}>"#,
+        stroke(&options.curve_color),
+        if options.is_dotted {
+            r#" stroke-dasharray="4""#
+        } else {
+            ""
+        }
+    )
+    .unwrap();
+    for (fitted, style) in paths.iter().zip(styles.iter()) {
+        if let Some(start) = fitted.path.start() {
+            let mut d = format!("M {}", pt(&start));
+            for segment in fitted.path.segments.iter() {
+                let pts = &segment.control_pts;
+                write!(d, " C {} {} {}", pt(&pts[1]), pt(&pts[2]), pt(&pts[3])).unwrap();
+            }
+            if fitted.path.closed {
+                d.push_str(" Z");
+            }
+            // paths dotted unlike the group
+            let dash = match (style.is_dotted, options.is_dotted) {
+                (true, false) => r#" stroke-dasharray="4""#,
+                (false, true) => r#" stroke-dasharray="none""#,
+                _ => "",
+            };
+            writeln!(svg, r#"    <path d="{}"{}/>"#, d, dash).unwrap();
+        }
+    }
+    writeln!(svg, "  </g>").unwrap();
+
+    // leaf arcs, alternating colors as in the editor
+    writeln!(svg, r#"  <g id="biarcs" fill="none" stroke-width="3">"#).unwrap();
+    for fitted in paths.iter() {
+        for (idx, arc) in fitted.leaves().iter().enumerate() {
+            let color = if idx % 2 == 0 {
+                &options.left_color
+            } else {
+                &options.right_color
+            };
+            writeln!(
+                svg,
+                r#"    <path d="{}"{}/>"#,
+                arc_path(arc, options.precision),
+                stroke(color)
+            )
+            .unwrap();
+        }
+    }
+    writeln!(svg, "  </g>").unwrap();
+
+    if styles.iter().any(|style| style.is_meshed) {
+        writeln!(
+            svg,
+            r#"  <g id="control-points"{}>"#,
+            fill(&options.point_color)
+        )
+        .unwrap();
+        for segment in meshed() {
+            for p in segment.control_pts.iter() {
+                writeln!(
+                    svg,
+                    r#"    <circle cx="{}" cy="{}" r="{}"/>"#,
+                    num(p.x),
+                    num(p.y),
+                    format_num((PTS_RADIUS * 2.0) as f64, options.precision)
+                )
+                .unwrap();
+            }
+        }
+        writeln!(svg, "  </g>").unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
+
+/// Path data of a single arc: `M start A r r 0 large-arc sweep end`
+pub fn arc_path<S: Scalar>(arc: &ArcData<S>, precision: usize) -> String {
+    let num = |v: S| format_num(v.to_f64(), precision);
+    let start = arc.start_point();
+    let mut d = format!("M {},{}", num(start.x), num(start.y));
+    let sweep_flag = if arc.is_ccw() { 1 } else { 0 };
+
+    // a full circle has coincident endpoints; split it in halves
+    let pieces = if arc.sweep.abs() >= S::TAU { 2 } else { 1 };
+    for k in 1..=pieces {
+        let sweep = arc.sweep / S::from_usize(pieces);
+        let large_arc = if sweep.abs() > S::PI { 1 } else { 0 };
+        let end = arc.point_at(S::from_usize(k) / S::from_usize(pieces));
+        write!(
+            d,
+            " A {r},{r} 0 {} {} {},{}",
+            large_arc,
+            sweep_flag,
+            num(end.x),
+            num(end.y),
+            r = num(arc.radius)
+        )
+        .unwrap();
+    }
+    d
+}
+
+/// Fixed precision without trailing zeros
+pub fn format_num(value: f64, precision: usize) -> String {
+    let mut text = format!("{:.*}", precision, value);
+    if text.contains('.') {
+        while text.ends_with('0') {
+            text.pop();
+        }
+        if text.ends_with('.') {
+            text.pop();
+        }
+    }
+    if text == "-0" {
+        text = String::from("0");
+    }
+    text
+}
+
+fn color(rgba: &Rgba) -> String {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(rgba[0]),
+        channel(rgba[1]),
+        channel(rgba[2])
+    )
+}
+
+fn stroke(rgba: &Rgba) -> String {
+    if rgba[3] < 1.0 {
+        format!(
+            r#" stroke="{}" stroke-opacity="{}""#,
+            color(rgba),
+            format_num(rgba[3] as f64, 3)
+        )
+    } else {
+        format!(r#" stroke="{}""#, color(rgba))
+    }
+}
+
+fn fill(rgba: &Rgba) -> String {
+    if rgba[3] < 1.0 {
+        format!(
+            r#" fill="{}" fill-opacity="{}""#,
+            color(rgba),
+            format_num(rgba[3] as f64, 3)
+        )
+    } else {
+        format!(r#" fill="{}""#, color(rgba))
+    }
+}
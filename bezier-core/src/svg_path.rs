@@ -0,0 +1,473 @@
+// SVG path data import
+//
+// https://www.w3.org/TR/SVG11/paths.html#PathData
+// Lines and quadratics are elevated to cubics; elliptical arcs are split into
+// pieces of at most 90 degree, each approximated by a cubic.
+// Transforms and other shape elements are not applied.
+
+use std::fmt;
+
+use crate::bezier::BezierCurve;
+use crate::path::BezierPath;
+use crate::point::Vec2;
+
+type Point = Vec2<f64>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Byte offset in the path data
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Paths of every `<path d="...">` element of an SVG document, in document order
+pub fn import_svg(svg: &str) -> Result<Vec<BezierPath<f64>>, ParseError> {
+    let mut paths = Vec::new();
+    let mut rest = svg;
+    while let Some(start) = rest.find("<path") {
+        let tag = &rest[start..];
+        let end = tag.find('>').unwrap_or(tag.len());
+        let is_path = matches!(
+            tag[5..].chars().next(),
+            Some(c) if c.is_whitespace() || c == '/' || c == '>'
+        );
+        if is_path {
+            if let Some(d) = attribute(&tag[..end], "d") {
+                paths.extend(parse_path_data(d)?);
+            }
+        }
+        rest = &tag[end..];
+    }
+    Ok(paths)
+}
+
+// value of ` name="..."` (or single quotes) in a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let bytes = tag.as_bytes();
+    let mut from = 0;
+    while let Some(offset) = tag[from..].find(name) {
+        let at = from + offset;
+        from = at + name.len();
+        if at == 0 || !bytes[at - 1].is_ascii_whitespace() {
+            continue;
+        }
+        let after = tag[from..].trim_start();
+        if let Some(after) = after.strip_prefix('=') {
+            let after = after.trim_start();
+            let quote = after.chars().next()?;
+            if quote == '"' || quote == '\'' {
+                let value = &after[1..];
+                return value.find(quote).map(|end| &value[..end]);
+            }
+        }
+    }
+    None
+}
+
+/// Parse path data into one path per subpath.
+pub fn parse_path_data(d: &str) -> Result<Vec<BezierPath<f64>>, ParseError> {
+    let mut parser = PathParser::new(d);
+    parser.parse()?;
+    parser.finish_subpath();
+    Ok(parser.paths)
+}
+
+struct PathParser<'a> {
+    data: &'a [u8],
+    pos: usize,
+    paths: Vec<BezierPath<f64>>,
+    current: BezierPath<f64>,
+    point: Point,
+    subpath_start: Point,
+    // reflected by S / T
+    last_cubic: Option<Point>,
+    last_quad: Option<Point>,
+}
+
+impl<'a> PathParser<'a> {
+    fn new(d: &'a str) -> Self {
+        PathParser {
+            data: d.as_bytes(),
+            pos: 0,
+            paths: Vec::new(),
+            current: BezierPath {
+                segments: Vec::new(),
+                closed: false,
+            },
+            point: Point::zero(),
+            subpath_start: Point::zero(),
+            last_cubic: None,
+            last_quad: None,
+        }
+    }
+
+    fn parse(&mut self) -> Result<(), ParseError> {
+        self.skip_separators();
+        if self.pos < self.data.len() && !matches!(self.data[self.pos], b'M' | b'm') {
+            return Err(self.error("path data must start with a moveto"));
+        }
+
+        while self.pos < self.data.len() {
+            let command = self.data[self.pos];
+            if !command.is_ascii_alphabetic() {
+                return Err(self.error("expected a command"));
+            }
+            self.pos += 1;
+            let relative = command.is_ascii_lowercase();
+
+            match command.to_ascii_uppercase() {
+                b'Z' => self.close(),
+                b'M' => {
+                    let p = self.read_point(relative)?;
+                    self.finish_subpath();
+                    self.point = p;
+                    self.subpath_start = p;
+                    self.reset_controls();
+                    // following pairs are implicit lineto
+                    while self.has_number() {
+                        let p = self.read_point(relative)?;
+                        self.line_to(p);
+                    }
+                }
+                b'L' => self.repeat(|parser| {
+                    let p = parser.read_point(relative)?;
+                    parser.line_to(p);
+                    Ok(())
+                })?,
+                b'H' => self.repeat(|parser| {
+                    let mut x = parser.read_number()?;
+                    if relative {
+                        x += parser.point.x;
+                    }
+                    parser.line_to(Point::new(x, parser.point.y));
+                    Ok(())
+                })?,
+                b'V' => self.repeat(|parser| {
+                    let mut y = parser.read_number()?;
+                    if relative {
+                        y += parser.point.y;
+                    }
+                    parser.line_to(Point::new(parser.point.x, y));
+                    Ok(())
+                })?,
+                b'C' => self.repeat(|parser| {
+                    let c1 = parser.read_point(relative)?;
+                    let c2 = parser.read_point(relative)?;
+                    let p = parser.read_point(relative)?;
+                    parser.cubic_to(c1, c2, p);
+                    Ok(())
+                })?,
+                b'S' => self.repeat(|parser| {
+                    let c1 = parser.reflect(parser.last_cubic);
+                    let c2 = parser.read_point(relative)?;
+                    let p = parser.read_point(relative)?;
+                    parser.cubic_to(c1, c2, p);
+                    Ok(())
+                })?,
+                b'Q' => self.repeat(|parser| {
+                    let q = parser.read_point(relative)?;
+                    let p = parser.read_point(relative)?;
+                    parser.quad_to(q, p);
+                    Ok(())
+                })?,
+                b'T' => self.repeat(|parser| {
+                    let q = parser.reflect(parser.last_quad);
+                    let p = parser.read_point(relative)?;
+                    parser.quad_to(q, p);
+                    Ok(())
+                })?,
+                b'A' => self.repeat(|parser| {
+                    let rx = parser.read_number()?;
+                    let ry = parser.read_number()?;
+                    let rotation = parser.read_number()?;
+                    let large_arc = parser.read_flag()?;
+                    let sweep = parser.read_flag()?;
+                    let p = parser.read_point(relative)?;
+                    parser.arc_to(rx, ry, rotation, large_arc, sweep, p);
+                    Ok(())
+                })?,
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("unknown command"));
+                }
+            }
+            self.skip_separators();
+        }
+        Ok(())
+    }
+
+    // run a command once, then again for every further parameter set
+    fn repeat<F>(&mut self, mut command: F) -> Result<(), ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<(), ParseError>,
+    {
+        command(self)?;
+        while self.has_number() {
+            command(self)?;
+        }
+        Ok(())
+    }
+
+    fn finish_subpath(&mut self) {
+        if !self.current.segments.is_empty() {
+            let path = std::mem::replace(
+                &mut self.current,
+                BezierPath {
+                    segments: Vec::new(),
+                    closed: false,
+                },
+            );
+            self.paths.push(path);
+        }
+    }
+
+    fn reset_controls(&mut self) {
+        self.last_cubic = None;
+        self.last_quad = None;
+    }
+
+    // a drawing command right after `Z` starts a new subpath at the same point
+    fn begin_segment(&mut self) {
+        if self.current.closed {
+            self.finish_subpath();
+        }
+    }
+
+    fn line_to(&mut self, p: Point) {
+        self.begin_segment();
+        self.current.segments.push(BezierCurve::line(self.point, p));
+        self.point = p;
+        self.reset_controls();
+    }
+
+    fn cubic_to(&mut self, c1: Point, c2: Point, p: Point) {
+        self.begin_segment();
+        self.current.segments.push(BezierCurve {
+            control_pts: [self.point, c1, c2, p],
+        });
+        self.point = p;
+        self.last_cubic = Some(c2);
+        self.last_quad = None;
+    }
+
+    fn quad_to(&mut self, q: Point, p: Point) {
+        self.begin_segment();
+        self.current
+            .segments
+            .push(BezierCurve::quadratic(self.point, q, p));
+        self.point = p;
+        self.last_cubic = None;
+        self.last_quad = Some(q);
+    }
+
+    fn arc_to(&mut self, rx: f64, ry: f64, rotation: f64, large_arc: bool, sweep: bool, p: Point) {
+        if p == self.point {
+            return;
+        }
+        if rx == 0.0 || ry == 0.0 {
+            self.line_to(p);
+            return;
+        }
+
+        self.begin_segment();
+        for segment in arc_to_cubics(self.point, rx, ry, rotation, large_arc, sweep, p) {
+            self.current.segments.push(segment);
+        }
+        self.point = p;
+        self.reset_controls();
+    }
+
+    fn close(&mut self) {
+        if self.current.segments.is_empty() || self.current.closed {
+            self.point = self.subpath_start;
+            return;
+        }
+        if self.point != self.subpath_start {
+            self.line_to(self.subpath_start);
+        }
+        self.current.closed = true;
+        self.point = self.subpath_start;
+        self.reset_controls();
+    }
+
+    // control point mirrored about the current point, or the current point itself
+    fn reflect(&self, control: Option<Point>) -> Point {
+        match control {
+            Some(c) => self.point * 2.0 - c,
+            None => self.point,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.data.len()
+            && (self.data[self.pos].is_ascii_whitespace() || self.data[self.pos] == b',')
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn has_number(&mut self) -> bool {
+        self.skip_separators();
+        self.pos < self.data.len()
+            && matches!(self.data[self.pos], b'0'..=b'9' | b'.' | b'-' | b'+')
+    }
+
+    fn read_point(&mut self, relative: bool) -> Result<Point, ParseError> {
+        let x = self.read_number()?;
+        let y = self.read_number()?;
+        if relative {
+            Ok(Point::new(self.point.x + x, self.point.y + y))
+        } else {
+            Ok(Point::new(x, y))
+        }
+    }
+
+    fn read_number(&mut self) -> Result<f64, ParseError> {
+        self.skip_separators();
+        let start = self.pos;
+        let data = self.data;
+        let digits = |pos: &mut usize| {
+            let from = *pos;
+            while *pos < data.len() && data[*pos].is_ascii_digit() {
+                *pos += 1;
+            }
+            *pos > from
+        };
+
+        if self.pos < data.len() && matches!(data[self.pos], b'-' | b'+') {
+            self.pos += 1;
+        }
+        let mut has_digits = digits(&mut self.pos);
+        if self.pos < data.len() && data[self.pos] == b'.' {
+            self.pos += 1;
+            has_digits |= digits(&mut self.pos);
+        }
+        if !has_digits {
+            self.pos = start;
+            return Err(self.error("expected a number"));
+        }
+        // exponent, but not the start of a following command
+        if self.pos < data.len() && matches!(data[self.pos], b'e' | b'E') {
+            let mut exp = self.pos + 1;
+            if exp < data.len() && matches!(data[exp], b'-' | b'+') {
+                exp += 1;
+            }
+            if digits(&mut exp) {
+                self.pos = exp;
+            }
+        }
+
+        let text = std::str::from_utf8(&data[start..self.pos]).unwrap();
+        text.parse().map_err(|_| ParseError {
+            position: start,
+            message: format!("invalid number '{}'", text),
+        })
+    }
+
+    // arc flags may be written without separators, as in "a1 1 0 00 2 2"
+    fn read_flag(&mut self) -> Result<bool, ParseError> {
+        self.skip_separators();
+        match self.data.get(self.pos) {
+            Some(b'0') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(self.error("expected an arc flag")),
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.pos,
+            message: String::from(message),
+        }
+    }
+}
+
+/// Cubics approximating the SVG elliptical arc from `p0` to `p1`.
+/// `rotation` is the x-axis rotation in degrees.
+pub fn arc_to_cubics(
+    p0: Point,
+    rx: f64,
+    ry: f64,
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+    p1: Point,
+) -> Vec<BezierCurve<f64>> {
+    use std::f64::consts::{FRAC_PI_2, TAU};
+
+    // endpoint to center parameterization, SVG 1.1 appendix F.6.5
+    let (sin_phi, cos_phi) = rotation.to_radians().sin_cos();
+    let rotate =
+        |v: Point| Point::new(cos_phi * v.x - sin_phi * v.y, sin_phi * v.x + cos_phi * v.y);
+    let half = (p0 - p1) * 0.5;
+    let x1 = cos_phi * half.x + sin_phi * half.y;
+    let y1 = -sin_phi * half.x + cos_phi * half.y;
+
+    // scale up radii too small to reach the endpoint
+    let mut rx = rx.abs();
+    let mut ry = ry.abs();
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+    let center = rotate(Point::new(cx1, cy1)) + p0.lerp(&p1, 0.5);
+
+    let angle = |ux: f64, uy: f64| uy.atan2(ux);
+    let theta = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut delta = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - theta;
+    if sweep && delta < 0.0 {
+        delta += TAU;
+    } else if !sweep && delta > 0.0 {
+        delta -= TAU;
+    }
+
+    // unit-circle cubics, mapped onto the ellipse
+    let pieces = (delta.abs() / FRAC_PI_2 - 1e-9).ceil().max(1.0) as usize;
+    let step = delta / pieces as f64;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan();
+    let ellipse = |unit: Point| center + rotate(Point::new(unit.x * rx, unit.y * ry));
+
+    let mut curves = Vec::with_capacity(pieces);
+    let mut start = p0;
+    for k in 0..pieces {
+        let a0 = theta + step * k as f64;
+        let a1 = a0 + step;
+        let u0 = Point::from_angle(a0);
+        let u1 = Point::from_angle(a1);
+        let end = if k + 1 == pieces { p1 } else { ellipse(u1) };
+        curves.push(BezierCurve {
+            control_pts: [
+                start,
+                ellipse(u0 + u0.perp() * handle),
+                ellipse(u1 - u1.perp() * handle),
+                end,
+            ],
+        });
+        start = end;
+    }
+    curves
+}
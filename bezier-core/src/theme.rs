@@ -0,0 +1,308 @@
+// Colors of the editor canvas shared by the front-ends
+//
+// Three themes are built in: `light` (the colors of the original editor), `dark` and
+// `colorblind`, whose arc and box colors come from the Okabe-Ito palette so the
+// alternating arcs stay apart under the common color vision deficiencies.
+// A theme file has one color per line, applied over the built-in theme of its
+// `base` line (`light` without one), which therefore comes first:
+//
+//     # comment
+//     name = solarized
+//     base = dark
+//     background = #002b36
+//     boxes = #268bd2, #2aa198, #859900
+//
+// Colors are `#rrggbb` or `#rrggbbaa`; comments take a whole line. `boxes` and
+// `construction` take a list of colors.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::util::{rgba8, Rgba};
+
+/// Names of the built-in themes, in the order the front-ends cycle through them
+pub const THEMES: [&str; 3] = ["light", "dark", "colorblind"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background: Rgba,
+    pub curve: Rgba,
+    /// Selected curves and the construction tangent
+    pub selected: Rgba,
+    pub mesh: Rgba,
+    pub control_point: Rgba,
+    /// Alternating colors of the arcs along a curve
+    pub left_arc: Rgba,
+    pub right_arc: Rgba,
+    /// Box colors by tree depth from the root, repeated for deeper levels
+    pub boxes: Vec<Rgba>,
+    /// Picked arc tree node and its subtree
+    pub highlight: Rgba,
+    /// De Casteljau polygons of the construction, from the control polygon down
+    pub construction: Vec<Rgba>,
+    /// Rubber band of the box selection, filled at a tenth of its alpha
+    pub selection: Rgba,
+    pub grid: Rgba,
+    pub grid_axis: Rgba,
+    pub arc_circle: Rgba,
+    pub joint_circle: Rgba,
+    pub tangent: Rgba,
+    /// Text and background of the help overlay
+    pub text: Rgba,
+    pub panel: Rgba,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+impl Theme {
+    /// Colors of the original editor
+    pub fn light() -> Theme {
+        Theme {
+            name: String::from("light"),
+            background: rgba8(255, 255, 255, 1.0),
+            curve: rgba8(0, 0, 0, 1.0),
+            selected: rgba8(255, 140, 0, 1.0),
+            mesh: rgba8(20, 210, 0, 1.0),
+            control_point: rgba8(255, 0, 0, 1.0),
+            left_arc: rgba8(40, 210, 0, 1.0),
+            right_arc: rgba8(30, 0, 210, 1.0),
+            boxes: vec![
+                rgba8(0, 30, 220, 1.0),
+                rgba8(0, 140, 200, 1.0),
+                rgba8(120, 0, 200, 1.0),
+                rgba8(0, 160, 120, 1.0),
+            ],
+            highlight: rgba8(230, 0, 120, 1.0),
+            construction: vec![
+                rgba8(150, 150, 150, 1.0),
+                rgba8(0, 150, 200, 1.0),
+                rgba8(150, 0, 200, 1.0),
+            ],
+            selection: rgba8(30, 120, 255, 1.0),
+            grid: rgba8(220, 220, 220, 1.0),
+            grid_axis: rgba8(160, 160, 160, 1.0),
+            arc_circle: rgba8(120, 120, 120, 0.6),
+            joint_circle: rgba8(200, 120, 0, 0.8),
+            tangent: rgba8(0, 150, 200, 0.8),
+            text: rgba8(0, 0, 0, 1.0),
+            panel: rgba8(255, 255, 255, 0.92),
+        }
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            name: String::from("dark"),
+            background: rgba8(30, 30, 34, 1.0),
+            curve: rgba8(230, 230, 230, 1.0),
+            selected: rgba8(255, 170, 40, 1.0),
+            mesh: rgba8(90, 200, 90, 1.0),
+            control_point: rgba8(255, 80, 80, 1.0),
+            left_arc: rgba8(110, 230, 80, 1.0),
+            right_arc: rgba8(120, 140, 255, 1.0),
+            boxes: vec![
+                rgba8(90, 130, 255, 1.0),
+                rgba8(60, 200, 230, 1.0),
+                rgba8(200, 120, 255, 1.0),
+                rgba8(60, 220, 170, 1.0),
+            ],
+            highlight: rgba8(255, 80, 170, 1.0),
+            construction: vec![
+                rgba8(140, 140, 145, 1.0),
+                rgba8(60, 190, 240, 1.0),
+                rgba8(200, 120, 255, 1.0),
+            ],
+            selection: rgba8(90, 150, 255, 1.0),
+            grid: rgba8(55, 55, 60, 1.0),
+            grid_axis: rgba8(95, 95, 100, 1.0),
+            arc_circle: rgba8(160, 160, 160, 0.6),
+            joint_circle: rgba8(230, 150, 40, 0.8),
+            tangent: rgba8(60, 190, 240, 0.8),
+            text: rgba8(235, 235, 235, 1.0),
+            panel: rgba8(20, 20, 24, 0.92),
+        }
+    }
+
+    /// Okabe-Ito colors: blue and orange arcs, vermillion points
+    pub fn colorblind() -> Theme {
+        Theme {
+            name: String::from("colorblind"),
+            selected: rgba8(204, 121, 167, 1.0),
+            mesh: rgba8(0, 158, 115, 1.0),
+            control_point: rgba8(213, 94, 0, 1.0),
+            left_arc: rgba8(0, 114, 178, 1.0),
+            right_arc: rgba8(230, 159, 0, 1.0),
+            boxes: vec![
+                rgba8(86, 180, 233, 1.0),
+                rgba8(0, 158, 115, 1.0),
+                rgba8(204, 121, 167, 1.0),
+                rgba8(240, 228, 66, 1.0),
+            ],
+            highlight: rgba8(204, 121, 167, 1.0),
+            construction: vec![
+                rgba8(150, 150, 150, 1.0),
+                rgba8(86, 180, 233, 1.0),
+                rgba8(204, 121, 167, 1.0),
+            ],
+            selection: rgba8(0, 114, 178, 1.0),
+            joint_circle: rgba8(230, 159, 0, 0.8),
+            tangent: rgba8(86, 180, 233, 0.8),
+            ..Theme::light()
+        }
+    }
+
+    /// Built-in theme by name
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "light" => Some(Theme::light()),
+            "dark" => Some(Theme::dark()),
+            "colorblind" => Some(Theme::colorblind()),
+            _ => None,
+        }
+    }
+
+    /// Light theme with the colors of `text` applied over it
+    pub fn parse(text: &str) -> Result<Theme, ThemeError> {
+        let mut theme = Theme::light();
+        theme.name = String::from("custom");
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: String| ThemeError::Parse {
+                line: i + 1,
+                message,
+            };
+            // colors start with `#` too, so only whole lines are comments
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.find('=') {
+                Some(at) => (line[..at].trim(), line[at + 1..].trim()),
+                None => return Err(error(format!("expected `key = value` in `{}`", line))),
+            };
+            match key {
+                "name" => theme.name = value.to_string(),
+                "base" => {
+                    let name = theme.name.clone();
+                    theme = Theme::named(value)
+                        .ok_or_else(|| error(format!("unknown theme `{}`", value)))?;
+                    theme.name = name;
+                }
+                "boxes" | "construction" => {
+                    let colors: Vec<Rgba> = value
+                        .split(',')
+                        .map(|color| parse_color(color.trim()))
+                        .collect::<Option<_>>()
+                        .ok_or_else(|| error(format!("bad colors `{}`", value)))?;
+                    if colors.is_empty() {
+                        return Err(error(format!("no {} colors", key)));
+                    }
+                    if key == "boxes" {
+                        theme.boxes = colors;
+                    } else {
+                        theme.construction = colors;
+                    }
+                }
+                _ => {
+                    let field = theme
+                        .color_mut(key)
+                        .ok_or_else(|| error(format!("unknown color `{}`", key)))?;
+                    *field = parse_color(value)
+                        .ok_or_else(|| error(format!("bad color `{}`", value)))?;
+                }
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Theme, ThemeError> {
+        Theme::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Color of the boxes at `depth` from the root
+    pub fn box_color(&self, depth: usize) -> Rgba {
+        self.boxes[depth % self.boxes.len()]
+    }
+
+    /// Color of the de Casteljau polygon `level` steps below the control polygon,
+    /// the last color repeated for deeper levels
+    pub fn construction_color(&self, level: usize) -> Rgba {
+        self.construction[level.min(self.construction.len() - 1)]
+    }
+
+    /// Color of the `index`-th arc along a curve
+    pub fn arc_color(&self, index: usize) -> Rgba {
+        match index % 2 {
+            0 => self.left_arc,
+            _ => self.right_arc,
+        }
+    }
+
+    fn color_mut(&mut self, key: &str) -> Option<&mut Rgba> {
+        Some(match key {
+            "background" => &mut self.background,
+            "curve" => &mut self.curve,
+            "selected" => &mut self.selected,
+            "mesh" => &mut self.mesh,
+            "control-point" => &mut self.control_point,
+            "left-arc" => &mut self.left_arc,
+            "right-arc" => &mut self.right_arc,
+            "highlight" => &mut self.highlight,
+            "selection" => &mut self.selection,
+            "grid" => &mut self.grid,
+            "grid-axis" => &mut self.grid_axis,
+            "arc-circle" => &mut self.arc_circle,
+            "joint-circle" => &mut self.joint_circle,
+            "tangent" => &mut self.tangent,
+            "text" => &mut self.text,
+            "panel" => &mut self.panel,
+            _ => return None,
+        })
+    }
+}
+
+// `#rrggbb` or `#rrggbbaa`
+fn parse_color(text: &str) -> Option<Rgba> {
+    let hex = text.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(rgba8(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        alpha as f32 / 255.0,
+    ))
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(std::io::Error),
+    /// Malformed line at a 1-based line
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::Io(err) => write!(f, "{}", err),
+            ThemeError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+impl From<std::io::Error> for ThemeError {
+    fn from(err: std::io::Error) -> Self {
+        ThemeError::Io(err)
+    }
+}
@@ -26,6 +26,10 @@ impl<T> Tree<T> {
         self.nodes.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
     pub fn get(&self, index: usize) -> Option<&Node<T>> {
         self.nodes.get(index)
     }
@@ -39,7 +43,7 @@ impl<T> Tree<T> {
             parent: None,
             left: None,
             right: None,
-            value: value,
+            value,
         };
         self.nodes.push(node);
         self.nodes.len()
@@ -74,12 +78,12 @@ impl<T> Tree<T> {
             return None;
         }
         let child_id = self.new_node(value);
-        let mut parent = self.get_mut(parent_id).unwrap();
+        let parent = self.get_mut(parent_id).unwrap();
         parent.left = Some(child_id);
-        let mut child = self.get_mut(child_id).unwrap();
+        let child = self.get_mut(child_id).unwrap();
         child.parent = Some(parent_id);
 
-        return Some(child_id);
+        Some(child_id)
     }
 
     pub fn set_right(&mut self, parent_id: usize, value: T) -> Option<usize> {
@@ -87,20 +91,20 @@ impl<T> Tree<T> {
             return None;
         }
         let child_id = self.new_node(value);
-        let mut parent = self.get_mut(parent_id).unwrap();
+        let parent = self.get_mut(parent_id).unwrap();
         parent.right = Some(child_id);
-        let mut child = self.get_mut(child_id).unwrap();
+        let child = self.get_mut(child_id).unwrap();
         child.parent = Some(parent_id);
 
-        return Some(child_id);
+        Some(child_id)
     }
 
     pub fn post_trav<F>(tree_cell: Rc<RefCell<Tree<T>>>, mut f: F)
     where
         F: FnMut(usize),
     {
-        if tree_cell.borrow().len() > 0 {
-            Tree::post_trav_inner(tree_cell.clone(), 0, &mut f);
+        if !tree_cell.borrow().is_empty() {
+            Tree::post_trav_inner(tree_cell, 0, &mut f);
         }
     }
 
@@ -128,7 +132,7 @@ impl<T> Tree<T> {
         }
 
         if right_idx > 0 {
-            Tree::post_trav_inner(tree_cell.clone(), right_idx, f);
+            Tree::post_trav_inner(tree_cell, right_idx, f);
         }
 
         f(idx);
@@ -164,7 +168,7 @@ impl<T> Tree<T> {
         Self { nodes }
     }
 
-    pub fn set_new_complete<F>(&mut self, depth: usize, builder: F) -> ()
+    pub fn set_new_complete<F>(&mut self, depth: usize, builder: F)
     where
         F: Fn(usize) -> T,
     {
@@ -173,6 +177,42 @@ impl<T> Tree<T> {
     }
 }
 
+/// Depth of the node `index` in a tree built by `new_complete` (root is 0)
+pub fn complete_depth(index: usize) -> usize {
+    let mut depth = 0;
+    while (index + 1) >> (depth + 1) > 0 {
+        depth += 1;
+    }
+    depth
+}
+
+/// Parent of the node `index` in a tree built by `new_complete`
+pub fn complete_parent(index: usize) -> Option<usize> {
+    if index == 0 {
+        None
+    } else {
+        Some((index - 1) / 2)
+    }
+}
+
+/// Left and right child of the node `index` in a tree of `len` nodes built by `new_complete`
+pub fn complete_children(index: usize, len: usize) -> Option<(usize, usize)> {
+    let left = index * 2 + 1;
+    if left + 1 < len {
+        Some((left, left + 1))
+    } else {
+        None
+    }
+}
+
+/// Whether the node `index` is `root` or one of its descendants, in a tree built by `new_complete`
+pub fn in_complete_subtree(mut index: usize, root: usize) -> bool {
+    while index > root {
+        index = (index - 1) / 2;
+    }
+    index == root
+}
+
 impl<T> Deref for Node<T> {
     type Target = T;
     fn deref(&self) -> &T {
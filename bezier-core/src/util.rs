@@ -0,0 +1,81 @@
+use crate::point::Vec2;
+use crate::scalar::Scalar;
+
+pub const RESOLUTION: usize = 100;
+pub const RES_4: usize = RESOLUTION / 4;
+pub const PTS_RADIUS: f32 = 3.0;
+
+/// RGBA, each channel in `[0, 1]`
+pub type Rgba = [f32; 4];
+
+/// 8-bit channels to `Rgba`
+pub fn rgba8(r: u8, g: u8, b: u8, a: f32) -> Rgba {
+    [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a]
+}
+
+pub fn point_clear<S: Scalar>(point: &mut Vec2<S>) {
+    point.x = S::ZERO;
+    point.y = S::ZERO;
+}
+
+pub fn point_add_weight_vec<S: Scalar>(point: &mut Vec2<S>, weight: S, vec: &Vec2<S>) {
+    point.x += weight * vec.x;
+    point.y += weight * vec.y;
+}
+
+/// Calculate distance of two vector
+pub fn distance<S: Scalar>(p0: &Vec2<S>, p1: &Vec2<S>) -> S {
+    let x = p1.x - p0.x;
+    let y = p1.y - p0.y;
+    (x * x + y * y).sqrt()
+}
+
+/// Calculate angle aligned to +x-axis
+/// return (-pi, pi]
+pub fn point_angle<S: Scalar>(center: &Vec2<S>, vec: &Vec2<S>) -> S {
+    let angle = (vec.y - center.y).atan2(vec.x - center.x);
+    // atan2 gives -pi for (-x, -0.0)
+    if angle <= -S::PI {
+        S::PI
+    } else {
+        angle
+    }
+}
+
+/// Calculate the angle between two vectors.
+/// return [0, pi]
+pub fn vec_angle<S: Scalar>(v0: &Vec2<S>, v1: &Vec2<S>) -> S {
+    let origin = Vec2::zero();
+    let theta_1 = point_angle(&origin, v0);
+    let theta_2 = point_angle(&origin, v1);
+
+    let mut theta = (theta_2 - theta_1).abs();
+    if theta > S::PI {
+        theta = S::TAU - theta;
+    }
+    theta
+}
+
+pub fn norm<S: Scalar>(point: &Vec2<S>) -> S {
+    (point.x * point.x + point.y * point.y).sqrt()
+}
+
+pub fn normalize<S: Scalar>(point: &mut Vec2<S>) {
+    let len = norm(point);
+    point.x /= len;
+    point.y /= len;
+}
+
+// calculate distance of angle (<= pi)
+pub fn diff_angle<S: Scalar>(angle0: S, angle1: S) -> S {
+    let result = if angle0 > angle1 {
+        angle0 - angle1
+    } else {
+        angle1 - angle0
+    };
+    if result > S::PI {
+        S::TAU - result
+    } else {
+        result
+    }
+}
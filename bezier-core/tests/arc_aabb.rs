@@ -0,0 +1,132 @@
+// Exhaustive comparison of arc bounding boxes against densely sampled arcs,
+// over every quadrant combination of start / end angle and both sweep directions.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use bezier_core::biarc::*;
+use bezier_core::point::Vec2;
+
+const SAMPLES: usize = 720;
+
+/// Start angles on a 7.5 degree grid, plus the quadrant boundaries themselves
+fn angles() -> Vec<f64> {
+    let mut angles: Vec<f64> = (-24..=24).map(|i| i as f64 * PI / 24.0).collect();
+    angles.extend(&[0.0, FRAC_PI_2, -FRAC_PI_2, PI, -PI + 1e-12]);
+    angles
+}
+
+fn sample_bounds(center: &Vec2<f64>, radius: f64, start: f64, sweep: f64) -> AABB<f64> {
+    let point = |angle: f64| *center + Vec2::from_angle(angle) * radius;
+    let mut aabb = AABB::new_point(point(start), point(start));
+    for i in 1..=SAMPLES {
+        let p = point(start + sweep * i as f64 / SAMPLES as f64);
+        aabb.merge(&AABB::new_point(p, p));
+    }
+    aabb
+}
+
+/// `outer` contains `inner`, and no side is farther than `slack` away
+fn assert_tight(outer: &AABB<f64>, inner: &AABB<f64>, slack: f64, context: &str) {
+    let eps = 1e-9;
+    let sides = [
+        (inner.x - outer.x, "left"),
+        (inner.y - outer.y, "bottom"),
+        ((outer.x + outer.w) - (inner.x + inner.w), "right"),
+        ((outer.y + outer.h) - (inner.y + inner.h), "top"),
+    ];
+    for (gap, side) in sides.iter() {
+        assert!(
+            *gap >= -eps,
+            "{}: {} side misses the arc by {}",
+            context,
+            side,
+            -gap
+        );
+        assert!(
+            *gap <= slack + eps,
+            "{}: {} side is loose by {}",
+            context,
+            side,
+            gap
+        );
+    }
+}
+
+#[test]
+fn from_arc_matches_samples_for_any_sweep() {
+    let center = Vec2::new(12.5, -40.0);
+    let radius = 30.0;
+    // worst-case distance between an extreme point and the nearest sample
+    let slack = radius * (1.0 - (TAU / SAMPLES as f64).cos());
+
+    for &start in &angles() {
+        for i in -36..=36 {
+            let sweep = i as f64 * TAU / 36.0;
+            let aabb = AABB::from_arc(&center, radius, start, sweep);
+            let sampled = sample_bounds(&center, radius, start, sweep);
+            let context = format!("start {} sweep {}", start, sweep);
+            assert_tight(&aabb, &sampled, slack, &context);
+        }
+    }
+}
+
+#[test]
+fn from_arc_full_circle() {
+    let center = Vec2::new(1.0, 2.0);
+    for &sweep in &[TAU, -TAU, 3.0 * TAU] {
+        let aabb = AABB::from_arc(&center, 2.0, 0.3, sweep);
+        assert!((aabb.x - -1.0).abs() < 1e-12);
+        assert!((aabb.y - 0.0).abs() < 1e-12);
+        assert!((aabb.w - 4.0).abs() < 1e-12);
+        assert!((aabb.h - 4.0).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn sweep_contains_boundaries() {
+    assert!(sweep_contains(0.0, FRAC_PI_2, 0.0));
+    assert!(sweep_contains(0.0, FRAC_PI_2, FRAC_PI_2));
+    assert!(!sweep_contains(0.0, FRAC_PI_2, PI));
+    assert!(sweep_contains(0.0, -FRAC_PI_2, -FRAC_PI_2));
+    assert!(!sweep_contains(0.0, -FRAC_PI_2, FRAC_PI_2));
+    // wrapping through +-pi
+    assert!(sweep_contains(3.0, 1.0, -PI));
+    assert!(sweep_contains(-3.0, -1.0, PI));
+    assert!(!sweep_contains(3.0, -1.0, -PI));
+}
+
+#[test]
+fn arc_data_aabb_matches_samples_for_all_quadrants() {
+    let center = Vec2::new(-5.0, 7.0);
+    let radius = 10.0;
+    let slack = radius * (1.0 - (PI / SAMPLES as f64).cos());
+
+    // every start angle, both directions, sweeps up to a full turn
+    for &start in &angles() {
+        for i in 1..24 {
+            for &dir in &[1.0, -1.0] {
+                let sweep = dir * i as f64 * PI / 12.0;
+                let arc = ArcData {
+                    center,
+                    radius,
+                    start,
+                    sweep,
+                };
+
+                let sampled = sample_bounds(&center, radius, start, sweep);
+                let context = format!("start {} sweep {}", start, sweep);
+                assert_tight(&arc.aabb(), &sampled, slack, &context);
+            }
+        }
+    }
+}
+
+#[test]
+fn rounded_area_of_a_box() {
+    let aabb = AABB::new_point(Vec2::new(0.0, 0.0), Vec2::new(4.0, 2.0));
+    assert_eq!(aabb.rounded_area(0.0), 8.0);
+    // 8 + edges 2 * (4 + 2) + a full circle of radius 1
+    assert!((aabb.rounded_area(1.0) - (20.0 + PI)).abs() < 1e-12);
+    let point = AABB::new_point(Vec2::new(1.0, 1.0), Vec2::new(1.0, 1.0));
+    assert!((point.rounded_area(2.0) - 4.0 * PI).abs() < 1e-12);
+}
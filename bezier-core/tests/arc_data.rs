@@ -0,0 +1,104 @@
+// Conversions and per-parameter helpers of `ArcData`.
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use bezier_core::biarc::*;
+use bezier_core::point::Vec2;
+use bezier_core::util::distance;
+
+const EPS: f64 = 1e-9;
+
+fn close(a: &Vec2<f64>, b: &Vec2<f64>) -> bool {
+    distance(a, b) < EPS
+}
+
+#[test]
+fn from_endpoints_follows_sweep_sign() {
+    let center = Vec2::new(0.0, 0.0);
+    let from = Vec2::new(1.0, 0.0);
+    let to = Vec2::new(0.0, 1.0);
+
+    let ccw = ArcData::from_endpoints(center, &from, &to, 1.0);
+    assert!(ccw.is_ccw());
+    assert!((ccw.sweep - FRAC_PI_2).abs() < EPS);
+    assert!(close(&ccw.mid_point(), &Vec2::from_angle(PI / 4.0)));
+
+    let cw = ArcData::from_endpoints(center, &from, &to, -1.0);
+    assert!(!cw.is_ccw());
+    assert!((cw.sweep + 3.0 * FRAC_PI_2).abs() < EPS);
+    assert!(close(&cw.mid_point(), &Vec2::from_angle(-3.0 * PI / 4.0)));
+
+    for arc in &[ccw, cw] {
+        assert!(close(&arc.start_point(), &from));
+        assert!(close(&arc.end_point(), &to));
+    }
+}
+
+#[test]
+fn tangent_follows_sweep_direction() {
+    let arc: ArcData<f64> = ArcData {
+        center: Vec2::new(2.0, -1.0),
+        radius: 3.0,
+        start: 0.5,
+        sweep: -2.0,
+    };
+    for i in 0..=8 {
+        let t = i as f64 / 8.0;
+        let h = 1e-6;
+        let forward = arc.point_at(t + h) - arc.point_at(t - h);
+        let tangent = arc.tangent_at(t);
+        assert!((tangent.dot(&tangent) - 1.0).abs() < EPS);
+        assert!(tangent.cross(&forward).abs() < 1e-6);
+        assert!(tangent.dot(&forward) > 0.0);
+    }
+}
+
+#[test]
+fn bulge_round_trip() {
+    let p0 = Vec2::new(-3.0, 4.0);
+    let p1 = Vec2::new(5.0, 1.0);
+    for &bulge in &[0.1, 0.5, 1.0, 2.5, -0.3, -1.0, -4.0] {
+        let arc = ArcData::from_bulge(&p0, &p1, bulge).unwrap();
+        assert!(close(&arc.start_point(), &p0), "bulge {}", bulge);
+        assert!(close(&arc.end_point(), &p1), "bulge {}", bulge);
+        assert_eq!(arc.is_ccw(), bulge > 0.0);
+
+        let (q0, q1, b) = arc.to_bulge();
+        assert!(close(&q0, &p0) && close(&q1, &p1));
+        assert!((b - bulge).abs() < 1e-9, "bulge {} -> {}", bulge, b);
+    }
+
+    assert!(ArcData::from_bulge(&p0, &p1, 0.0).is_none());
+    assert!(ArcData::from_bulge(&p0, &p0, 1.0).is_none());
+}
+
+#[test]
+fn length_and_containment() {
+    let arc: ArcData<f64> = ArcData {
+        center: Vec2::new(0.0, 0.0),
+        radius: 2.0,
+        start: 3.0,
+        sweep: 1.0,
+    };
+    assert!((arc.length() - 2.0).abs() < EPS);
+    assert!((arc.end() - 4.0).abs() < EPS);
+    // wraps through +-pi
+    assert!(arc.contains_angle(-PI + 0.1));
+    assert!(!arc.contains_angle(0.0));
+    assert_eq!(arc.sample(4).len(), 5);
+}
+
+#[test]
+fn distance_to_arc_or_endpoint() {
+    // upper half circle, counter-clockwise
+    let arc: ArcData<f64> = ArcData {
+        center: Vec2::new(0.0, 0.0),
+        radius: 1.0,
+        start: 0.0,
+        sweep: PI,
+    };
+    assert!((arc.distance(&Vec2::new(0.0, 3.0)) - 2.0).abs() < EPS);
+    assert!((arc.distance(&Vec2::new(0.0, 0.5)) - 0.5).abs() < EPS);
+    // below the arc the nearest point is an endpoint
+    assert!((arc.distance(&Vec2::new(1.0, -1.0)) - 1.0).abs() < EPS);
+}
@@ -0,0 +1,207 @@
+// Command line of the bezier-fit binary: options reach the written output.
+
+use std::process::Command;
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::path::BezierPath;
+use bezier_core::point::Vec2;
+use bezier_core::project::{Display, Project};
+
+const LINE: &str = "0,0,10,0,20,0,30,0";
+
+fn bezier_fit(args: &[&str]) -> (bool, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_bezier-fit"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn gcode_defaults() {
+    let (ok, gcode, _) = bezier_fit(&["--points", LINE, "--format", "gcode"]);
+    assert!(ok);
+    assert!(gcode.starts_with("G21"));
+    assert!(gcode.contains("G0 Z5\n"));
+    assert!(gcode.contains("G1 Z-1 F100\n"));
+    assert!(gcode.contains("G1 X30 Y0 F600"));
+}
+
+#[test]
+fn gcode_options() {
+    let (ok, gcode, stderr) = bezier_fit(&[
+        "--points",
+        "0,0,10,10,20,10,30,0",
+        "--format",
+        "gcode",
+        "--units",
+        "in",
+        "--feed",
+        "1200",
+        "--plunge",
+        "50",
+        "--safe-z",
+        "2.5",
+        "--cut-z",
+        "-0.5",
+        "--precision",
+        "1",
+        "--flip-y",
+        "off",
+    ]);
+    assert!(ok, "{}", stderr);
+    assert!(gcode.starts_with("G20 ; inches"));
+    assert!(gcode.contains("G0 Z2.5\n"));
+    assert!(gcode.contains("G1 Z-0.5 F50\n"));
+    assert!(gcode.contains(" F1200"));
+    // one digit after the point, y kept up
+    for line in gcode
+        .lines()
+        .filter(|line| line.starts_with("G2") || line.starts_with("G3"))
+    {
+        for word in line.split(' ').skip(1) {
+            let digits = word[1..].split('.').nth(1).map_or(0, str::len);
+            assert!(digits <= 1, "{}", line);
+        }
+        assert!(!line.contains("Y-"), "{}", line);
+    }
+}
+
+#[test]
+fn gcode_offset() {
+    let (_, plain, _) = bezier_fit(&["--points", LINE, "--format", "gcode", "--flip-y", "off"]);
+    let (ok, offset, _) = bezier_fit(&[
+        "--points", LINE, "--format", "gcode", "--flip-y", "off", "--offset", "2",
+    ]);
+    assert!(ok);
+    assert!(plain.contains("G0 X0 Y0\n"));
+    assert!(offset.contains("G0 X0 Y2\n"), "{}", offset);
+}
+
+#[test]
+fn invalid_gcode_options() {
+    for args in [
+        ["--units", "ft"],
+        ["--feed", "0"],
+        ["--flip-y", "maybe"],
+        ["--safe-z", "-2"],
+    ] {
+        let mut argv = vec!["--points", LINE, "--format", "gcode"];
+        argv.extend_from_slice(&args);
+        let (ok, _, stderr) = bezier_fit(&argv);
+        assert!(!ok);
+        assert!(stderr.starts_with("bezier-fit: "), "{}", stderr);
+    }
+}
+
+#[test]
+fn split_is_capped() {
+    for args in [["--split", "17"], ["--max-split", "64"]] {
+        let mut argv = vec!["--points", LINE];
+        argv.extend_from_slice(&args);
+        let (ok, _, stderr) = bezier_fit(&argv);
+        assert!(!ok);
+        assert!(stderr.contains("at most 16"), "{}", stderr);
+    }
+}
+
+#[test]
+fn project_curves_keep_their_display() {
+    let line =
+        |y: f64| BezierPath::from_curve(BezierCurve::line(Vec2::new(0.0, y), Vec2::new(30.0, y)));
+    let project = Project {
+        curves: vec![line(0.0), line(10.0), line(20.0)],
+        curve_display: vec![
+            Display {
+                num_split: 3,
+                aabb_depth: 2,
+                ..Display::default()
+            },
+            Display {
+                num_split: 2,
+                aabb_depth: 0,
+                ..Display::default()
+            },
+        ],
+        ..Project::default()
+    };
+    let file = std::env::temp_dir().join(format!("bezier-fit-{}.json", std::process::id()));
+    project.save(&file).unwrap();
+
+    let (ok, json, stderr) = bezier_fit(&["--project", file.to_str().unwrap()]);
+    let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let (_, overridden, _) = bezier_fit(&["--project", file.to_str().unwrap(), "--split", "0"]);
+    std::fs::remove_file(&file).unwrap();
+    assert!(ok, "{}", stderr);
+    assert!(stderr.starts_with("split 1 to 3:"), "{}", stderr);
+
+    // the third curve has no entry and uses the project display
+    let curves = document["curves"].as_array().unwrap();
+    let count = |i: usize, key: &str| curves[i][key].as_array().unwrap().len();
+    assert_eq!(document["split"], 3);
+    assert_eq!(
+        [count(0, "arcs"), count(1, "arcs"), count(2, "arcs")],
+        [16, 8, 4]
+    );
+    assert_eq!(
+        [count(0, "boxes"), count(1, "boxes"), count(2, "boxes")],
+        [24, 0, 4]
+    );
+
+    let document: serde_json::Value = serde_json::from_str(&overridden).unwrap();
+    for curve in document["curves"].as_array().unwrap() {
+        assert_eq!(curve["split"], 0);
+        assert_eq!(curve["arcs"].as_array().unwrap().len(), 2);
+    }
+}
+
+/// Project shared with the editor's export test, and the expected exports of it.
+/// Run with `BLESS=1` to rewrite the expected files.
+fn golden(name: &str) -> std::path::PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect()
+}
+
+#[test]
+fn project_exports_match_the_editor() {
+    let project = golden("export_project.json");
+    for &(format, name) in &[
+        ("samples-csv", "export_samples.csv"),
+        ("leaves-csv", "export_leaves.csv"),
+        ("samples-json", "export_samples.json"),
+        ("gcode", "export.nc"),
+    ] {
+        let (ok, output, stderr) = bezier_fit(&[
+            "--project",
+            project.to_str().unwrap(),
+            "--samples",
+            "32",
+            "--format",
+            format,
+        ]);
+        assert!(ok, "{}", stderr);
+        if std::env::var_os("BLESS").is_some() {
+            std::fs::write(golden(name), &output).unwrap();
+            continue;
+        }
+        let expected = std::fs::read_to_string(golden(name)).expect("run with BLESS=1");
+        assert_eq!(output, expected, "{}", format);
+    }
+    // world coordinates, y-up like the project
+    let (_, samples, _) = bezier_fit(&[
+        "--project",
+        project.to_str().unwrap(),
+        "--format",
+        "samples-csv",
+    ]);
+    assert!(
+        samples.lines().nth(1).unwrap().contains(",20,40,"),
+        "{}",
+        samples
+    );
+}
@@ -0,0 +1,43 @@
+// Error statistics of the fitted biarcs.
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+
+fn fit(split_num: usize) -> FittedPath<f64> {
+    let curve = BezierCurve {
+        control_pts: [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(200.0, 100.0),
+            Vec2::new(200.0, 300.0),
+        ],
+    };
+    FittedPath::new(
+        BezierPath::from_curve(curve),
+        split_num,
+        false,
+        &Tolerance::default(),
+    )
+}
+
+#[test]
+fn deviation_shrinks_with_splits() {
+    let coarse = fit(1).deviation(64);
+    let fine = fit(4).deviation(64);
+    assert_eq!(coarse.samples, 65);
+    assert!(fine.max < coarse.max / 4.0, "{:?} -> {:?}", coarse, fine);
+    assert!(fine.max < 0.5);
+    for d in &[coarse, fine] {
+        assert!(d.mean <= d.rms && d.rms <= d.max);
+    }
+}
+
+#[test]
+fn biarc_endpoints_have_no_deviation() {
+    // one sample at each end of the segment
+    let deviation = fit(2).deviation(1);
+    assert_eq!(deviation.samples, 2);
+    assert!(deviation.max < 1e-6);
+}
@@ -0,0 +1,193 @@
+// DXF entities of fitted arc chains, read back as group code / value pairs.
+
+use std::f64::consts::FRAC_PI_2;
+
+use bezier_core::biarc::ArcData;
+use bezier_core::dxf::*;
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::util::distance;
+
+fn pairs(dxf: &str) -> Vec<(u32, String)> {
+    let lines: Vec<&str> = dxf.lines().collect();
+    assert_eq!(lines.len() % 2, 0);
+    lines
+        .chunks(2)
+        .map(|pair| (pair[0].trim().parse().unwrap(), pair[1].to_string()))
+        .collect()
+}
+
+/// Group codes and values of every entity of `kind`
+fn entities(dxf: &str, kind: &str) -> Vec<Vec<(u32, String)>> {
+    let mut entities = Vec::new();
+    let mut current: Option<Vec<(u32, String)>> = None;
+    let mut in_entities = false;
+    for (code, value) in pairs(dxf) {
+        if code == 2 && value == "ENTITIES" {
+            in_entities = true;
+        }
+        if code == 0 {
+            if let Some(entity) = current.take() {
+                entities.push(entity);
+            }
+            if in_entities && value == kind {
+                current = Some(Vec::new());
+            }
+        } else if let Some(ref mut entity) = current {
+            entity.push((code, value));
+        }
+    }
+    entities
+}
+
+fn values(entity: &[(u32, String)], code: u32) -> Vec<f64> {
+    entity
+        .iter()
+        .filter(|(c, _)| *c == code)
+        .map(|(_, v)| v.parse().unwrap())
+        .collect()
+}
+
+fn fitted(split_num: usize) -> FittedPath<f64> {
+    FittedPath::new(
+        BezierPath::default(),
+        split_num,
+        false,
+        &Tolerance::default(),
+    )
+}
+
+#[test]
+fn document_structure() {
+    let dxf = export_dxf(&[fitted(1)], &DxfOptions::default());
+    let pairs = pairs(&dxf);
+    assert_eq!(pairs.first().unwrap(), &(0, String::from("SECTION")));
+    assert_eq!(pairs.last().unwrap(), &(0, String::from("EOF")));
+    let layers = pairs
+        .iter()
+        .filter(|(code, value)| *code == 0 && value == "LAYER");
+    assert_eq!(layers.count(), 3);
+    for name in &["curve", "arcs", "boxes"] {
+        assert!(pairs.contains(&(2, name.to_string())));
+    }
+}
+
+#[test]
+fn arcs_run_counter_clockwise() {
+    let path = fitted(2);
+    let arcs = path.leaves();
+    for &flip_y in &[false, true] {
+        let options = DxfOptions {
+            flip_y,
+            ..DxfOptions::default()
+        };
+        let dxf = export_dxf(std::slice::from_ref(&path), &options);
+        let entities = entities(&dxf, "ARC");
+        assert_eq!(entities.len(), arcs.len());
+
+        for (arc, entity) in arcs.iter().zip(entities.iter()) {
+            assert_eq!(entity[0], (8, String::from("arcs")));
+            let sign = if flip_y { -1.0 } else { 1.0 };
+            let center = Vec2::new(values(entity, 10)[0], values(entity, 20)[0]);
+            let radius = values(entity, 40)[0];
+            let start = values(entity, 50)[0].to_radians();
+            let end = values(entity, 51)[0].to_radians();
+            assert!((radius - arc.radius).abs() < 1e-5);
+
+            // counter-clockwise from start to end covers the arc's endpoints
+            let at = |angle: f64| {
+                let p = center + Vec2::from_angle(angle) * radius;
+                Vec2::new(p.x, sign * p.y)
+            };
+            let (from, to) = if arc.is_ccw() != flip_y {
+                (arc.start_point(), arc.end_point())
+            } else {
+                (arc.end_point(), arc.start_point())
+            };
+            assert!(distance(&at(start), &from) < 1e-4);
+            assert!(distance(&at(end), &to) < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn polyline_bulges() {
+    let arc = ArcData {
+        center: Vec2::new(0.0, 0.0),
+        radius: 1.0,
+        start: 0.0,
+        sweep: FRAC_PI_2,
+    };
+    let options = DxfOptions {
+        arc_output: ArcOutput::Polyline,
+        flip_y: false,
+        ..DxfOptions::default()
+    };
+    let path = fitted(1);
+    let arcs = path.leaves();
+    let dxf = export_dxf(std::slice::from_ref(&path), &options);
+    let polylines = entities(&dxf, "LWPOLYLINE");
+    assert_eq!(polylines.len(), 1);
+    let polyline = &polylines[0];
+    assert_eq!(values(polyline, 90), [(arcs.len() + 1) as f64]);
+    let bulges = values(polyline, 42);
+    assert_eq!(bulges.len(), arcs.len());
+    for (bulge, arc) in bulges.iter().zip(arcs.iter()) {
+        assert!((bulge - arc.to_bulge().2).abs() < 1e-5);
+    }
+
+    // a quarter circle has bulge tan(pi / 8)
+    assert!((arc.to_bulge().2 - (std::f64::consts::PI / 8.0).tan()).abs() < 1e-12);
+}
+
+#[test]
+fn spline_and_boxes() {
+    let mut path = BezierPath::<f64>::default();
+    let end = path.end().unwrap();
+    path.segments.push(bezier_core::bezier::BezierCurve::line(
+        end,
+        Vec2::new(600.0, 300.0),
+    ));
+    let fitted = FittedPath::new(path, 1, false, &Tolerance::default());
+    let options = DxfOptions {
+        spline: true,
+        aabb_depth: 1,
+        ..DxfOptions::default()
+    };
+    let dxf = export_dxf(std::slice::from_ref(&fitted), &options);
+
+    let splines = entities(&dxf, "SPLINE");
+    assert_eq!(splines.len(), 1);
+    let spline = &splines[0];
+    assert_eq!(
+        values(spline, 40),
+        [0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0]
+    );
+    assert_eq!(values(spline, 10).len(), 7);
+    // y is mirrored
+    assert_eq!(values(spline, 20)[0], -100.0);
+
+    // leaf boxes of both segments, rounded ones with 8 vertices
+    let boxes = entities(&dxf, "LWPOLYLINE");
+    assert_eq!(boxes.len(), 2 * 4);
+    for entity in boxes.iter() {
+        assert_eq!(entity[0], (8, String::from("boxes")));
+        assert_eq!(values(entity, 70), [1.0]);
+        let n = values(entity, 90)[0];
+        assert!(n == 4.0 || n == 8.0);
+    }
+}
+
+#[test]
+fn box_depth_per_path() {
+    let paths = [fitted(1), fitted(1), fitted(1)];
+    let options = DxfOptions {
+        aabb_depth: 1,
+        aabb_depths: vec![0, 2],
+        ..DxfOptions::default()
+    };
+    let dxf = export_dxf(&paths, &options);
+    // none, leaves and their parents, leaves from the scene-wide depth
+    assert_eq!(entities(&dxf, "LWPOLYLINE").len(), 4 + 2 + 4);
+}
@@ -0,0 +1,118 @@
+// Glyph outlines of the font shipped with rs-bezier.
+
+use bezier_core::font::*;
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::util::distance;
+
+fn noto() -> Font {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../rs-bezier/assets/NotoSans-Regular.ttf"
+    );
+    Font::load(path).unwrap()
+}
+
+fn outline(font: &Font, ch: char) -> Vec<BezierPath<f64>> {
+    font.glyph_outline(font.glyph_index(ch).unwrap()).unwrap()
+}
+
+fn assert_closed(path: &BezierPath<f64>) {
+    assert!(path.closed);
+    let segments = &path.segments;
+    for (i, segment) in segments.iter().enumerate() {
+        let next = &segments[(i + 1) % segments.len()];
+        assert!(distance(&segment.control_pts[3], &next.control_pts[0]) < 1e-9);
+    }
+}
+
+#[test]
+fn contours_per_glyph() {
+    let font = noto();
+    assert_eq!(font.units_per_em(), 2048);
+    for &(ch, contours) in &[('l', 1), ('o', 2), ('B', 3), ('i', 2), ('%', 5)] {
+        let paths = outline(&font, ch);
+        assert_eq!(paths.len(), contours, "{:?}", ch);
+        paths.iter().for_each(assert_closed);
+    }
+    // no outline, but an advance
+    let space = font.glyph_index(' ').unwrap();
+    assert!(font.glyph_outline(space).unwrap().is_empty());
+    assert!(font.advance_width(space) > 0);
+
+    assert_eq!(font.glyph_index('\u{e000}'), None);
+}
+
+#[test]
+fn straight_glyph_matches_its_box() {
+    let font = noto();
+    let paths = outline(&font, 'H');
+    assert_eq!(paths.len(), 1);
+    let (mut min, mut max) = (Vec2::new(f64::MAX, f64::MAX), Vec2::new(f64::MIN, f64::MIN));
+    for segment in paths[0].segments.iter() {
+        for p in segment.control_pts.iter() {
+            min = Vec2::new(min.x.min(p.x), min.y.min(p.y));
+            max = Vec2::new(max.x.max(p.x), max.y.max(p.y));
+        }
+    }
+    // sits on the baseline, cap height below the em
+    let em = font.units_per_em() as f64;
+    assert_eq!(min.y, 0.0);
+    assert!(max.y > 0.6 * em && max.y < 0.8 * em);
+    assert!(min.x > 0.0 && max.x < font.advance_width(font.glyph_index('H').unwrap()) as f64);
+}
+
+#[test]
+fn composite_glyph_adds_the_accent() {
+    let font = noto();
+    let e = outline(&font, 'e');
+    let e_acute = outline(&font, 'é');
+    assert_eq!(e_acute.len(), e.len() + 1);
+    e_acute.iter().for_each(assert_closed);
+}
+
+#[test]
+fn text_is_laid_out_on_the_canvas() {
+    let font = noto();
+    let origin = Vec2::new(100.0, 300.0);
+    let paths = font.text_outline("Hi", 100.0, origin).unwrap();
+    assert_eq!(paths.len(), 3);
+
+    let scale = 100.0 / font.units_per_em() as f64;
+    let h_advance = font.advance_width(font.glyph_index('H').unwrap()) as f64 * scale;
+    let points = || {
+        paths
+            .iter()
+            .flat_map(|path| path.segments.iter())
+            .flat_map(|segment| segment.control_pts.iter())
+    };
+    // y-down: glyphs rise above the baseline
+    assert!(points().all(|p| p.y <= origin.y + 1e-9));
+    assert!(points().any(|p| p.y < origin.y - 50.0));
+    // the 'i' starts after the advance of 'H'
+    let i_left = paths[1..]
+        .iter()
+        .flat_map(|path| path.segments.iter())
+        .flat_map(|segment| segment.control_pts.iter())
+        .fold(f64::MAX, |x, p| x.min(p.x));
+    assert!(i_left > origin.x + h_advance);
+
+    // lettering goes through the biarc pipeline
+    for path in paths {
+        let fitted = FittedPath::new(path, 2, false, &Tolerance::default());
+        assert!(fitted.deviation(16).max < 0.5);
+    }
+}
+
+#[test]
+fn rejects_other_files() {
+    assert!(matches!(
+        Font::parse(b"not a font at all".to_vec()),
+        Err(FontError::Malformed(_)) | Err(FontError::MissingTable(_))
+    ));
+    assert!(matches!(
+        Font::load("/nonexistent.ttf"),
+        Err(FontError::Io(_))
+    ));
+}
@@ -0,0 +1,129 @@
+// G-code moves of single arcs, and offsets of fitted biarc chains.
+
+use std::f64::consts::FRAC_PI_2;
+
+use bezier_core::biarc::ArcData;
+use bezier_core::gcode::*;
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::util::distance;
+
+fn quarter(sweep: f64) -> ArcData<f64> {
+    ArcData {
+        center: Vec2::new(10.0, 0.0),
+        radius: 10.0,
+        start: std::f64::consts::PI,
+        sweep,
+    }
+}
+
+fn moves(gcode: &str) -> Vec<&str> {
+    gcode
+        .lines()
+        .filter(|line| {
+            line.starts_with("G1 X") || line.starts_with("G2 ") || line.starts_with("G3 ")
+        })
+        .collect()
+}
+
+#[test]
+fn arc_direction_and_center_offset() {
+    let mut options = GcodeOptions {
+        flip_y: false,
+        ..GcodeOptions::default()
+    };
+    // from (0, 0) counter-clockwise to (10, -10)
+    let gcode = export_gcode(&[vec![quarter(FRAC_PI_2)]], &options);
+    assert!(gcode.starts_with("G21"));
+    assert!(gcode.contains("G0 X0 Y0\nG1 Z-1 F100\n"));
+    assert_eq!(moves(&gcode), ["G3 X10 Y-10 I10 J0 F600"]);
+    assert!(gcode.trim_end().ends_with("M2 ; end of program"));
+
+    // mirrored, it turns the other way
+    options.flip_y = true;
+    let gcode = export_gcode(&[vec![quarter(FRAC_PI_2)]], &options);
+    assert_eq!(moves(&gcode), ["G2 X10 Y10 I10 J0 F600"]);
+    let gcode = export_gcode(&[vec![quarter(-FRAC_PI_2)]], &options);
+    assert_eq!(moves(&gcode), ["G3 X10 Y-10 I10 J0 F600"]);
+}
+
+#[test]
+fn units_scale_and_precision() {
+    let options = GcodeOptions {
+        units: Units::Inches,
+        scale: 1.0 / 3.0,
+        precision: 2,
+        flip_y: false,
+        feed_rate: 20.0,
+        ..GcodeOptions::default()
+    };
+    let gcode = export_gcode(&[vec![quarter(FRAC_PI_2)]], &options);
+    assert!(gcode.starts_with("G20"));
+    assert_eq!(moves(&gcode), ["G3 X3.33 Y-3.33 I3.33 J0 F20"]);
+}
+
+#[test]
+fn flat_arcs_are_lines() {
+    let flat = ArcData {
+        center: Vec2::new(5.0, 1e6),
+        radius: 1e6,
+        start: -FRAC_PI_2 - 5e-6,
+        sweep: 1e-5,
+    };
+    let gcode = export_gcode(&[vec![flat]], &GcodeOptions::default());
+    assert_eq!(moves(&gcode), ["G1 X10 Y0 F600"]);
+}
+
+#[test]
+fn offset_chain_stays_connected() {
+    let fitted = FittedPath::new(
+        BezierPath::<f64>::default(),
+        3,
+        false,
+        &Tolerance::default(),
+    );
+    let arcs = fitted.leaves();
+    for &shift_by in &[-8.0, 8.0] {
+        let offset = offset_chain(&arcs, shift_by);
+        assert_eq!(offset.len(), arcs.len());
+        for pair in offset.windows(2) {
+            let gap = distance(&pair[0].end_point(), &pair[1].start_point());
+            assert!(gap < 1e-6, "gap {}", gap);
+        }
+        // every point is `shift_by` away from the original arc, on the left for positive
+        for (arc, off) in arcs.iter().zip(offset.iter()) {
+            let normal = arc.tangent_at(0.5).perp();
+            let shift = off.mid_point() - arc.mid_point();
+            assert!((shift.dot(&normal) - shift_by).abs() < 1e-6);
+        }
+    }
+
+    // no straight joining moves for a connected chain
+    let options = GcodeOptions {
+        offset: 4.0,
+        ..GcodeOptions::default()
+    };
+    let gcode = export_gcode(std::slice::from_ref(&arcs), &options);
+    assert_eq!(moves(&gcode).len(), arcs.len());
+}
+
+#[test]
+fn collapsed_arcs_are_bridged() {
+    let small = quarter(FRAC_PI_2);
+    let mut next = quarter(FRAC_PI_2);
+    next.center = Vec2::new(30.0, 0.0);
+    let offset = offset_chain(&[small, next.clone()], 15.0);
+    assert!(offset.is_empty());
+
+    let options = GcodeOptions {
+        offset: -15.0,
+        flip_y: false,
+        ..GcodeOptions::default()
+    };
+    // growing radii leave a gap between the two (unconnected) arcs
+    let gcode = export_gcode(&[vec![quarter(FRAC_PI_2), next]], &options);
+    let moves = moves(&gcode);
+    assert_eq!(moves.len(), 3);
+    assert!(moves[1].starts_with("G1 X"));
+}
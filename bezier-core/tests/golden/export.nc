@@ -0,0 +1,31 @@
+G21 ; millimeters
+G90 ; absolute coordinates
+G17 ; xy plane
+G0 Z5
+G0 X20 Y40
+G1 Z-1 F100
+G2 X40.915 Y76.14 I306.418 J-153.209 F600
+G2 X68.125 Y107.813 I140.508 J-93.187 F600
+G2 X91.989 Y124.512 I77.687 J-85.614 F600
+G2 X120 Y132.5 I33.072 J-62.868 F600
+G2 X147.395 Y128.783 I4.811 J-67.356 F600
+G2 X171.875 Y115.938 I-32.61 J-91.894 F600
+G2 X198.778 Y90.413 I-81.488 J-112.83 F600
+G2 X220 Y60 I-209.995 J-169.143 F600
+G3 X232.889 Y41.488 I145.008 J87.223 F600
+G3 X250 Y26.789 I47.582 J38.08 F600
+G3 X264.674 Y22.013 I18.599 J32.215 F600
+G3 X280 Y23.812 I3.688 J34.756 F600
+G3 X296.439 Y33.212 I-17.616 J49.887 F600
+G3 X310 Y46.43 I-83.253 J98.977 F600
+G3 X326.122 Y67.443 I-140.755 J124.683 F600
+G3 X340 Y90 I-340.997 J225.344 F600
+G0 Z5
+G0 X40 Y200
+G1 Z-1 F100
+G2 X53.901 Y236.442 I54.716 J0 F600
+G2 X87.5 Y256.25 I47.8 J-42.679 F600
+G2 X136.817 Y253.932 I20.478 J-90.105 F600
+G2 X180 Y230 I-41.078 J-125.045 F600
+G0 Z5
+M2 ; end of program
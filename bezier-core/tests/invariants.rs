@@ -0,0 +1,258 @@
+// Property tests over random control polygons for the invariants the front-ends rely on.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use bezier_core::bezier::{BezierCurve, JointStrategy};
+use bezier_core::biarc::*;
+use bezier_core::path::{BezierPath, FittedPath};
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::tree::Tree;
+use bezier_core::util::*;
+
+use proptest::prelude::*;
+
+const EPS: f64 = 1e-6;
+const SAMPLES: usize = 256;
+
+fn point() -> impl Strategy<Value = Vec2<f64>> {
+    (0.0..600.0, 0.0..400.0).prop_map(|(x, y)| Vec2::new(x, y))
+}
+
+fn curve() -> impl Strategy<Value = BezierCurve<f64>> {
+    [point(), point(), point(), point()]
+        .prop_filter("endpoints must differ", |pts| {
+            distance(&pts[0], &pts[3]) > 1.0
+        })
+        .prop_map(|control_pts| BezierCurve { control_pts })
+}
+
+fn joint() -> impl Strategy<Value = JointStrategy> {
+    prop_oneof![
+        Just(JointStrategy::ChordBisector),
+        Just(JointStrategy::CurveMidpoint)
+    ]
+}
+
+fn build(
+    curve: &BezierCurve<f64>,
+    split_num: usize,
+    use_bezier_aabb: bool,
+    joint: JointStrategy,
+) -> ArcTree<f64> {
+    let depth = split_num + 1;
+    let arcs = Rc::new(RefCell::new(Tree::new_complete(
+        depth,
+        ArcBox::arc_builder(depth),
+    )));
+    curve.build_biarc(
+        arcs.clone(),
+        split_num,
+        use_bezier_aabb,
+        joint,
+        &Tolerance::default(),
+    );
+    arcs
+}
+
+/// Leaf arcs from left to right
+fn leaves(arcs: &ArcTree<f64>) -> Vec<ArcData<f64>> {
+    let tree = arcs.borrow();
+    (0..tree.len())
+        .filter_map(|i| tree.get(i).unwrap().arc.clone())
+        .collect()
+}
+
+fn arc_samples(arc: &ArcData<f64>) -> Vec<Vec2<f64>> {
+    arc.sample(SAMPLES)
+}
+
+fn contains(aabb: &AABB<f64>, inflate: f64, point: &Vec2<f64>, eps: f64) -> bool {
+    let pad = inflate + eps;
+    aabb.x - pad <= point.x
+        && point.x <= aabb.x + aabb.w + pad
+        && aabb.y - pad <= point.y
+        && point.y <= aabb.y + aabb.h + pad
+}
+
+fn contains_box(outer: &ArcBox<f64>, inner: &ArcBox<f64>, eps: f64) -> bool {
+    let AABB { x, y, h, w } = inner.aabb;
+    let r = inner.radius;
+    contains(&outer.aabb, outer.radius, &Vec2::new(x - r, y - r), eps)
+        && contains(
+            &outer.aabb,
+            outer.radius,
+            &Vec2::new(x + w + r, y + h + r),
+            eps,
+        )
+}
+
+/// Tolerance scaled to the size of the configuration
+fn scaled(arc: &ArcData<f64>) -> f64 {
+    EPS * (1.0 + arc.radius.min(1e3))
+}
+
+proptest! {
+    #[test]
+    fn biarcs_are_g1_continuous(curve in curve(), split_num in 1usize..=5, joint in joint()) {
+        let arcs = leaves(&build(&curve, split_num, false, joint));
+
+        for pair in arcs.windows(2) {
+            let (left, right) = (&pair[0], &pair[1]);
+
+            let end = left.end_point();
+            let start = right.start_point();
+            let eps = scaled(left).max(scaled(right));
+            prop_assert!(distance(&end, &start) < eps, "gap {:?} -> {:?}", end, start);
+
+            let t0 = left.tangent_at(1.0);
+            let t1 = right.tangent_at(0.0);
+            prop_assert!(t0.cross(&t1).abs() < 1e-4, "kink {:?} -> {:?}", t0, t1);
+            prop_assert!(t0.dot(&t1) > 0.0, "cusp {:?} -> {:?}", t0, t1);
+        }
+    }
+
+    #[test]
+    fn biarc_endpoints_match_bezier(curve in curve(), split_num in 1usize..=5, joint in joint()) {
+        let arcs = leaves(&build(&curve, split_num, false, joint));
+        let biarc_n = 2usize.pow(split_num as u32);
+        prop_assert_eq!(arcs.len(), 2 * biarc_n);
+
+        let mut expected = Vec2::zero();
+        for k in 0..biarc_n {
+            let left = &arcs[2 * k];
+            let right = &arcs[2 * k + 1];
+
+            curve.cubic_curve_to(&mut expected, k as f64 / biarc_n as f64);
+            let start = left.start_point();
+            prop_assert!(distance(&start, &expected) < scaled(left));
+
+            curve.cubic_curve_to(&mut expected, (k + 1) as f64 / biarc_n as f64);
+            let end = right.end_point();
+            prop_assert!(distance(&end, &expected) < scaled(right));
+        }
+    }
+
+    #[test]
+    fn arc_aabb_contains_arc(curve in curve(), split_num in 1usize..=5) {
+        for arc in leaves(&build(&curve, split_num, false, JointStrategy::default())) {
+            let aabb = arc.aabb();
+            for point in arc_samples(&arc) {
+                prop_assert!(contains(&aabb, 0.0, &point, scaled(&arc)), "{:?} outside {:?}", point, aabb);
+            }
+        }
+    }
+
+    #[test]
+    fn leaf_box_contains_arc(
+        curve in curve(),
+        split_num in 1usize..=5,
+        use_bezier_aabb in any::<bool>(),
+    ) {
+        let arcs = build(&curve, split_num, use_bezier_aabb, JointStrategy::default());
+        let tree = arcs.borrow();
+        for i in 0..tree.len() {
+            let node = tree.get(i).unwrap();
+            if let Some(ref arc) = node.arc {
+                for point in arc_samples(arc) {
+                    prop_assert!(contains(&node.aabb, node.radius, &point, scaled(arc)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parent_box_contains_children(
+        curve in curve(),
+        split_num in 1usize..=5,
+        use_bezier_aabb in any::<bool>(),
+    ) {
+        let arcs = build(&curve, split_num, use_bezier_aabb, JointStrategy::default());
+        let tree = arcs.borrow();
+        for i in 0..tree.len() {
+            let node = tree.get(i).unwrap();
+            for child in tree.left(node).into_iter().chain(tree.right(node)) {
+                prop_assert!(contains_box(node, child, EPS), "node {} does not contain child", i);
+            }
+        }
+    }
+
+    #[test]
+    fn de_casteljau_ends_at_curve_point(curve in curve(), t in 0.0..=1.0f64) {
+        let levels = curve.de_casteljau(t);
+        prop_assert_eq!(levels.iter().map(Vec::len).collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+
+        let mut expected = Vec2::zero();
+        curve.cubic_curve_to(&mut expected, t);
+        prop_assert!(distance(&levels[3][0], &expected) < EPS);
+
+        // the last edge is a third of the derivative
+        curve.cubic_deriv_to(&mut expected, t);
+        let edge = (levels[2][1] - levels[2][0]) * 3.0;
+        prop_assert!(distance(&edge, &expected) < EPS * (1.0 + norm(&expected)));
+    }
+
+    #[test]
+    fn construction_matches_biarc_tree(curve in curve(), split_num in 1usize..=5, joint in joint()) {
+        let arcs = leaves(&build(&curve, split_num, false, joint));
+        let biarc_n = 2usize.pow(split_num as u32);
+        let delta = 1.0 / biarc_n as f64;
+        let tol = Tolerance::default();
+        for k in 0..biarc_n {
+            let t = delta * k as f64;
+            let construction = curve.biarc_construction(t, t + delta, joint, &tol);
+            prop_assert_eq!(&construction.left, &arcs[2 * k]);
+            prop_assert_eq!(&construction.right, &arcs[2 * k + 1]);
+            if let Some((center, radius)) = construction.joint_circle {
+                let eps = EPS * (1.0 + radius.min(1e3));
+                prop_assert!((distance(&center, &construction.joint) - radius).abs() < eps);
+            }
+        }
+    }
+
+    #[test]
+    fn box_levels_sum_up_the_boxes(curve in curve(), split_num in 1usize..=5, aabb_depth in 0usize..=8) {
+        let mut path = BezierPath::from_curve(curve.clone());
+        path.segments.push(curve);
+        let fitted = FittedPath::new(path, split_num, false, &Tolerance::default());
+        let boxes = fitted.boxes(aabb_depth);
+        let levels = fitted.box_levels(aabb_depth);
+        prop_assert_eq!(levels.len(), aabb_depth.min(split_num + 2));
+        prop_assert_eq!(levels.iter().map(|l| l.count).sum::<usize>(), boxes.len());
+        for (level, next) in levels.iter().zip(levels.iter().skip(1)) {
+            prop_assert_eq!(level.depth + 1, next.depth);
+            prop_assert_eq!(level.count * 2, next.count);
+        }
+        let area: f64 = boxes.iter().map(|(aabb, r)| aabb.rounded_area(*r)).sum();
+        let total: f64 = levels.iter().map(|l| l.area).sum();
+        prop_assert!((area - total).abs() <= EPS * (1.0 + area));
+    }
+
+    #[test]
+    fn with_joint_matches_rebuild(curve in curve(), split_num in 1usize..=5, joint in joint()) {
+        let path = BezierPath::from_curve(curve);
+        let tol = Tolerance::default();
+        let fitted = FittedPath::with_joint(path.clone(), split_num, false, joint, &tol);
+        let mut rebuilt = FittedPath::new(path, split_num, false, &tol);
+        rebuilt.joint = joint;
+        rebuilt.rebuild(split_num, false, &tol);
+        prop_assert_eq!(fitted.joint, joint);
+        prop_assert_eq!(fitted.leaves(), rebuilt.leaves());
+    }
+
+    #[test]
+    fn path_constructions_follow_leaves(curve in curve(), split_num in 1usize..=5) {
+        let mut path = BezierPath::from_curve(curve.clone());
+        path.segments.push(curve);
+        let tol = Tolerance::default();
+        let fitted = FittedPath::new(path, split_num, false, &tol);
+        let leaves = fitted.leaves();
+        let constructions = fitted.constructions(&tol);
+        prop_assert_eq!(constructions.len() * 2, leaves.len());
+        for (construction, pair) in constructions.iter().zip(leaves.chunks(2)) {
+            prop_assert_eq!(&construction.left, &pair[0]);
+            prop_assert_eq!(&construction.right, &pair[1]);
+        }
+    }
+}
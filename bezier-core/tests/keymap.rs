@@ -0,0 +1,79 @@
+// Parsing and lookup of keymaps.
+
+use bezier_core::keymap::*;
+
+fn chord(text: &str) -> KeyChord {
+    KeyChord::parse(text).unwrap()
+}
+
+#[test]
+fn default_keys_of_the_original_editor() {
+    let keymap = Keymap::default();
+    assert_eq!(keymap.action(&chord("I")), Some(Action::Initialize));
+    assert_eq!(keymap.action(&chord("l")), Some(Action::ToggleDotted));
+    assert_eq!(keymap.action(&chord("C")), Some(Action::ToggleMesh));
+    assert_eq!(keymap.action(&chord("R")), Some(Action::ToggleCircles));
+    assert_eq!(keymap.action(&chord("3")), Some(Action::SetSplit(3)));
+    assert_eq!(keymap.action(&chord("Ctrl+Shift+Z")), Some(Action::Redo));
+    assert_eq!(keymap.action(&chord("Shift+I")), None);
+}
+
+#[test]
+fn key_code_names() {
+    assert_eq!(KeyChord::new("Key4", false, false, false), chord("4"));
+    assert_eq!(
+        KeyChord::new("PageUp", true, false, false),
+        chord("ctrl+pageup")
+    );
+    assert_eq!(chord("Ctrl++"), KeyChord::new("Plus", true, false, false));
+    assert_eq!(chord("cmd + shift + z").to_string(), "Ctrl+Shift+Z");
+    assert_eq!(KeyChord::parse("Hyper+Z"), None);
+    assert_eq!(KeyChord::parse("Ctrl+"), None);
+}
+
+#[test]
+fn file_bindings_apply_over_defaults() {
+    let keymap = Keymap::parse(
+        "# swap dotted and mesh\n\
+         L = mesh\n\
+         C = dotted   # trailing comment\n\
+         \n\
+         R = none\n\
+         Alt+U = undo\n",
+    )
+    .unwrap();
+    assert_eq!(keymap.action(&chord("L")), Some(Action::ToggleMesh));
+    assert_eq!(keymap.action(&chord("C")), Some(Action::ToggleDotted));
+    assert_eq!(keymap.action(&chord("R")), None);
+    assert_eq!(keymap.action(&chord("Alt+U")), Some(Action::Undo));
+    assert_eq!(keymap.action(&chord("Ctrl+Z")), Some(Action::Undo));
+
+    let help = keymap.help();
+    assert!(help.contains(&(String::from("Ctrl+Z, Alt+U"), "Undo")));
+    assert!(!help
+        .iter()
+        .any(|(_, description)| *description == "Draw full control circles"));
+}
+
+#[test]
+fn errors_name_the_line() {
+    match Keymap::parse("I = initialize\nQ = quit\n") {
+        Err(KeymapError::Parse { line, message }) => {
+            assert_eq!(line, 2);
+            assert!(message.contains("quit"));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(matches!(
+        Keymap::parse("just words"),
+        Err(KeymapError::Parse { line: 1, .. })
+    ));
+}
+
+#[test]
+fn action_names_round_trip() {
+    for action in [Action::SetSplit(4), Action::ZoomIn, Action::ToggleHelp].iter() {
+        assert_eq!(Action::from_name(action.name()), Some(*action));
+        assert!(!action.description().is_empty());
+    }
+}
@@ -0,0 +1,27 @@
+// Toggling of the construction overlay elements.
+
+use bezier_core::keymap::*;
+use bezier_core::overlay::Overlay;
+
+#[test]
+fn each_element_toggles_alone() {
+    let keymap = Keymap::default();
+    let mut overlay = Overlay::default();
+    let joint = keymap.action(&KeyChord::parse("J").unwrap()).unwrap();
+    assert!(overlay.toggle(joint));
+    assert_eq!(
+        overlay,
+        Overlay {
+            joint_circles: true,
+            ..Overlay::default()
+        }
+    );
+    assert!(overlay.needs_constructions());
+
+    assert!(overlay.toggle(Action::ToggleCircles));
+    assert!(overlay.toggle(Action::ToggleTangents));
+    assert!(overlay.toggle(Action::ToggleJointCircles));
+    assert!(overlay.arc_circles && overlay.tangents && !overlay.joint_circles);
+
+    assert!(!overlay.toggle(Action::ToggleMesh));
+}
@@ -0,0 +1,60 @@
+// Exact orientation and the line intersection classification built on it.
+
+use bezier_core::point::Vec2;
+use bezier_core::predicates::*;
+
+fn exact() -> Tolerance<f64> {
+    Tolerance {
+        parallel: 0.0,
+        distance: 0.0,
+        ..Tolerance::default()
+    }
+}
+
+#[test]
+fn near_collinear_sign_is_exact() {
+    // a few ulps off the line y = x through b and c
+    let a = Vec2::new(0.500_000_000_000_004_6, 0.500_000_000_000_005_3);
+    let b = Vec2::new(12.0, 12.0);
+    let c = Vec2::new(24.0, 24.0);
+
+    // the naive determinant rounds to the wrong side
+    let naive = (b - a).cross(&(c - a));
+    assert!(naive < 0.0);
+    assert_eq!(orientation(&a, &b, &c), Orientation::CounterClockwise);
+    assert_eq!(orientation(&b, &a, &c), Orientation::Clockwise);
+    assert!(orient2d(&a, &b, &c) > 0.0);
+}
+
+#[test]
+fn exactly_collinear() {
+    let a = Vec2::new(0.1, 0.1);
+    let b = Vec2::new(12.0, 12.0);
+    let c = Vec2::new(-1e300, -1e300);
+    assert_eq!(orientation(&a, &b, &c), Orientation::Collinear);
+    assert_eq!(orientation(&a, &a, &b), Orientation::Collinear);
+    assert_eq!(cross_exact(&Vec2::new(0.1, 0.3), &Vec2::new(0.2, 0.6)), 0.0);
+}
+
+#[test]
+fn parallel_and_coincident_lines_without_tolerance() {
+    let tol = exact();
+    let p0 = Vec2::new(0.0, 0.0);
+    let v0 = Vec2::new(0.1, 0.3);
+    let v1 = Vec2::new(0.2, 0.6);
+    assert_eq!(
+        line_intersection(&p0, &v0, &Vec2::new(0.2, 0.6), &v1, &tol),
+        LineIntersection::Coincident
+    );
+    let off = Vec2::new(0.2, 0.6 + f64::EPSILON);
+    assert_eq!(
+        line_intersection(&p0, &v0, &off, &v1, &tol),
+        LineIntersection::Parallel
+    );
+
+    // not exactly parallel: a point, however far away
+    let v1 = Vec2::new(0.1 * 3.0, 0.3 * 3.0);
+    assert!(line_intersection(&p0, &v0, &off, &v1, &tol)
+        .point()
+        .is_some());
+}
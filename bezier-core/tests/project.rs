@@ -0,0 +1,125 @@
+// Project documents round-trip, and stay readable across format changes.
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::path::BezierPath;
+use bezier_core::point::Vec2;
+use bezier_core::project::*;
+
+#[test]
+fn round_trip() {
+    let mut project = Project::default();
+    let mut path = BezierPath::from_curve(BezierCurve {
+        control_pts: [
+            Vec2::new(0.5, -1.0),
+            Vec2::new(2.0, 3.25),
+            Vec2::new(1e-3, 7.0),
+            Vec2::new(10.0, 0.0),
+        ],
+    });
+    path.segments.push(BezierCurve::line(
+        Vec2::new(10.0, 0.0),
+        Vec2::new(0.5, -1.0),
+    ));
+    path.closed = true;
+    project.curves.push(path);
+    project.display.num_split = 4;
+    project.display.use_bezier_aabb = true;
+    project.colors.left_arc = rgba8(1, 2, 3, 0.5);
+    project.curve_display = vec![
+        Display::default(),
+        Display {
+            num_split: 2,
+            ..Display::default()
+        },
+    ];
+
+    let json = project.to_json().unwrap();
+    assert_eq!(Project::from_json(&json).unwrap(), project);
+}
+
+#[test]
+fn missing_fields_take_defaults() {
+    let project = Project::from_json(r#"{ "display": { "num_split": 3 } }"#).unwrap();
+    assert_eq!(project.version, FORMAT_VERSION);
+    assert_eq!(project.display.num_split, 3);
+    assert_eq!(project.display.aabb_depth, Display::default().aabb_depth);
+    assert_eq!(project.colors, Colors::default());
+    assert_eq!(project.curves, Project::default().curves);
+    // curves without their own settings use the project's
+    assert!(project.curve_display.is_empty());
+    assert_eq!(project.curve_display(0).num_split, 3);
+}
+
+#[test]
+fn unknown_fields_are_ignored() {
+    let json = r#"{
+        "version": 2,
+        "layers": [],
+        "display": { "is_dotted": true, "grid": 10 }
+    }"#;
+    let project = Project::from_json(json).unwrap();
+    assert!(project.display.is_dotted);
+}
+
+#[test]
+fn newer_version_is_rejected() {
+    let json = format!(r#"{{ "version": {} }}"#, FORMAT_VERSION + 1);
+    match Project::from_json(&json) {
+        Err(ProjectError::UnsupportedVersion(version)) => assert_eq!(version, FORMAT_VERSION + 1),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn save_and_load_file() {
+    let path = std::env::temp_dir().join(format!("bezier-project-{}.json", std::process::id()));
+    let mut project = Project::default();
+    project.display.is_meshed = false;
+    project.save(&path).unwrap();
+    let loaded = Project::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap(), project);
+}
+
+#[test]
+fn version_1_cubics_become_paths() {
+    let json = r#"{
+        "version": 1,
+        "curves": [
+            { "control_pts": [
+                { "x": 1.0, "y": 2.0 }, { "x": 3.0, "y": 4.0 },
+                { "x": 5.0, "y": 6.0 }, { "x": 7.0, "y": 8.0 }
+            ] }
+        ]
+    }"#;
+    let project = Project::from_json(json).unwrap();
+    assert_eq!(project.version, FORMAT_VERSION);
+    assert_eq!(project.curves.len(), 1);
+    let path = &project.curves[0];
+    assert!(!path.closed);
+    assert_eq!(path.segments.len(), 1);
+    // mirrored to y-up within the y range 2..8
+    assert_eq!(path.segments[0].control_pts[3], Vec2::new(7.0, 2.0));
+}
+
+#[test]
+fn version_2_is_mirrored_to_y_up() {
+    let json = r#"{
+        "version": 2,
+        "curves": [
+            { "segments": [ { "control_pts": [
+                { "x": 0.0, "y": 100.0 }, { "x": 0.0, "y": 300.0 },
+                { "x": 50.0, "y": 300.0 }, { "x": 50.0, "y": 200.0 }
+            ] } ] }
+        ]
+    }"#;
+    let project = Project::from_json(json).unwrap();
+    let pts = &project.curves[0].segments[0].control_pts;
+    assert_eq!(pts[0], Vec2::new(0.0, 300.0));
+    assert_eq!(pts[1], Vec2::new(0.0, 100.0));
+    assert_eq!(pts[3], Vec2::new(50.0, 200.0));
+
+    // the current version is read unchanged
+    let json = project.to_json().unwrap();
+    assert_eq!(Project::from_json(&json).unwrap(), project);
+}
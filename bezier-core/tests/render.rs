@@ -0,0 +1,154 @@
+// CPU renderer against golden images, and the PNG codec round trip.
+//
+// Run with `BLESS=1` to rewrite the golden images after an intended change.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::path::*;
+use bezier_core::png::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::raster::*;
+use bezier_core::util::rgba8;
+
+fn scene(split_num: usize) -> Vec<FittedPath<f64>> {
+    let curve = BezierCurve {
+        control_pts: [
+            Vec2::new(20.0, 120.0),
+            Vec2::new(60.0, 20.0),
+            Vec2::new(140.0, 20.0),
+            Vec2::new(180.0, 120.0),
+        ],
+    };
+    vec![FittedPath::new(
+        BezierPath::from_curve(curve),
+        split_num,
+        false,
+        &Tolerance::default(),
+    )]
+}
+
+fn options() -> RenderOptions {
+    RenderOptions {
+        width: 200.0,
+        height: 150.0,
+        ..RenderOptions::default()
+    }
+}
+
+/// Compare with `tests/golden/<name>.png`, allowing small anti-aliasing differences.
+fn assert_golden(name: &str, pixmap: &Pixmap, dpi: f64) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect::<PathBuf>()
+        .with_extension("png");
+    if env::var_os("BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, pixmap.to_png(dpi)).unwrap();
+        return;
+    }
+
+    let golden =
+        decode_png(&fs::read(&path).expect("missing golden image, run with BLESS=1")).unwrap();
+    assert_eq!((golden.width, golden.height), (pixmap.width, pixmap.height));
+    let differing = golden
+        .data
+        .chunks(4)
+        .zip(pixmap.data.chunks(4))
+        .filter(|(a, b)| {
+            a.iter()
+                .zip(b.iter())
+                .any(|(x, y)| (*x as i16 - *y as i16).abs() > 2)
+        })
+        .count();
+    assert!(
+        differing <= pixmap.width * pixmap.height / 1000,
+        "{}: {} pixels differ from the golden image",
+        name,
+        differing
+    );
+}
+
+#[test]
+fn png_round_trip() {
+    let mut data = Vec::new();
+    for i in 0..(37 * 11) {
+        let v = (i * 7 % 256) as u8;
+        // runs and noise
+        data.extend_from_slice(&[v / 64 * 64, 255 - v, v, 255]);
+    }
+    let png = encode_png(37, 11, &data, 300.0);
+    let image = decode_png(&png).unwrap();
+    assert_eq!((image.width, image.height), (37, 11));
+    assert_eq!(image.data, data);
+    assert!((image.dpi.unwrap() - 300.0).abs() < 0.1);
+
+    assert!(decode_png(&png[..png.len() / 2]).is_err());
+    assert!(decode_png(b"not a png").is_err());
+}
+
+#[test]
+fn scene_size_follows_dpi() {
+    let pixmap = render(
+        &scene(1),
+        &RenderOptions {
+            dpi: 192.0,
+            ..options()
+        },
+    );
+    assert_eq!((pixmap.width, pixmap.height), (400, 300));
+}
+
+#[test]
+fn scene_colors() {
+    let options = options();
+    let pixmap = render(&scene(2), &options);
+    // background, and a control point drawn on top of everything
+    assert_eq!(pixmap.pixel(100, 140), [255, 255, 255, 255]);
+    assert_eq!(pixmap.pixel(20, 120), [255, 0, 0, 255]);
+
+    let mut hidden = options.clone();
+    hidden.is_meshed = false;
+    hidden.point_color = rgba8(255, 255, 255, 1.0);
+    let pixmap = render(&scene(2), &hidden);
+    // no green mesh or biarcs without the mesh toggle
+    assert!(pixmap.data.chunks(4).all(|p| !(p[1] > 150 && p[0] < 100)));
+}
+
+#[test]
+fn path_styles_override_the_scene() {
+    let mut options = options();
+    options.is_meshed = false;
+    options.path_styles = vec![PathStyle {
+        is_dotted: false,
+        is_meshed: true,
+        aabb_depth: 3,
+    }];
+    let styled = render(&scene(2), &options);
+    // the mesh toggle of the path wins over the scene's
+    assert!(styled.data.chunks(4).any(|p| p[1] > 150 && p[0] < 100));
+
+    options.is_meshed = true;
+    options.aabb_depth = 3;
+    options.path_styles.clear();
+    assert_eq!(render(&scene(2), &options), styled);
+}
+
+#[test]
+fn golden_scene() {
+    assert_golden("scene", &render(&scene(2), &options()), BASE_DPI);
+}
+
+#[test]
+fn golden_dotted_boxes() {
+    let options = RenderOptions {
+        is_dotted: true,
+        aabb_depth: 3,
+        dpi: 144.0,
+        ..options()
+    };
+    assert_golden("dotted_boxes", &render(&scene(1), &options), options.dpi);
+}
@@ -0,0 +1,151 @@
+// Sampled curve data and the leaf table.
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::samples::*;
+use bezier_core::util::distance;
+
+// quarter of the unit circle, scaled by 100
+fn quarter_circle() -> BezierCurve<f64> {
+    let k = 0.552_284_749_8 * 100.0;
+    BezierCurve {
+        control_pts: [
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, k),
+            Vec2::new(k, 100.0),
+            Vec2::new(0.0, 100.0),
+        ],
+    }
+}
+
+fn fitted(split_num: usize) -> Vec<FittedPath<f64>> {
+    let s_curve = BezierPath {
+        segments: vec![
+            BezierCurve::line(Vec2::new(0.0, 0.0), Vec2::new(300.0, 0.0)),
+            BezierCurve {
+                control_pts: [
+                    Vec2::new(300.0, 0.0),
+                    Vec2::new(400.0, 0.0),
+                    Vec2::new(400.0, 100.0),
+                    Vec2::new(500.0, 200.0),
+                ],
+            },
+        ],
+        closed: false,
+    };
+    [BezierPath::from_curve(quarter_circle()), s_curve]
+        .iter()
+        .map(|path| FittedPath::new(path.clone(), split_num, false, &Tolerance::default()))
+        .collect()
+}
+
+#[test]
+fn parameter_spacing() {
+    let paths = fitted(2);
+    let options = SampleOptions {
+        spacing: Spacing::Parameter,
+        samples: 8,
+    };
+    let samples = sample_paths(&paths, &options);
+    assert_eq!(samples.len(), 9 + 17);
+
+    let mut expected = Vec2::zero();
+    for s in samples.iter() {
+        paths[s.curve].path.segments[s.segment].cubic_curve_to(&mut expected, s.t);
+        assert!(distance(&s.position, &expected) < 1e-9);
+        assert!((s.tangent.dot(&s.tangent) - 1.0).abs() < 1e-9);
+    }
+    // the joint is sampled once, at the end of the first segment
+    let second = &samples[9..];
+    assert_eq!((second[8].segment, second[8].t), (0, 1.0));
+    assert_eq!((second[9].segment, second[9].t), (1, 0.125));
+    assert!(second
+        .windows(2)
+        .all(|pair| pair[0].length < pair[1].length));
+}
+
+#[test]
+fn arc_length_spacing() {
+    let paths = fitted(2);
+    let samples = sample_paths(
+        &paths,
+        &SampleOptions {
+            spacing: Spacing::ArcLength,
+            samples: 16,
+        },
+    );
+    let second: Vec<_> = samples.iter().filter(|s| s.curve == 1).collect();
+    assert_eq!(second.len(), 33);
+    let step = second[32].length / 32.0;
+    for pair in second.windows(2) {
+        assert!((pair[1].length - pair[0].length - step).abs() < 1e-6);
+        // equal chords on a gently curved path
+        assert!((distance(&pair[0].position, &pair[1].position) - step).abs() < 0.05 * step);
+    }
+    // the straight segment is 300 long
+    let joint = second.iter().find(|s| s.segment == 1).unwrap();
+    assert!(joint.length >= 300.0 - 1e-6);
+}
+
+#[test]
+fn curvature_and_deviation() {
+    let paths = fitted(3);
+    let samples = sample_paths(&paths, &SampleOptions::default());
+    for s in samples.iter().filter(|s| s.curve == 0) {
+        // counter-clockwise circle of radius 100, to the cubic's accuracy (about 2%)
+        assert!((s.curvature - 0.01).abs() < 3e-4, "{}", s.curvature);
+        assert!(s.deviation < 0.05);
+    }
+    // straight segment: no curvature, fitted by nearly flat arcs
+    for s in samples.iter().filter(|s| s.curve == 1 && s.segment == 0) {
+        assert!(s.curvature.abs() < 1e-9);
+        assert!(s.deviation < 1e-3, "{:?}", s);
+    }
+}
+
+#[test]
+fn leaf_table_matches_leaves() {
+    let paths = fitted(2);
+    let rows = leaf_table(&paths);
+    // 2^split biarcs of two arcs per segment
+    assert_eq!(rows.len(), 8 * 3);
+    let leaves: Vec<_> = paths.iter().flat_map(|path| path.leaves()).collect();
+    for (row, arc) in rows.iter().zip(leaves.iter()) {
+        assert_eq!(&row.arc, arc);
+        let mid = row.arc.mid_point();
+        let aabb = &row.aabb;
+        let r = row.rounding + 1e-6;
+        assert!(mid.x >= aabb.x - r && mid.x <= aabb.x + aabb.w + r);
+        assert!(mid.y >= aabb.y - r && mid.y <= aabb.y + aabb.h + r);
+    }
+    assert_eq!(rows[9].segment, 0);
+    assert_eq!(rows[9].leaf, 1);
+    assert_eq!(rows[16].segment, 1);
+    assert_eq!(rows[16].leaf, 0);
+}
+
+#[test]
+fn csv_and_json_tables() {
+    let paths = fitted(1);
+    let samples = sample_paths(&paths, &SampleOptions::default());
+    let rows = leaf_table(&paths);
+
+    let csv = samples_csv(&samples, 4);
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), samples.len() + 1);
+    assert!(lines[0].starts_with("curve,segment,t,length,x,y"));
+    assert!(lines[1].starts_with("0,0,0,0,100,0,0,1,0.0098,"));
+    assert!(lines.iter().all(|line| line.split(',').count() == 10));
+
+    let csv = leaves_csv(&rows, 4);
+    assert_eq!(csv.lines().count(), rows.len() + 1);
+    assert!(csv.lines().all(|line| line.split(',').count() == 14));
+
+    let json = export_json(&samples, &rows, 4);
+    assert!(json.starts_with("{\n  \"samples\": [\n    {\"curve\": 0, \"segment\": 0, \"t\": 0,"));
+    assert_eq!(json.matches("\"deviation\"").count(), samples.len());
+    assert_eq!(json.matches("\"rounding\"").count(), rows.len());
+    assert!(!json.contains("NaN") && !json.contains("inf"));
+}
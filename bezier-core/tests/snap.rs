@@ -0,0 +1,92 @@
+// Nearest point on a cubic, and snapping of dragged points.
+
+use std::f64::consts::FRAC_PI_4;
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::point::Vec2;
+use bezier_core::snap::*;
+use bezier_core::util::distance;
+
+const EPS: f64 = 1e-9;
+
+fn arch() -> BezierCurve<f64> {
+    BezierCurve {
+        control_pts: [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 100.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(100.0, 0.0),
+        ],
+    }
+}
+
+#[test]
+fn nearest_t_on_the_curve() {
+    let curve = arch();
+    // apex of the symmetric arch
+    let t = curve.nearest_t(&Vec2::new(50.0, 200.0));
+    assert!((t - 0.5).abs() < 1e-6);
+
+    // the foot of a normal comes back to its curve point
+    let mut point = Vec2::zero();
+    let mut tangent = Vec2::zero();
+    curve.cubic_curve_to(&mut point, 0.3);
+    curve.cubic_deriv_to(&mut tangent, 0.3);
+    let off_curve = point + tangent.perp() * (5.0 / tangent.dot(&tangent).sqrt());
+    assert!((curve.nearest_t(&off_curve) - 0.3).abs() < 1e-6);
+
+    // clamped to the ends
+    assert_eq!(curve.nearest_t(&Vec2::new(-10.0, -50.0)), 0.0);
+    assert_eq!(curve.nearest_t(&Vec2::new(110.0, -50.0)), 1.0);
+}
+
+#[test]
+fn snap_priorities() {
+    let options = SnapOptions {
+        grid: Some(10.0),
+        points: true,
+        curves: true,
+        radius: 5.0,
+    };
+    let points = [Vec2::new(33.0, 33.0)];
+    let curves = [arch()];
+
+    // a point in reach wins over the curve and the grid
+    let (p, target) = snap(&Vec2::new(31.0, 35.0), &options, &points, &curves);
+    assert_eq!(target, SnapTarget::Point);
+    assert_eq!(p, points[0]);
+
+    // the curve apex is at (50, 75)
+    let (p, target) = snap(&Vec2::new(50.0, 78.0), &options, &points, &curves);
+    assert_eq!(target, SnapTarget::Curve);
+    assert!(distance(&p, &Vec2::new(50.0, 75.0)) < 1e-6);
+
+    let (p, target) = snap(&Vec2::new(-21.0, 14.9), &options, &points, &curves);
+    assert_eq!(target, SnapTarget::Grid);
+    assert_eq!(p, Vec2::new(-20.0, 10.0));
+
+    let free = SnapOptions {
+        grid: None,
+        ..options
+    };
+    let (p, target) = snap(&Vec2::new(-21.0, 14.9), &free, &points, &curves);
+    assert_eq!(target, SnapTarget::Free);
+    assert_eq!(p, Vec2::new(-21.0, 14.9));
+}
+
+#[test]
+fn angle_constraint() {
+    let anchor = Vec2::new(10.0, 10.0);
+    // 40 degrees rounds to 45
+    let p = anchor + Vec2::from_angle(40f64.to_radians()) * 20.0;
+    let q = constrain_angle(&anchor, &p, FRAC_PI_4);
+    let offset = q - anchor;
+    assert!((offset.x - offset.y).abs() < EPS);
+    assert!((distance(&anchor, &q) - 20.0 * 5f64.to_radians().cos()).abs() < EPS);
+
+    // nearly horizontal to the left stays on the line through the anchor
+    let q = constrain_angle(&anchor, &Vec2::new(-30.0, 12.0), FRAC_PI_4);
+    assert!((q.y - 10.0).abs() < EPS && (q.x + 30.0).abs() < EPS);
+
+    assert_eq!(constrain_angle(&anchor, &anchor, FRAC_PI_4), anchor);
+}
@@ -0,0 +1,156 @@
+// SVG path commands of arcs and the exported document structure.
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::biarc::*;
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::svg::*;
+
+fn arc(start: f64, sweep: f64) -> ArcData<f64> {
+    ArcData {
+        center: Vec2::new(10.0, 20.0),
+        radius: 5.0,
+        start,
+        sweep,
+    }
+}
+
+#[test]
+fn arc_flags() {
+    // quarter circle, +x towards +y
+    assert_eq!(
+        arc_path(&arc(0.0, FRAC_PI_2), 3),
+        "M 15,20 A 5,5 0 0 1 10,25"
+    );
+    // same endpoints the other way round
+    assert_eq!(
+        arc_path(&arc(0.0, -3.0 * FRAC_PI_2), 3),
+        "M 15,20 A 5,5 0 1 0 10,25"
+    );
+    assert_eq!(
+        arc_path(&arc(PI, -FRAC_PI_2), 3),
+        "M 5,20 A 5,5 0 0 0 10,25"
+    );
+    assert_eq!(
+        arc_path(&arc(PI, 3.0 * FRAC_PI_2), 3),
+        "M 5,20 A 5,5 0 1 1 10,25"
+    );
+}
+
+#[test]
+fn full_circle_is_split() {
+    assert_eq!(
+        arc_path(&arc(0.0, 2.0 * PI), 3),
+        "M 15,20 A 5,5 0 0 1 5,20 A 5,5 0 0 1 15,20"
+    );
+}
+
+#[test]
+fn number_format() {
+    assert_eq!(format_num(1.5, 3), "1.5");
+    assert_eq!(format_num(2.0, 3), "2");
+    assert_eq!(format_num(-0.0001, 3), "0");
+    assert_eq!(format_num(123.45678, 2), "123.46");
+    assert_eq!(format_num(100.0, 0), "100");
+}
+
+#[test]
+fn document_contents() {
+    let split_num = 2;
+    let depth = split_num + 1;
+    let fitted = FittedPath::new(
+        BezierPath::<f64>::default(),
+        split_num,
+        false,
+        &Tolerance::default(),
+    );
+    let paths = [fitted];
+
+    let mut options = SvgOptions::default();
+    let svg = export_svg(&paths, &options);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains(r#"<path d="M 50,100 C 200,300 400,300 550,100"/>"#));
+    assert!(svg.contains(r#"<path d="M 50,100 L 200,300 400,300 550,100"/>"#));
+    // 4 biarcs, 8 arcs
+    assert_eq!(svg.matches(" A ").count(), 8);
+    // only the leaf boxes
+    assert_eq!(svg.matches("<rect").count(), 8);
+    assert_eq!(svg.matches("<circle").count(), 4);
+
+    options.aabb_depth = depth + 1;
+    options.control_mesh = false;
+    let svg = export_svg(&paths, &options);
+    assert_eq!(
+        svg.matches("<rect").count(),
+        2usize.pow(depth as u32 + 1) - 1
+    );
+    assert!(!svg.contains("mesh") && !svg.contains("<circle"));
+
+    options.aabb_depth = 0;
+    assert!(!export_svg(&paths, &options).contains("<rect"));
+}
+
+#[test]
+fn closed_path() {
+    let mut path =
+        BezierPath::from_curve(BezierCurve::line(Vec2::new(0.0, 0.0), Vec2::new(30.0, 0.0)));
+    path.segments.push(BezierCurve::line(
+        Vec2::new(30.0, 0.0),
+        Vec2::new(0.0, 30.0),
+    ));
+    path.segments
+        .push(BezierCurve::line(Vec2::new(0.0, 30.0), Vec2::new(0.0, 0.0)));
+    path.closed = true;
+    let paths = [FittedPath::new(path, 1, false, &Tolerance::default())];
+
+    let svg = export_svg(&paths, &SvgOptions::default());
+    assert!(
+        svg.contains(r#"<path d="M 0,0 C 10,0 20,0 30,0 C 20,10 10,20 0,30 C 0,20 0,10 0,0 Z"/>"#)
+    );
+    assert_eq!(svg.matches(" A ").count(), 3 * 4);
+}
+
+#[test]
+fn path_styles_override_the_scene() {
+    let fit = || {
+        FittedPath::new(
+            BezierPath::<f64>::default(),
+            1,
+            false,
+            &Tolerance::default(),
+        )
+    };
+    let paths = [fit(), fit()];
+    let options = SvgOptions {
+        path_styles: vec![PathStyle {
+            is_dotted: true,
+            is_meshed: false,
+            aabb_depth: 2,
+        }],
+        ..SvgOptions::default()
+    };
+    assert_eq!(options.path_style(1).aabb_depth, options.aabb_depth);
+
+    let svg = export_svg(&paths, &options);
+    // the second path falls back to the scene settings
+    assert_eq!(svg.matches(r#"stroke-dasharray="4""#).count(), 1);
+    assert_eq!(svg.matches("<circle").count(), 4);
+    assert_eq!(svg.matches(" L ").count(), 1);
+    assert_eq!(svg.matches("<rect").count(), 4 + 2 + 4);
+}
+
+#[test]
+fn view_box_origin() {
+    let options = SvgOptions {
+        origin: Vec2::new(-10.0, -20.5),
+        width: 40.0,
+        height: 30.0,
+        ..SvgOptions::default()
+    };
+    let svg = export_svg::<f64>(&[], &options);
+    assert!(svg.contains(r#"width="40" height="30" viewBox="-10 -20.5 40 30""#));
+}
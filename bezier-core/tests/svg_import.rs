@@ -0,0 +1,230 @@
+// SVG path data parsing: every command, absolute and relative, and the conversions.
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::path::BezierPath;
+use bezier_core::point::Vec2;
+use bezier_core::svg_path::*;
+use bezier_core::util::distance;
+
+const EPS: f64 = 1e-9;
+
+fn point_at(curve: &BezierCurve<f64>, t: f64) -> Vec2<f64> {
+    let mut point = Vec2::zero();
+    curve.cubic_curve_to(&mut point, t);
+    point
+}
+
+fn assert_close(a: Vec2<f64>, b: Vec2<f64>, eps: f64) {
+    assert!(distance(&a, &b) < eps, "{:?} != {:?}", a, b);
+}
+
+fn assert_same(a: &[BezierPath<f64>], b: &[BezierPath<f64>]) {
+    assert_eq!(a.len(), b.len());
+    for (pa, pb) in a.iter().zip(b.iter()) {
+        assert_eq!(pa.closed, pb.closed);
+        assert_eq!(pa.segments.len(), pb.segments.len());
+        for (sa, sb) in pa.segments.iter().zip(pb.segments.iter()) {
+            for k in 0..4 {
+                assert_close(sa.control_pts[k], sb.control_pts[k], EPS);
+            }
+        }
+    }
+}
+
+/// Segments are connected, and closed paths end at their start
+fn assert_connected(path: &BezierPath<f64>) {
+    for pair in path.segments.windows(2) {
+        assert_eq!(pair[0].control_pts[3], pair[1].control_pts[0]);
+    }
+    if path.closed {
+        assert_eq!(path.end(), path.start());
+    }
+}
+
+#[test]
+fn relative_matches_absolute() {
+    let absolute = parse_path_data(
+        "M 10 20 L 30 20 H 50 V 40 C 60 50 70 50 80 40 S 100 30 110 40 \
+         Q 120 60 130 40 T 150 40 A 10 10 0 0 1 170 40 Z",
+    )
+    .unwrap();
+    let relative = parse_path_data(
+        "m10,20l20,0h20v20c10,10 20,10 30,0s20-10 30,0q10,20 20,0t20,0a10,10 0 0,1 20,0z",
+    )
+    .unwrap();
+    assert_same(&absolute, &relative);
+
+    assert_eq!(absolute.len(), 1);
+    let path = &absolute[0];
+    assert!(path.closed);
+    assert_connected(path);
+    assert_eq!(path.start(), Some(Vec2::new(10.0, 20.0)));
+}
+
+#[test]
+fn lines_are_straight_cubics() {
+    let paths = parse_path_data("M0 0 30 0 30 30").unwrap();
+    let segments = &paths[0].segments;
+    assert_eq!(segments.len(), 2);
+    assert_eq!(
+        segments[0].control_pts,
+        [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            Vec2::new(30.0, 0.0)
+        ]
+    );
+}
+
+#[test]
+fn quadratic_is_elevated_exactly() {
+    let paths = parse_path_data("M 0 0 Q 50 100 100 0").unwrap();
+    let cubic = &paths[0].segments[0];
+    for i in 0..=10 {
+        let t = i as f64 / 10.0;
+        let s = 1.0 - t;
+        let expected = Vec2::new(2.0 * s * t * 50.0 + t * t * 100.0, 2.0 * s * t * 100.0);
+        assert_close(point_at(cubic, t), expected, EPS);
+    }
+}
+
+#[test]
+fn smooth_commands_reflect_controls() {
+    let paths =
+        parse_path_data("M0 0 C 0 10 10 10 10 0 S 20 -10 20 0 M 0 0 Q 5 10 10 0 T 20 0").unwrap();
+    assert_eq!(paths.len(), 2);
+
+    let cubic = &paths[0].segments[1];
+    assert_eq!(cubic.control_pts[1], Vec2::new(10.0, -10.0));
+
+    // T mirrors the quadratic control (5, 10) to (15, -10)
+    let expected = BezierCurve::quadratic(
+        Vec2::new(10.0, 0.0),
+        Vec2::new(15.0, -10.0),
+        Vec2::new(20.0, 0.0),
+    );
+    assert_eq!(paths[1].segments[1], expected);
+
+    // without a previous curve, the current point is the control
+    let paths = parse_path_data("M0 0 L 10 0 S 20 10 30 0").unwrap();
+    assert_eq!(paths[0].segments[1].control_pts[1], Vec2::new(10.0, 0.0));
+}
+
+#[test]
+fn arcs_lie_on_the_ellipse() {
+    let (rx, ry) = (40.0, 20.0);
+    for &(large_arc, sweep) in &[(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let d = format!(
+            "M 100 100 A {} {} 30 {} {} 130 120",
+            rx, ry, large_arc, sweep
+        );
+        let paths = parse_path_data(&d).unwrap();
+        let path = &paths[0];
+        assert_connected(path);
+        assert_eq!(path.start(), Some(Vec2::new(100.0, 100.0)));
+        assert_eq!(path.end(), Some(Vec2::new(130.0, 120.0)));
+        if large_arc == 1 {
+            assert!(path.segments.len() >= 2);
+        }
+
+        // every sample satisfies the ellipse equation of one common center
+        let center = ellipse_center(&path.segments[0], rx, ry, 30f64.to_radians());
+        for segment in path.segments.iter() {
+            for i in 0..=8 {
+                let p = point_at(segment, i as f64 / 8.0) - center;
+                let (sin, cos) = 30f64.to_radians().sin_cos();
+                let (u, v) = (cos * p.x + sin * p.y, -sin * p.x + cos * p.y);
+                let r = (u / rx).powi(2) + (v / ry).powi(2);
+                assert!(
+                    (r - 1.0).abs() < 1e-3,
+                    "flags {} {}: {}",
+                    large_arc,
+                    sweep,
+                    r
+                );
+            }
+        }
+    }
+}
+
+// center of the ellipse through the segment's start, from its start tangent direction
+fn ellipse_center(segment: &BezierCurve<f64>, rx: f64, ry: f64, phi: f64) -> Vec2<f64> {
+    let (sin, cos) = phi.sin_cos();
+    let to_local =
+        |p: Vec2<f64>| Vec2::new((cos * p.x + sin * p.y) / rx, (-sin * p.x + cos * p.y) / ry);
+    let from_local = |p: Vec2<f64>| {
+        let (x, y) = (p.x * rx, p.y * ry);
+        Vec2::new(cos * x - sin * y, sin * x + cos * y)
+    };
+    let p0 = to_local(segment.control_pts[0]);
+    let tangent = to_local(segment.control_pts[1] - segment.control_pts[0]);
+    let normal = tangent.perp() * (1.0 / tangent.dot(&tangent).sqrt());
+    let mid = to_local(point_at(segment, 0.5));
+    // the unit circle center is one radius along the normal, on the side of the curve
+    let sign = if (mid - p0).dot(&normal) > 0.0 {
+        1.0
+    } else {
+        -1.0
+    };
+    from_local(p0 + normal * sign)
+}
+
+#[test]
+fn degenerate_arcs() {
+    // zero radius is a line, coincident endpoints are skipped
+    let paths = parse_path_data("M 0 0 A 0 5 0 0 1 10 0 A 5 5 0 0 1 10 0").unwrap();
+    assert_eq!(paths[0].segments.len(), 1);
+    assert_eq!(
+        paths[0].segments[0],
+        BezierCurve::line(Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0))
+    );
+
+    // radius too small is scaled up to a half circle
+    let paths = parse_path_data("M 0 0 A 1 1 0 0 1 10 0").unwrap();
+    let mid = point_at(&paths[0].segments[0], 1.0);
+    assert!((distance(&mid, &Vec2::new(5.0, 0.0)) - 5.0).abs() < 1e-6);
+}
+
+#[test]
+fn compact_numbers_and_flags() {
+    let compact = parse_path_data("M.5.5l1e1-2E0a5 5 0 015 5").unwrap();
+    let spaced = parse_path_data("M 0.5 0.5 L 10.5 -1.5 A 5 5 0 0 1 15.5 3.5").unwrap();
+    assert_same(&compact, &spaced);
+}
+
+#[test]
+fn subpaths_after_close() {
+    let paths = parse_path_data("M 0 0 L 10 0 L 10 10 Z L 0 10 Z m 20 0 l 5 5").unwrap();
+    assert_eq!(paths.len(), 3);
+    assert!(paths[0].closed && paths[1].closed && !paths[2].closed);
+    // drawing after Z continues from the subpath start
+    assert_eq!(paths[1].start(), Some(Vec2::new(0.0, 0.0)));
+    assert_eq!(paths[1].segments.len(), 2);
+    // relative moveto after Z is relative to the subpath start
+    assert_eq!(paths[2].start(), Some(Vec2::new(20.0, 0.0)));
+    for path in paths.iter() {
+        assert_connected(path);
+    }
+}
+
+#[test]
+fn errors() {
+    assert!(parse_path_data("L 10 10").is_err());
+    assert_eq!(parse_path_data("M 0 0 L 10").unwrap_err().position, 10);
+    assert!(parse_path_data("M 0 0 X 1 1").is_err());
+    assert!(parse_path_data("M 0 0 A 5 5 0 2 0 1 1").is_err());
+    assert!(parse_path_data("").unwrap().is_empty());
+}
+
+#[test]
+fn document_paths() {
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+        <pathology d="M 0 0 L 1 1"/>
+        <g><path id="a" fill="red" d="M 0 0 L 10 0"/></g>
+        <path d='M 0 0 C 1 1 2 2 3 3 M 5 5 L 6 6'></path>
+    </svg>"#;
+    let paths = import_svg(svg).unwrap();
+    assert_eq!(paths.len(), 3);
+    assert_eq!(paths[0].end(), Some(Vec2::new(10.0, 0.0)));
+}
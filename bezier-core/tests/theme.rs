@@ -0,0 +1,88 @@
+// Built-in themes and theme files.
+
+use bezier_core::theme::*;
+use bezier_core::util::rgba8;
+
+#[test]
+fn builtin_themes_by_name() {
+    for name in THEMES.iter() {
+        assert_eq!(Theme::named(name).unwrap().name, *name);
+    }
+    assert_eq!(Theme::named("solarized"), None);
+    assert_eq!(Theme::default(), Theme::light());
+}
+
+#[test]
+fn colorblind_arcs_differ_in_lightness() {
+    // the alternating arcs must stay apart without hue, as in grayscale
+    let luma = |c: [f32; 4]| 0.299 * c[0] + 0.587 * c[1] + 0.114 * c[2];
+    let theme = Theme::colorblind();
+    assert!((luma(theme.left_arc) - luma(theme.right_arc)).abs() > 0.2);
+}
+
+#[test]
+fn box_colors_repeat_by_depth() {
+    let theme = Theme::light();
+    let n = theme.boxes.len();
+    assert_ne!(theme.box_color(0), theme.box_color(1));
+    assert_eq!(theme.box_color(1), theme.box_color(n + 1));
+    assert_eq!(theme.arc_color(2), theme.left_arc);
+    assert_eq!(theme.arc_color(3), theme.right_arc);
+}
+
+#[test]
+fn file_colors_apply_over_base() {
+    let theme = Theme::parse(
+        "# night\n\
+         name = night\n\
+         base = dark\n\
+         \n\
+         background = #000000\n\
+         left-arc = #ff000080\n\
+         boxes = #00ff00, #0000ff\n",
+    )
+    .unwrap();
+    assert_eq!(theme.name, "night");
+    assert_eq!(theme.background, rgba8(0, 0, 0, 1.0));
+    assert_eq!(theme.left_arc, rgba8(255, 0, 0, 128.0 / 255.0));
+    assert_eq!(
+        theme.boxes,
+        vec![rgba8(0, 255, 0, 1.0), rgba8(0, 0, 255, 1.0)]
+    );
+    assert_eq!(theme.right_arc, Theme::dark().right_arc);
+
+    assert_eq!(Theme::parse("").unwrap().name, "custom");
+}
+
+#[test]
+fn construction_and_selection_colors() {
+    let theme = Theme::parse(
+        "construction = #111111, #222222
+         selection = #333333
+",
+    )
+    .unwrap();
+    assert_eq!(theme.selection, rgba8(51, 51, 51, 1.0));
+    assert_eq!(theme.construction_color(0), rgba8(17, 17, 17, 1.0));
+    // deeper levels keep the last color
+    assert_eq!(theme.construction_color(2), rgba8(34, 34, 34, 1.0));
+    for name in THEMES.iter() {
+        let theme = Theme::named(name).unwrap();
+        assert_eq!(theme.construction.len(), 3, "{}", name);
+        assert_ne!(theme.selection, theme.background, "{}", name);
+    }
+}
+
+#[test]
+fn bad_lines_are_reported() {
+    let line = |text: &str| match Theme::parse(text) {
+        Err(ThemeError::Parse { line, .. }) => line,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(line("curve = #000000\nsky = #ffffff"), 2);
+    assert_eq!(line("curve = red"), 1);
+    assert_eq!(line("curve = #12345"), 1);
+    assert_eq!(line("\nbase = solarized"), 2);
+    assert_eq!(line("boxes = #000000,"), 1);
+    assert_eq!(line("background"), 1);
+}
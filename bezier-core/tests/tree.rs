@@ -0,0 +1,42 @@
+// Index arithmetic of complete trees and the parameter intervals of biarc tree nodes.
+
+use bezier_core::biarc::*;
+use bezier_core::tree::*;
+
+#[test]
+fn complete_helpers_match_tree_links() {
+    let depth = 3;
+    let tree = Tree::new_complete(depth, |i| i);
+    for index in 0..tree.len() {
+        let node = tree.get(index).unwrap();
+        assert_eq!(complete_parent(index), tree.parent(node).map(|n| n.value));
+        assert_eq!(
+            complete_children(index, tree.len()),
+            tree.left(node)
+                .map(|l| (l.value, tree.right(node).unwrap().value))
+        );
+    }
+}
+
+#[test]
+fn subtree_membership() {
+    assert!(in_complete_subtree(0, 0));
+    assert!(in_complete_subtree(14, 0));
+    assert!(in_complete_subtree(3, 1));
+    assert!(in_complete_subtree(10, 4));
+    assert!(!in_complete_subtree(5, 1));
+    assert!(!in_complete_subtree(1, 3));
+}
+
+#[test]
+fn node_intervals_halve_down_to_the_biarcs() {
+    let split_num = 2;
+    assert_eq!(node_interval::<f64>(0, split_num), (0.0, 1.0));
+    assert_eq!(node_interval::<f64>(1, split_num), (0.0, 0.5));
+    assert_eq!(node_interval::<f64>(2, split_num), (0.5, 1.0));
+    assert_eq!(node_interval::<f64>(5, split_num), (0.5, 0.75));
+    // both arcs of the biarc at node 5 cover its interval
+    assert_eq!(node_interval::<f64>(11, split_num), (0.5, 0.75));
+    assert_eq!(node_interval::<f64>(12, split_num), (0.5, 0.75));
+    assert_eq!(node_interval::<f64>(14, split_num), (0.75, 1.0));
+}
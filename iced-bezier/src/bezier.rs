@@ -1,226 +1,1496 @@
+use std::cell::Cell;
+
 use iced::{
     canvas::event::{self, Event},
     canvas::{self, Cursor, Frame, Geometry, Path, Stroke},
-    mouse, Color, Point, Rectangle,
+    keyboard, mouse, Color, Point, Rectangle, Size, Vector,
 };
 
-use std::cell::RefCell;
-use std::rc::Rc;
+use bezier_core::bezier::BezierCurve;
+use bezier_core::biarc::*;
+use bezier_core::dxf::*;
+use bezier_core::gcode::*;
+use bezier_core::keymap::Action;
+use bezier_core::overlay::Overlay;
+use bezier_core::path::*;
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::project::*;
+use bezier_core::samples::*;
+use bezier_core::snap::*;
+use bezier_core::svg::*;
+use bezier_core::theme::Theme;
+use bezier_core::tree::*;
+use bezier_core::util::*;
 
 use crate::biarc::*;
-use crate::tree::*;
-use crate::util::*;
+use crate::construction::*;
+use crate::history::*;
+use crate::viewport::Viewport;
+use crate::Message;
+
+/// Step of the Shift angle constraint
+const ANGLE_STEP: Real = std::f64::consts::PI / 12.0;
+/// Pick radius of snap targets and curves, in pixels
+const SNAP_RADIUS: f32 = 8.0;
+/// Grid lines closer than this many pixels are thinned out
+const MIN_GRID_PIXELS: f32 = 8.0;
+/// Offset of duplicated curves, in world units
+const DUPLICATE_OFFSET: Real = 20.0;
+
+/// Scalar type of the geometry kernel; drawing converts it to `f32`.
+pub type Real = f64;
+
+/// Control point `index` (0-3) of `segment` of the curve `curve`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointId {
+    pub curve: usize,
+    pub segment: usize,
+    pub index: usize,
+}
+
+/// Node `index` of the arc tree of `segment` of the curve `curve`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeId {
+    pub curve: usize,
+    pub segment: usize,
+    pub index: usize,
+}
+
+/// What the node panel shows about a node of an arc tree
+#[derive(Debug, Clone)]
+pub struct NodeInfo {
+    pub id: NodeId,
+    pub depth: usize,
+    /// Parameter interval of the cubic the node covers
+    pub interval: (Real, Real),
+    pub aabb: AABB<Real>,
+    /// Rounding radius of the box
+    pub radius: Real,
+    /// Set on leaves
+    pub arc: Option<ArcData<Real>>,
+    pub parent: Option<usize>,
+    pub children: Option<(usize, usize)>,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Control {
-    Moving(usize, Point),
+    Moving(PointId, Point),
+    /// Middle-button drag of the view, from the last cursor position
+    Panning(Point),
+    /// Box drag selecting the curves inside, from `start`
+    Selecting {
+        start: Point,
+        end: Point,
+        additive: bool,
+    },
     Static,
 }
 
+/// A curve of the scene, with its own arc trees and settings
+#[derive(Debug)]
+pub struct Curve {
+    pub fitted: FittedPath<Real>,
+    pub display: Display,
+}
+
+impl Curve {
+    fn new(path: BezierPath<Real>, display: Display, tolerance: &Tolerance<Real>) -> Curve {
+        Curve {
+            fitted: FittedPath::new(path, display.num_split, display.use_bezier_aabb, tolerance),
+            display,
+        }
+    }
+
+    fn rebuild(&mut self, tolerance: &Tolerance<Real>) {
+        self.fitted.rebuild(
+            self.display.num_split,
+            self.display.use_bezier_aabb,
+            tolerance,
+        );
+    }
+
+    fn saved(&self) -> SavedCurve {
+        SavedCurve {
+            path: self.fitted.path.clone(),
+            display: self.display.clone(),
+        }
+    }
+}
+
+/// How the boxes of the arc trees are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxStyle {
+    /// Opacity of the box fill relative to its outline, `0..=1`
+    pub fill_opacity: f32,
+    pub outline_only: bool,
+}
+
+impl Default for BoxStyle {
+    fn default() -> Self {
+        BoxStyle {
+            fill_opacity: 0.15,
+            outline_only: false,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct State {
     cache: canvas::Cache,
-    curve: BezierCurve,
-    arcs: Rc<RefCell<Tree<ArcBox>>>,
+    curves: Vec<Curve>,
     control: Control,
-    pub is_dotted: bool,
-    pub is_meshed: bool,
-    pub use_bezier_aabb: bool,
-    pub num_split: usize,
-    pub aabb_depth: usize,
-    pub left_color: Color,
-    pub right_color: Color,
+    history: History,
+    // position of the dragged point when the drag started
+    drag_start: Vec2<Real>,
+    // set in `draw` when a fit was requested, since only `draw` knows the canvas size
+    viewport: Cell<Viewport>,
+    fit_pending: Cell<bool>,
+    // size of the canvas at the last draw, for zooming from the keyboard
+    size: Cell<Size>,
+    // selected curves, ascending; the settings and exports apply to them, or to all if empty
+    selection: Vec<usize>,
+    // last grabbed control point, shown in the point panel
+    selected: Option<PointId>,
+    // clicked arc tree node, shown in the node panel with its subtree highlighted
+    selected_node: Option<NodeId>,
+    // arc or box under the cursor
+    hovered_node: Option<NodeId>,
+    // Shift constrains drags to angle steps, Ctrl adds to the selection
+    modifiers: keyboard::Modifiers,
+    /// Settings of new curves
+    display: Display,
+    pub tolerance: Tolerance<Real>,
+    /// Colors of the canvas; the arc colors are saved with the project
+    pub theme: Theme,
+    pub show_grid: bool,
+    /// World units between grid lines
+    pub grid_spacing: Real,
+    pub snap_grid: bool,
+    pub snap_points: bool,
+    pub snap_curves: bool,
+    /// Circles and tangents drawn over every biarc
+    pub overlay: Overlay,
+    pub box_style: BoxStyle,
+    /// Shortcuts shown over the canvas, if any
+    pub help: Option<Vec<(String, &'static str)>>,
+    /// Draw the de Casteljau and biarc construction at `construction_t`
+    pub show_construction: bool,
+    /// Parameter of the construction over the whole edited curve, `0..=1`
+    pub construction_t: Real,
 }
 
 impl State {
     pub fn new() -> State {
-        let default_num_split = 1;
-        let curve = BezierCurve::default();
-        let depth = default_num_split + 1;
-        let arcs = Rc::new(RefCell::new(Tree::new_complete(
-            depth,
-            ArcBox::arc_builder(depth),
-        )));
-
-        curve.build_biarc(arcs.clone(), default_num_split, false);
+        State::from_project(&Project::default())
+    }
 
+    pub fn from_project(project: &Project) -> State {
+        let tolerance = Tolerance::default();
         State {
             cache: Default::default(),
-            curve,
-            arcs,
+            curves: project_curves(project, &tolerance),
             control: Control::Static,
-            is_dotted: false,
-            is_meshed: true,
-            use_bezier_aabb: false,
-            num_split: default_num_split,
-            aabb_depth: 1usize,
-            left_color: Color::from_rgba8(40, 210, 0, 1.0),
-            right_color: Color::from_rgba8(30, 0, 210, 1.0),
+            history: History::default(),
+            drag_start: Vec2::zero(),
+            viewport: Cell::new(Viewport::default()),
+            fit_pending: Cell::new(false),
+            size: Cell::new(Size::ZERO),
+            selection: Vec::new(),
+            selected: None,
+            selected_node: None,
+            hovered_node: None,
+            modifiers: keyboard::Modifiers::default(),
+            display: project.display.clone(),
+            tolerance,
+            theme: with_colors(Theme::default(), &project.colors),
+            show_grid: false,
+            grid_spacing: 20.0,
+            snap_grid: false,
+            snap_points: true,
+            snap_curves: false,
+            overlay: Overlay::default(),
+            box_style: BoxStyle::default(),
+            help: None,
+            show_construction: false,
+            construction_t: 0.5,
+        }
+    }
+
+    pub fn to_project(&self) -> Project {
+        Project {
+            version: FORMAT_VERSION,
+            curves: self
+                .curves
+                .iter()
+                .map(|curve| curve.fitted.path.clone())
+                .collect(),
+            display: self.display.clone(),
+            curve_display: self
+                .curves
+                .iter()
+                .map(|curve| curve.display.clone())
+                .collect(),
+            colors: Colors {
+                left_arc: self.theme.left_arc,
+                right_arc: self.theme.right_arc,
+            },
+        }
+    }
+
+    // the selected curves, or all of them
+    fn targets(&self) -> Vec<usize> {
+        if self.selection.is_empty() {
+            (0..self.curves.len()).collect()
+        } else {
+            self.selection.clone()
+        }
+    }
+
+    // fresh fits of the curves that exports apply to, with `map` applied to their paths
+    fn export_curves(&self, map: impl Fn(&mut [BezierPath<Real>])) -> Vec<FittedPath<Real>> {
+        let targets = self.targets();
+        let mut paths: Vec<_> = targets
+            .iter()
+            .map(|&i| self.curves[i].fitted.path.clone())
+            .collect();
+        map(&mut paths);
+        paths
+            .into_iter()
+            .zip(targets.iter())
+            .map(|(path, &i)| {
+                let display = &self.curves[i].display;
+                FittedPath::new(
+                    path,
+                    display.num_split,
+                    display.use_bezier_aabb,
+                    &self.tolerance,
+                )
+            })
+            .collect()
+    }
+
+    // display settings of the curves that exports apply to, in `export_curves` order
+    fn export_styles(&self) -> Vec<PathStyle> {
+        self.targets()
+            .iter()
+            .map(|&i| {
+                let display = &self.curves[i].display;
+                PathStyle {
+                    is_dotted: display.is_dotted,
+                    is_meshed: display.is_meshed,
+                    aabb_depth: display.aabb_depth,
+                }
+            })
+            .collect()
+    }
+
+    /// SVG document of the selected curves, or of all of them
+    pub fn to_svg(&self) -> String {
+        // SVG is y-down
+        let curves = self.export_curves(|paths| mirror_y(paths));
+
+        // the bounding box of the control points and the root boxes, with a margin
+        let margin = 20.0;
+        let mut min = Vec2::new(Real::INFINITY, Real::INFINITY);
+        let mut max = Vec2::new(Real::NEG_INFINITY, Real::NEG_INFINITY);
+        let mut extend = |lo: Vec2<Real>, hi: Vec2<Real>| {
+            min = Vec2::new(min.x.min(lo.x), min.y.min(lo.y));
+            max = Vec2::new(max.x.max(hi.x), max.y.max(hi.y));
+        };
+        for fitted in curves.iter() {
+            for segment in fitted.path.segments.iter() {
+                for pt in segment.control_pts.iter() {
+                    extend(*pt, *pt);
+                }
+            }
+            for arcs in fitted.arcs.iter() {
+                if let Some(root) = arcs.borrow().get(0) {
+                    let (aabb, r) = (&root.aabb, root.radius);
+                    extend(
+                        Vec2::new(aabb.x - r, aabb.y - r),
+                        Vec2::new(aabb.x + aabb.w + r, aabb.y + aabb.h + r),
+                    );
+                }
+            }
+        }
+        if min.x > max.x {
+            min = Vec2::zero();
+            max = Vec2::zero();
+        }
+
+        let options = SvgOptions {
+            origin: Vec2::new(min.x - margin, min.y - margin),
+            width: max.x - min.x + 2.0 * margin,
+            height: max.y - min.y + 2.0 * margin,
+            path_styles: self.export_styles(),
+            left_color: self.theme.left_arc,
+            right_color: self.theme.right_arc,
+            ..SvgOptions::default()
+        };
+        export_svg(&curves, &options)
+    }
+
+    /// G-code cutting the biarcs of the selected curves, or of all of them
+    pub fn to_gcode(&self, options: &GcodeOptions) -> String {
+        let chains: Vec<_> = self
+            .export_curves(|_| {})
+            .iter()
+            .map(|fitted| fitted.leaves())
+            .collect();
+        export_gcode(&chains, options)
+    }
+
+    /// DXF document of the biarcs of the selected curves, or of all of them
+    /// with the boxes of each curve's own AABB depth
+    pub fn to_dxf(&self, options: &DxfOptions) -> String {
+        let options = DxfOptions {
+            aabb_depths: self
+                .export_styles()
+                .iter()
+                .map(|style| style.aabb_depth)
+                .collect(),
+            ..options.clone()
+        };
+        export_dxf(&self.export_curves(|_| {}), &options)
+    }
+
+    /// Sample table and leaf table of the selected curves, or of all of them, as CSV
+    pub fn to_csv(&self, options: &SampleOptions) -> (String, String) {
+        let curves = self.export_curves(|_| {});
+        (
+            samples_csv(&sample_paths(&curves, options), 6),
+            leaves_csv(&leaf_table(&curves), 6),
+        )
+    }
+
+    /// Both tables as one JSON document
+    pub fn to_samples_json(&self, options: &SampleOptions) -> String {
+        let curves = self.export_curves(|_| {});
+        export_json(&sample_paths(&curves, options), &leaf_table(&curves), 6)
+    }
+
+    /// Add imported curves on top of the scene, and select them
+    pub fn add_curves(&mut self, paths: Vec<BezierPath<Real>>) {
+        if paths.is_empty() {
+            return;
+        }
+        let index = self.curves.len();
+        let curves: Vec<_> = paths
+            .into_iter()
+            .map(|path| SavedCurve {
+                path,
+                display: self.display.clone(),
+            })
+            .collect();
+        let count = curves.len();
+        self.commit(Edit::InsertCurves { index, curves });
+        self.selection = (index..index + count).collect();
+    }
+
+    /// Add a copy of the initial curve
+    pub fn add_curve(&mut self) {
+        self.add_curves(vec![BezierPath::default()]);
+    }
+
+    pub fn curve_count(&self) -> usize {
+        self.curves.len()
+    }
+
+    pub fn selection(&self) -> &[usize] {
+        &self.selection
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection = (0..self.curves.len()).collect();
+        self.request_redraw();
+    }
+
+    fn select(&mut self, curve: usize, additive: bool) {
+        if !additive {
+            self.selection.clear();
+        }
+        match self.selection.binary_search(&curve) {
+            Ok(i) if additive => {
+                self.selection.remove(i);
+            }
+            Ok(_) => {}
+            Err(i) => self.selection.insert(i, curve),
+        }
+        self.request_redraw();
+    }
+
+    /// Remove the selected curves; false if none is selected
+    pub fn delete_selected(&mut self) -> bool {
+        if self.selection.is_empty() {
+            return false;
+        }
+        // from the top, so the indices below stay valid
+        let edits = self
+            .selection
+            .iter()
+            .rev()
+            .map(|&i| Edit::RemoveCurves {
+                index: i,
+                curves: vec![self.curves[i].saved()],
+            })
+            .collect();
+        self.commit(Edit::Batch(edits));
+        true
+    }
+
+    /// Copy the selected curves on top of the scene, slightly offset, and select the copies
+    pub fn duplicate_selected(&mut self) -> bool {
+        if self.selection.is_empty() {
+            return false;
+        }
+        let offset = Vec2::new(DUPLICATE_OFFSET, -DUPLICATE_OFFSET);
+        let curves: Vec<_> = self
+            .selection
+            .iter()
+            .map(|&i| {
+                let mut saved = self.curves[i].saved();
+                for segment in saved.path.segments.iter_mut() {
+                    for p in segment.control_pts.iter_mut() {
+                        *p = *p + offset;
+                    }
+                }
+                saved
+            })
+            .collect();
+        let index = self.curves.len();
+        let count = curves.len();
+        self.commit(Edit::InsertCurves { index, curves });
+        self.selection = (index..index + count).collect();
+        true
+    }
+
+    /// Move the selected curves one step up the z-order
+    pub fn raise_selected(&mut self) -> bool {
+        let mut order: Vec<usize> = (0..self.curves.len()).collect();
+        for i in (0..order.len().saturating_sub(1)).rev() {
+            if self.is_selected(order[i]) && !self.is_selected(order[i + 1]) {
+                order.swap(i, i + 1);
+            }
+        }
+        self.reorder(order)
+    }
+
+    /// Move the selected curves one step down the z-order
+    pub fn lower_selected(&mut self) -> bool {
+        let mut order: Vec<usize> = (0..self.curves.len()).collect();
+        for i in 1..order.len() {
+            if self.is_selected(order[i]) && !self.is_selected(order[i - 1]) {
+                order.swap(i - 1, i);
+            }
+        }
+        self.reorder(order)
+    }
+
+    /// Draw the selected curves above all others
+    pub fn selected_to_front(&mut self) -> bool {
+        let (selected, others): (Vec<usize>, Vec<usize>) =
+            (0..self.curves.len()).partition(|&i| self.is_selected(i));
+        self.reorder(others.into_iter().chain(selected).collect())
+    }
+
+    /// Draw the selected curves below all others
+    pub fn selected_to_back(&mut self) -> bool {
+        let (selected, others): (Vec<usize>, Vec<usize>) =
+            (0..self.curves.len()).partition(|&i| self.is_selected(i));
+        self.reorder(selected.into_iter().chain(others).collect())
+    }
+
+    fn is_selected(&self, curve: usize) -> bool {
+        self.selection.binary_search(&curve).is_ok()
+    }
+
+    // apply a new z-order keeping the selection; false if nothing moves
+    fn reorder(&mut self, order: Vec<usize>) -> bool {
+        if order.iter().enumerate().all(|(i, &old)| i == old) {
+            return false;
+        }
+        let selection = (0..order.len())
+            .filter(|&i| self.is_selected(order[i]))
+            .collect();
+        self.commit(Edit::Reorder { order });
+        self.selection = selection;
+        true
+    }
+
+    // the curve that segment edits apply to: the first selected, else the top one
+    fn edited_curve(&self) -> Option<usize> {
+        self.selection
+            .first()
+            .copied()
+            .or_else(|| self.curves.len().checked_sub(1))
+    }
+
+    /// Continue the edited curve with a straight segment along its end tangent;
+    /// false if there is no open curve to extend
+    pub fn append_segment(&mut self) -> bool {
+        let curve = match self.edited_curve() {
+            Some(curve) if !self.curves[curve].fitted.path.closed => curve,
+            _ => return false,
+        };
+        let last = match self.curves[curve].fitted.path.segments.last() {
+            Some(last) => last.control_pts,
+            None => return false,
+        };
+        let end = last[3];
+        let mut step = end - last[2];
+        if step.dot(&step) < 1.0 {
+            step = Vec2::new(50.0, 0.0);
+        }
+        let segment = BezierCurve {
+            control_pts: [end, end + step, end + step * 2.0, end + step * 3.0],
+        };
+        self.commit(Edit::AppendSegment { curve, segment });
+        true
+    }
+
+    /// Remove the last segment of the edited curve, keeping at least one;
+    /// false if there is nothing to remove
+    pub fn pop_segment(&mut self) -> bool {
+        let curve = match self.edited_curve() {
+            Some(curve) => curve,
+            None => return false,
+        };
+        let path = &self.curves[curve].fitted.path;
+        if path.closed || path.segments.len() < 2 {
+            return false;
+        }
+        let segment = path.segments.last().unwrap().clone();
+        self.commit(Edit::PopSegment { curve, segment });
+        true
+    }
+
+    /// Reset to the initial curve and display settings
+    pub fn initialize(&mut self) {
+        let from = Box::new(self.to_project());
+        let to = Box::new(Project {
+            colors: from.colors.clone(),
+            ..Project::default()
+        });
+        if from != to {
+            self.commit(Edit::Replace { from, to });
+        }
+    }
+
+    /// Revert the last edit; false if there is none or a point is being dragged
+    pub fn undo(&mut self) -> bool {
+        if self.control != Control::Static {
+            return false;
+        }
+        match self.history.undo() {
+            Some(edit) => {
+                self.apply(&edit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Apply the last undone edit again
+    pub fn redo(&mut self) -> bool {
+        if self.control != Control::Static {
+            return false;
+        }
+        match self.history.redo() {
+            Some(edit) => {
+                self.apply(&edit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Apply an edit and record it for undo
+    pub fn commit(&mut self, edit: Edit) {
+        self.apply(&edit);
+        self.history.push(edit);
+    }
+
+    /// Remember where a grabbed control point starts, for `end_drag`
+    pub fn start_drag(&mut self, id: PointId) {
+        self.drag_start =
+            self.curves[id.curve].fitted.path.segments[id.segment].control_pts[id.index];
+    }
+
+    /// Move a grabbed control point without recording the step
+    pub fn drag_point(&mut self, id: PointId, to: Vec2<Real>) {
+        let curve = &mut self.curves[id.curve];
+        curve.fitted.path.set_point(id.segment, id.index, to);
+        curve.rebuild(&self.tolerance);
+    }
+
+    /// Record the whole drag since `start_drag` as one edit, if the point moved
+    pub fn end_drag(&mut self, id: PointId) {
+        // the drag already moved the point
+        let to = self.curves[id.curve].fitted.path.segments[id.segment].control_pts[id.index];
+        if to != self.drag_start {
+            self.history.push(Edit::MovePoint {
+                id,
+                from: self.drag_start,
+                to,
+            });
         }
     }
 
+    fn apply(&mut self, edit: &Edit) {
+        match edit {
+            Edit::MovePoint { id, to, .. } => {
+                let curve = &mut self.curves[id.curve];
+                curve.fitted.path.set_point(id.segment, id.index, *to);
+                curve.rebuild(&self.tolerance);
+            }
+            Edit::SetDisplay { to, .. } => {
+                self.display = to.clone();
+            }
+            Edit::SetCurveDisplay { curve, to, .. } => {
+                let curve = &mut self.curves[*curve];
+                let rebuild = to.num_split != curve.display.num_split
+                    || to.use_bezier_aabb != curve.display.use_bezier_aabb;
+                curve.display = to.clone();
+                if rebuild {
+                    curve.rebuild(&self.tolerance);
+                }
+            }
+            Edit::AppendSegment { curve, segment } => {
+                let curve = &mut self.curves[*curve];
+                curve.fitted.path.segments.push(segment.clone());
+                curve.rebuild(&self.tolerance);
+            }
+            Edit::PopSegment { curve, .. } => {
+                let curve = &mut self.curves[*curve];
+                curve.fitted.path.segments.pop();
+                curve.rebuild(&self.tolerance);
+            }
+            Edit::InsertCurves { index, curves } => {
+                let inserted: Vec<_> = curves
+                    .iter()
+                    .map(|saved| {
+                        Curve::new(saved.path.clone(), saved.display.clone(), &self.tolerance)
+                    })
+                    .collect();
+                self.curves.splice(*index..*index, inserted);
+                self.forget_selection();
+            }
+            Edit::RemoveCurves { index, curves } => {
+                self.curves.drain(*index..*index + curves.len());
+                self.forget_selection();
+            }
+            Edit::Reorder { order } => {
+                let mut old: Vec<Option<Curve>> = self.curves.drain(..).map(Some).collect();
+                self.curves = order.iter().map(|&i| old[i].take().unwrap()).collect();
+                self.forget_selection();
+            }
+            Edit::Batch(edits) => {
+                for edit in edits.iter() {
+                    self.apply(edit);
+                }
+            }
+            Edit::Replace { to, .. } => {
+                // only what the project holds is replaced; the view and the history stay
+                self.curves = project_curves(to, &self.tolerance);
+                self.display = to.display.clone();
+                self.forget_selection();
+            }
+        }
+        self.request_redraw();
+    }
+
+    // curve indices changed
+    fn forget_selection(&mut self) {
+        self.selection.clear();
+        self.selected = None;
+        self.selected_node = None;
+        self.hovered_node = None;
+    }
+
     pub fn request_redraw(&mut self) {
         self.cache.clear()
     }
 
-    pub fn toggle_dotted(&mut self) {
-        self.is_dotted = !self.is_dotted;
+    /// The point of the point panel and its position, if it still exists
+    pub fn selected_point(&self) -> Option<(PointId, Vec2<Real>)> {
+        let id = self.selected?;
+        let segment = self
+            .curves
+            .get(id.curve)?
+            .fitted
+            .path
+            .segments
+            .get(id.segment)?;
+        Some((id, segment.control_pts[id.index]))
+    }
+
+    /// Move the selected point to typed coordinates; false if there is none
+    pub fn move_selected(&mut self, to: Vec2<Real>) -> bool {
+        match self.selected_point() {
+            Some((id, from)) => {
+                if from != to {
+                    self.commit(Edit::MovePoint { id, from, to });
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn node_info(&self, id: NodeId) -> Option<NodeInfo> {
+        let curve = self.curves.get(id.curve)?;
+        let tree = curve.fitted.arcs.get(id.segment)?.borrow();
+        let node = tree.get(id.index)?;
+        Some(NodeInfo {
+            id,
+            depth: complete_depth(id.index),
+            interval: node_interval(id.index, curve.display.num_split),
+            aabb: node.aabb.clone(),
+            radius: node.radius,
+            arc: node.arc.clone(),
+            parent: complete_parent(id.index),
+            children: complete_children(id.index, tree.len()),
+        })
+    }
+
+    /// The clicked node, else the one under the cursor
+    pub fn selected_node(&self) -> Option<NodeInfo> {
+        self.selected_node
+            .or(self.hovered_node)
+            .and_then(|id| self.node_info(id))
+    }
+
+    /// Select another node of the shown node's tree; false if there is no such node
+    pub fn select_node(&mut self, index: usize) -> bool {
+        let id = match self.selected_node() {
+            Some(info) => NodeId { index, ..info.id },
+            None => return false,
+        };
+        if self.node_info(id).is_none() {
+            return false;
+        }
+        self.selected_node = Some(id);
         self.request_redraw();
+        true
     }
 
-    pub fn toggle_meshed(&mut self) {
-        self.is_meshed = !self.is_meshed;
+    pub fn clear_node(&mut self) {
+        self.selected_node = None;
         self.request_redraw();
     }
 
-    pub fn set_bezier_aabb(&mut self, checked: bool) {
-        self.use_bezier_aabb = checked;
-        self.curve
-            .build_biarc(self.arcs.clone(), self.num_split, self.use_bezier_aabb);
+    // closest arc or visible box outline of a meshed curve near the screen position `p`
+    fn node_at(&self, p: Point, view: &Viewport, size: Size) -> Option<NodeId> {
+        let world = view.to_world(p, size);
+        let reach = view.to_world_length(SNAP_RADIUS);
+        let mut best: Option<(Real, NodeId)> = None;
+        // topmost first, so it wins ties
+        for (curve, fitted) in self.curves.iter().enumerate().rev() {
+            let display = &fitted.display;
+            if !display.is_meshed {
+                continue;
+            }
+            for (segment, arcs) in fitted.fitted.arcs.iter().enumerate() {
+                let tree = arcs.borrow();
+                for index in 0..tree.len() {
+                    let node = tree.get(index).unwrap();
+                    let mut d = match node.arc {
+                        Some(ref arc) => arc.distance(&world),
+                        None => Real::INFINITY,
+                    };
+                    if complete_depth(index) + display.aabb_depth >= display.num_split + 2 {
+                        d = d.min(box_distance(&node.aabb, node.radius, &world).abs());
+                    }
+                    if d <= reach && best.map_or(true, |(best, _)| d < best) {
+                        let id = NodeId {
+                            curve,
+                            segment,
+                            index,
+                        };
+                        best = Some((d, id));
+                    }
+                }
+            }
+        }
+        best.map(|(_, id)| id)
+    }
+
+    pub fn set_show_grid(&mut self, checked: bool) {
+        self.show_grid = checked;
         self.request_redraw();
     }
 
-    pub fn set_num_biarc(&mut self, num_biarc: usize) {
-        self.num_split = num_biarc;
-        if self.aabb_depth > num_biarc + 1 {
-            self.aabb_depth = num_biarc + 1;
+    pub fn set_grid_spacing(&mut self, spacing: Real) {
+        self.grid_spacing = spacing;
+        self.request_redraw();
+    }
+
+    pub fn set_show_construction(&mut self, checked: bool) {
+        self.show_construction = checked;
+        self.request_redraw();
+    }
+
+    pub fn set_construction_t(&mut self, t: Real) {
+        self.construction_t = t.max(0.0).min(1.0);
+        self.request_redraw();
+    }
+
+    /// Move the construction parameter by `step`, wrapping around at the curve end
+    pub fn advance_construction(&mut self, step: Real) {
+        self.construction_t = (self.construction_t + step).rem_euclid(1.0);
+        self.request_redraw();
+    }
+
+    /// Curve of the construction and where its parameter falls
+    pub fn construction_location(&self) -> Option<(usize, Location)> {
+        let curve = self.edited_curve()?;
+        let segments = self.curves[curve].fitted.path.segments.len();
+        if segments == 0 {
+            return None;
+        }
+        let num_split = self.curves[curve].display.num_split;
+        Some((
+            curve,
+            Location::new(segments, num_split, self.construction_t),
+        ))
+    }
+
+    // where a dragged point goes for the cursor at world position `p`
+    fn drag_target(&self, id: PointId, p: Vec2<Real>, view: &Viewport) -> Vec2<Real> {
+        let segment = &self.curves[id.curve].fitted.path.segments[id.segment];
+        if self.modifiers.shift {
+            // handles turn about their end point, end points about where they started
+            let anchor = match id.index {
+                1 => segment.control_pts[0],
+                2 => segment.control_pts[3],
+                _ => self.drag_start,
+            };
+            return constrain_angle(&anchor, &p, ANGLE_STEP);
+        }
+
+        let options = SnapOptions {
+            grid: if self.snap_grid && self.grid_spacing > 0.0 {
+                Some(self.grid_spacing)
+            } else {
+                None
+            },
+            points: self.snap_points,
+            curves: self.snap_curves,
+            radius: view.to_world_length(SNAP_RADIUS),
+        };
+        // not to the point itself (or the joint sharing it), nor to its own curve
+        let current = segment.control_pts[id.index];
+        let points: Vec<_> = self
+            .curves
+            .iter()
+            .enumerate()
+            .flat_map(|(i, curve)| {
+                curve
+                    .fitted
+                    .path
+                    .segments
+                    .iter()
+                    .flat_map(|segment| segment.control_pts.iter().copied())
+                    .filter(move |pt| i != id.curve || *pt != current)
+            })
+            .collect();
+        let curves: Vec<_> = self
+            .curves
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != id.curve)
+            .flat_map(|(_, curve)| curve.fitted.path.segments.iter().cloned())
+            .collect();
+        snap(&p, &options, &points, &curves).0
+    }
+
+    // topmost control point under the screen position `p`
+    fn point_at(&self, p: Point, view: &Viewport, size: Size) -> Option<PointId> {
+        // make clickable range * 1.5
+        let local_rad = PTS_RADIUS * 1.5;
+        for (curve, fitted) in self.curves.iter().enumerate().rev() {
+            for (segment, bezier) in fitted.fitted.path.segments.iter().enumerate() {
+                for index in 0..4 {
+                    let ctr_pts = view.to_screen(&bezier.control_pts[index], size);
+                    if (ctr_pts.x - p.x).abs() <= local_rad && (ctr_pts.y - p.y).abs() <= local_rad
+                    {
+                        return Some(PointId {
+                            curve,
+                            segment,
+                            index,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // topmost curve passing near the screen position `p`
+    fn curve_at(&self, p: Point, view: &Viewport, size: Size) -> Option<usize> {
+        let world = view.to_world(p, size);
+        let reach = view.to_world_length(SNAP_RADIUS);
+        let mut point = Vec2::zero();
+        (0..self.curves.len()).rev().find(|&i| {
+            self.curves[i].fitted.path.segments.iter().any(|segment| {
+                segment.cubic_curve_to(&mut point, segment.nearest_t(&world));
+                distance(&point, &world) <= reach
+            })
+        })
+    }
+
+    // select the curves with every control point inside the screen box `a`-`b`
+    fn select_box(&mut self, a: Point, b: Point, additive: bool, view: &Viewport, size: Size) {
+        let (min, max) = (
+            Point::new(a.x.min(b.x), a.y.min(b.y)),
+            Point::new(a.x.max(b.x), a.y.max(b.y)),
+        );
+        let inside: Vec<usize> = (0..self.curves.len())
+            .filter(|&i| {
+                self.curves[i]
+                    .fitted
+                    .path
+                    .segments
+                    .iter()
+                    .flat_map(|segment| segment.control_pts.iter())
+                    .all(|pt| {
+                        let p = view.to_screen(pt, size);
+                        p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y
+                    })
+            })
+            .collect();
+        if !additive {
+            self.selection.clear();
+        }
+        for i in inside {
+            if let Err(at) = self.selection.binary_search(&i) {
+                self.selection.insert(at, i);
+            }
         }
-        self.curve
-            .build_biarc(self.arcs.clone(), self.num_split, self.use_bezier_aabb);
         self.request_redraw();
     }
 
-    pub fn set_aabb_depth(&mut self, aabb_depth: usize) {
-        self.aabb_depth = aabb_depth;
+    /// Zoom and pan so every curve and its boxes are in view
+    pub fn fit_to_content(&mut self) {
+        self.fit_pending.set(true);
         self.request_redraw();
     }
 
-    fn draw_frame(&self, frame: &mut Frame) {
-        // draw control meshes
-        if self.is_meshed {
+    // bounds of the control points and root boxes, if there are any
+    fn content_bounds(&self) -> Option<(Vec2<Real>, Vec2<Real>)> {
+        let mut bounds: Option<(Vec2<Real>, Vec2<Real>)> = None;
+        let mut add = |p: Vec2<Real>| {
+            bounds = Some(match bounds {
+                Some((min, max)) => (
+                    Vec2::new(min.x.min(p.x), min.y.min(p.y)),
+                    Vec2::new(max.x.max(p.x), max.y.max(p.y)),
+                ),
+                None => (p, p),
+            });
+        };
+        for curve in self.curves.iter() {
+            let fitted = &curve.fitted;
+            for pt in fitted
+                .path
+                .segments
+                .iter()
+                .flat_map(|s| s.control_pts.iter())
+            {
+                add(*pt);
+            }
+            for arcs in fitted.arcs.iter() {
+                if let Some(root) = arcs.borrow().get(0) {
+                    let AABB { x, y, h, w } = root.aabb;
+                    let r = root.radius;
+                    add(Vec2::new(x - r, y - r));
+                    add(Vec2::new(x + w + r, y + h + r));
+                }
+            }
+        }
+        bounds
+    }
+
+    /// Zoom by `factor` about the middle of the canvas
+    pub fn zoom(&mut self, factor: f32) {
+        let size = self.size.get();
+        let mut viewport = self.viewport(size);
+        viewport.zoom(
+            Point::new(size.width / 2.0, size.height / 2.0),
+            factor,
+            size,
+        );
+        self.viewport.set(viewport);
+        self.request_redraw();
+    }
+
+    /// Use `theme`, keeping the arc colors of an opened project that has its own
+    pub fn set_theme(&mut self, theme: Theme, colors: Option<&Colors>) {
+        self.theme = match colors {
+            Some(colors) => with_colors(theme, colors),
+            None => theme,
+        };
+        self.request_redraw();
+    }
+
+    pub fn set_box_style(&mut self, box_style: BoxStyle) {
+        self.box_style = box_style;
+        self.request_redraw();
+    }
+
+    /// Count and area of the shown boxes of all curves, by tree depth
+    pub fn box_levels(&self) -> Vec<BoxLevel<Real>> {
+        self.curves
+            .iter()
+            .filter(|curve| curve.display.is_meshed)
+            .fold(Vec::new(), |levels, curve| {
+                merge_box_levels(&levels, &curve.fitted.box_levels(curve.display.aabb_depth))
+            })
+    }
+
+    /// Flip the overlay element of a keymap action
+    pub fn toggle_overlay(&mut self, action: Action) {
+        if self.overlay.toggle(action) {
+            self.request_redraw();
+        }
+    }
+
+    // the viewport for a canvas of `size`, applying a requested fit
+    fn viewport(&self, size: Size) -> Viewport {
+        self.size.set(size);
+        let mut viewport = self.viewport.get();
+        if self.fit_pending.replace(false) {
+            viewport = match self.content_bounds() {
+                Some((min, max)) => {
+                    viewport.fit(min, max, size, 20.0);
+                    viewport
+                }
+                None => Viewport::default(),
+            };
+            self.viewport.set(viewport);
+        }
+        viewport
+    }
+
+    /// Settings shown by the controls: the first selected curve's, else those of new curves
+    pub fn display(&self) -> Display {
+        match self.selection.first() {
+            Some(&i) => self.curves[i].display.clone(),
+            None => self.display.clone(),
+        }
+    }
+
+    // change the settings of the selected curves, or of all curves and new ones
+    fn change_display(&mut self, change: impl Fn(&mut Display)) {
+        let mut edits = Vec::new();
+        if self.selection.is_empty() {
+            let mut to = self.display.clone();
+            change(&mut to);
+            if to != self.display {
+                edits.push(Edit::SetDisplay {
+                    from: self.display.clone(),
+                    to,
+                });
+            }
+        }
+        for curve in self.targets() {
+            let from = self.curves[curve].display.clone();
+            let mut to = from.clone();
+            change(&mut to);
+            if to != from {
+                edits.push(Edit::SetCurveDisplay { curve, from, to });
+            }
+        }
+        match edits.len() {
+            0 => {}
+            1 => self.commit(edits.pop().unwrap()),
+            _ => self.commit(Edit::Batch(edits)),
+        }
+    }
+
+    pub fn toggle_dotted(&mut self) {
+        let is_dotted = !self.display().is_dotted;
+        self.change_display(|display| display.is_dotted = is_dotted);
+    }
+
+    pub fn toggle_meshed(&mut self) {
+        let is_meshed = !self.display().is_meshed;
+        self.change_display(|display| display.is_meshed = is_meshed);
+    }
+
+    pub fn set_bezier_aabb(&mut self, checked: bool) {
+        self.change_display(|display| display.use_bezier_aabb = checked);
+    }
+
+    pub fn set_num_biarc(&mut self, num_biarc: usize) {
+        self.change_display(|display| {
+            display.num_split = num_biarc;
+            if display.aabb_depth > num_biarc + 1 {
+                display.aabb_depth = num_biarc + 1;
+            }
+        });
+    }
+
+    pub fn set_aabb_depth(&mut self, aabb_depth: usize) {
+        self.change_display(|display| display.aabb_depth = aabb_depth);
+    }
+
+    /// Hide the boxes if any are shown, else show those of every depth
+    pub fn toggle_boxes(&mut self) {
+        let show = self.display().aabb_depth == 0;
+        self.change_display(|display| {
+            display.aabb_depth = if show { display.num_split + 2 } else { 0 };
+        });
+    }
+
+    fn draw_frame(&self, frame: &mut Frame, view: &Viewport) {
+        let size = frame.size();
+        let theme = &self.theme;
+        frame.fill(
+            &Path::rectangle(Point::ORIGIN, size),
+            Color::from(theme.background),
+        );
+        if self.show_grid && self.grid_spacing > 0.0 {
+            draw_grid(frame, view, self.grid_spacing, theme);
+        }
+
+        // bottom to top: each curve with its mesh, biarcs and boxes
+        for (i, curve) in self.curves.iter().enumerate() {
+            self.draw_curve(frame, view, curve, self.is_selected(i));
+        }
+        frame.stroke(
+            &Path::rectangle(Point::ORIGIN, frame.size()),
+            Stroke::default().with_color(Color::from(theme.text)),
+        );
+
+        self.draw_node_selection(frame, view);
+
+        if self.show_construction {
+            if let Some((curve, location)) = self.construction_location() {
+                let fitted = &self.curves[curve].fitted;
+                draw_construction(
+                    frame,
+                    view,
+                    &fitted.path,
+                    &location,
+                    fitted.joint,
+                    &self.tolerance,
+                    theme,
+                );
+            }
+        }
+
+        // draw control points
+        for curve in self.curves.iter() {
+            for segment in curve.fitted.path.segments.iter() {
+                for ctr_point in segment.control_pts.iter() {
+                    let point_circ =
+                        Path::circle(view.to_screen(ctr_point, size), PTS_RADIUS * 2.0);
+                    frame.fill(&point_circ, Color::from(theme.control_point));
+                }
+            }
+        }
+
+        if let Some(ref help) = self.help {
+            draw_help(frame, help, theme);
+        }
+
+        if let Control::Selecting { start, end, .. } = self.control {
+            let rubber_band = Path::rectangle(
+                Point::new(start.x.min(end.x), start.y.min(end.y)),
+                Size::new((end.x - start.x).abs(), (end.y - start.y).abs()),
+            );
+            let color = Color::from(self.theme.selection);
+            frame.fill(
+                &rubber_band,
+                Color {
+                    a: 0.1 * color.a,
+                    ..color
+                },
+            );
+            frame.stroke(&rubber_band, Stroke::default().with_color(color));
+        }
+    }
+
+    fn draw_curve(&self, frame: &mut Frame, view: &Viewport, curve: &Curve, is_selected: bool) {
+        let size = frame.size();
+        let display = &curve.display;
+        let segments = &curve.fitted.path.segments;
+
+        // draw control mesh
+        if display.is_meshed {
             let mesh = Path::new(|p| {
-                let pts = self.curve.control_pts;
-                p.move_to(pts[0]);
-                for i in 1..4 {
-                    p.line_to(pts[i]);
+                for segment in segments.iter() {
+                    let pts = segment.control_pts;
+                    p.move_to(view.to_screen(&pts[0], size));
+                    for pt in &pts[1..] {
+                        p.line_to(view.to_screen(pt, size));
+                    }
                 }
             });
             frame.stroke(
                 &mesh,
                 Stroke::default()
                     .with_width(2.0)
-                    .with_color(Color::from_rgba8(20, 210, 0, 1.0)),
+                    .with_color(Color::from(self.theme.mesh)),
             );
         }
 
-        // draw bezier curve
-        self.curve.draw(frame, self.is_dotted);
-        frame.stroke(
-            &Path::rectangle(Point::ORIGIN, frame.size()),
-            Stroke::default(),
-        );
+        // draw bezier curve, wider and highlighted when selected
+        let stroke = if is_selected {
+            Stroke::default()
+                .with_width(2.5)
+                .with_color(Color::from(self.theme.selected))
+        } else {
+            Stroke::default()
+                .with_width(1.2)
+                .with_color(Color::from(self.theme.curve))
+        };
+        for segment in segments.iter() {
+            draw_curve(segment, frame, view, display.is_dotted, stroke);
+        }
 
-        // draw biarcs
-        if self.is_meshed {
+        // draw biarcs, alternating colors along the curve
+        if display.is_meshed {
             let mut color_idx: i64 = 0;
-            self.draw_node(frame, self.arcs.borrow().get(0).unwrap(), &mut color_idx, 0);
+            for arcs in curve.fitted.arcs.iter() {
+                let tree = arcs.borrow();
+                let root = tree.get(0).unwrap();
+                self.draw_node(frame, view, display, &tree, root, &mut color_idx, 0);
+            }
         }
 
-        // draw control points
-        for ctr_point in self.curve.control_pts {
-            let point_circ = Path::circle(ctr_point, PTS_RADIUS * 2.0);
-            frame.fill(&point_circ, Color::from_rgba8(255, 0, 0, 1.0));
-        }
+        draw_overlay(
+            frame,
+            view,
+            &curve.fitted,
+            &self.overlay,
+            &self.tolerance,
+            &self.theme,
+        );
     }
 
-    fn draw_node(&self, frame: &mut Frame, node: &Node<ArcBox>, color_idx: &mut i64, depth: usize) {
-        let tree = self.arcs.borrow();
+    // the selected node's subtree and the box of the node under the cursor
+    fn draw_node_selection(&self, frame: &mut Frame, view: &Viewport) {
+        let color = Color::from(self.theme.highlight);
+        if let Some(id) = self.selected_node {
+            if let Some(arcs) = self
+                .curves
+                .get(id.curve)
+                .and_then(|curve| curve.fitted.arcs.get(id.segment))
+            {
+                let tree = arcs.borrow();
+                for index in (id.index..tree.len()).filter(|&i| in_complete_subtree(i, id.index)) {
+                    let node = tree.get(index).unwrap();
+                    node.draw_aabb(frame, view, &color);
+                    node.draw_arc(frame, view, &color);
+                }
+            }
+        }
+        if let Some(id) = self.hovered_node {
+            if let Some(arcs) = self
+                .curves
+                .get(id.curve)
+                .and_then(|curve| curve.fitted.arcs.get(id.segment))
+            {
+                if let Some(node) = arcs.borrow().get(id.index) {
+                    node.draw_aabb(frame, view, &Color { a: 0.4, ..color });
+                }
+            }
+        }
+    }
 
+    #[allow(clippy::too_many_arguments)]
+    fn draw_node(
+        &self,
+        frame: &mut Frame,
+        view: &Viewport,
+        display: &Display,
+        tree: &Tree<ArcBox<Real>>,
+        node: &Node<ArcBox<Real>>,
+        color_idx: &mut i64,
+        depth: usize,
+    ) {
         if let Some(left_node) = tree.left(node) {
-            self.draw_node(frame, left_node, color_idx, depth + 1);
+            self.draw_node(frame, view, display, tree, left_node, color_idx, depth + 1);
         }
 
         if let Some(right_node) = tree.right(node) {
-            self.draw_node(frame, right_node, color_idx, depth + 1);
+            self.draw_node(frame, view, display, tree, right_node, color_idx, depth + 1);
         }
 
         if node.arc.is_some() {
-            let color = if *color_idx % 2 == 0 {
-                self.left_color
-            } else {
-                self.right_color
-            };
-            node.draw_arc(frame, &color);
+            let color = Color::from(self.theme.arc_color(*color_idx as usize));
+            node.draw_arc(frame, view, &color);
             *color_idx += 1;
         }
 
-        if depth + self.aabb_depth >= self.num_split + 2 {
-            node.draw_aabb(frame, &Color::from_rgba8(0, 30, 220, 1.0));
+        if depth + display.aabb_depth >= display.num_split + 2 {
+            let color = Color::from(self.theme.box_color(depth));
+            let style = &self.box_style;
+            if !style.outline_only && style.fill_opacity > 0.0 {
+                let fill = Color {
+                    a: color.a * style.fill_opacity,
+                    ..color
+                };
+                node.fill_aabb(frame, view, &fill);
+            }
+            node.draw_aabb(frame, view, &color);
         }
     }
 }
 
-impl<Message> canvas::Program<Message> for State {
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl canvas::Program<Message> for State {
     fn update(
         &mut self,
         event: Event,
         bounds: Rectangle,
         cursor: Cursor,
     ) -> (event::Status, Option<Message>) {
-        if cursor.position_in(&bounds).is_none() {
+        if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+            self.modifiers = modifiers;
             return (event::Status::Ignored, None);
         }
+        let position = match cursor.position_in(&bounds) {
+            Some(position) => position,
+            None if self.hovered_node.is_some() => {
+                self.hovered_node = None;
+                self.request_redraw();
+                return (event::Status::Ignored, Some(Message::NodeHovered));
+            }
+            None => return (event::Status::Ignored, None),
+        };
+        let size = bounds.size();
+        let mut viewport = self.viewport(size);
+        let additive = self.modifiers.is_command_pressed();
 
         match event {
             Event::Mouse(mouse_event) => match mouse_event {
                 mouse::Event::ButtonPressed(mouse::Button::Left) => {
-                    for i in 0..4 {
-                        let ctr_pts = self.curve.control_pts[i];
-
-                        // make clickable range * 1.5
-                        let local_rad = PTS_RADIUS * 1.5;
-                        let pts_bound = Rectangle {
-                            x: bounds.x + ctr_pts.x - local_rad,
-                            y: bounds.y + ctr_pts.y - local_rad,
-                            height: 2.0 * local_rad,
-                            width: 2.0 * local_rad,
-                        };
-                        if let Some(in_pos) = cursor.position_in(&pts_bound) {
-                            self.control = Control::Moving(
-                                i,
-                                Point {
-                                    x: pts_bound.x + in_pos.x,
-                                    y: pts_bound.y + in_pos.y,
-                                },
-                            );
-                            break;
+                    if let Some(id) = self.point_at(position, &viewport, size) {
+                        self.start_drag(id);
+                        self.control = Control::Moving(id, position);
+                        self.selected = Some(id);
+                        if !self.is_selected(id.curve) {
+                            self.select(id.curve, additive);
+                        }
+                    } else if let Some(id) = self.node_at(position, &viewport, size) {
+                        self.selected_node = Some(id);
+                        if !self.is_selected(id.curve) {
+                            self.select(id.curve, additive);
                         }
+                        self.request_redraw();
+                    } else if let Some(curve) = self.curve_at(position, &viewport, size) {
+                        self.select(curve, additive);
+                    } else {
+                        self.control = Control::Selecting {
+                            start: position,
+                            end: position,
+                            additive,
+                        };
                     }
-                    (event::Status::Captured, None)
+                    (event::Status::Captured, Some(Message::SelectionChanged))
                 }
-                mouse::Event::CursorMoved { position } => {
-                    if let Control::Moving(idx, _) = self.control {
-                        let pts = Point {
-                            x: position.x - bounds.x,
-                            y: position.y - bounds.y,
-                        };
-                        self.control = Control::Moving(idx, pts);
-                        self.curve.control_pts[idx] = pts;
-                        self.curve.build_biarc(
-                            self.arcs.clone(),
-                            self.num_split,
-                            self.use_bezier_aabb,
-                        );
+                mouse::Event::ButtonPressed(mouse::Button::Middle) => {
+                    if self.control == Control::Static {
+                        self.control = Control::Panning(position);
                     }
                     (event::Status::Captured, None)
                 }
-                mouse::Event::ButtonReleased(mouse::Button::Left) => {
-                    if let Control::Moving(_, _) = self.control {
+                mouse::Event::CursorMoved { .. } => match self.control {
+                    Control::Moving(idx, _) => {
+                        self.control = Control::Moving(idx, position);
+                        let target =
+                            self.drag_target(idx, viewport.to_world(position, size), &viewport);
+                        self.drag_point(idx, target);
+                        (event::Status::Captured, Some(Message::SelectionChanged))
+                    }
+                    Control::Panning(last) => {
+                        viewport.pan(position - last);
+                        self.viewport.set(viewport);
+                        self.control = Control::Panning(position);
+                        (event::Status::Captured, None)
+                    }
+                    Control::Selecting {
+                        start, additive, ..
+                    } => {
+                        self.control = Control::Selecting {
+                            start,
+                            end: position,
+                            additive,
+                        };
+                        (event::Status::Captured, None)
+                    }
+                    Control::Static => {
+                        let hovered = self.node_at(position, &viewport, size);
+                        if hovered == self.hovered_node {
+                            return (event::Status::Captured, None);
+                        }
+                        self.hovered_node = hovered;
+                        self.request_redraw();
+                        (event::Status::Captured, Some(Message::NodeHovered))
+                    }
+                },
+                mouse::Event::ButtonReleased(mouse::Button::Left) => match self.control {
+                    Control::Moving(id, _) => {
+                        self.control = Control::Static;
+                        self.end_drag(id);
+                        self.cache.clear();
+                        (event::Status::Captured, None)
+                    }
+                    Control::Selecting {
+                        start,
+                        end,
+                        additive,
+                    } => {
+                        self.control = Control::Static;
+                        let drag: Vector = end - start;
+                        if drag.x.abs() < 3.0 && drag.y.abs() < 3.0 {
+                            // a click on empty canvas
+                            if !additive {
+                                self.selection.clear();
+                                self.selected_node = None;
+                            }
+                        } else {
+                            self.select_box(start, end, additive, &viewport, size);
+                        }
+                        self.cache.clear();
+                        (event::Status::Captured, Some(Message::SelectionChanged))
+                    }
+                    _ => (event::Status::Captured, None),
+                },
+                mouse::Event::ButtonReleased(mouse::Button::Middle) => {
+                    if let Control::Panning(_) = self.control {
                         self.control = Control::Static;
                         self.cache.clear();
                     }
                     (event::Status::Captured, None)
                 }
+                mouse::Event::WheelScrolled { delta } => {
+                    // one notch zooms by 20%
+                    let notches = match delta {
+                        mouse::ScrollDelta::Lines { y, .. } => y,
+                        mouse::ScrollDelta::Pixels { y, .. } => y / 50.0,
+                    };
+                    viewport.zoom(position, 1.2f32.powf(notches), size);
+                    self.viewport.set(viewport);
+                    self.cache.clear();
+                    (event::Status::Captured, None)
+                }
                 _ => (event::Status::Ignored, None),
             },
             _ => (event::Status::Ignored, None),
@@ -228,14 +1498,15 @@ impl<Message> canvas::Program<Message> for State {
     }
 
     fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let viewport = self.viewport(bounds.size());
         if Control::Static == self.control {
             let content = self.cache.draw(bounds.size(), |frame: &mut Frame| {
-                self.draw_frame(frame);
+                self.draw_frame(frame, &viewport);
             });
             vec![content]
         } else {
             let mut frame = Frame::new(bounds.size());
-            self.draw_frame(&mut frame);
+            self.draw_frame(&mut frame, &viewport);
             let content = frame.into_geometry();
             vec![content]
         }
@@ -250,458 +1521,222 @@ impl<Message> canvas::Program<Message> for State {
     }
 }
 
-#[derive(Debug)]
-pub struct BezierCurve {
-    control_pts: [Point; 4],
+// the curves of a project, fitted with their display settings
+fn project_curves(project: &Project, tolerance: &Tolerance<Real>) -> Vec<Curve> {
+    project
+        .curves
+        .iter()
+        .enumerate()
+        .map(|(i, path)| Curve::new(path.clone(), project.curve_display(i).clone(), tolerance))
+        .collect()
 }
 
-impl BezierCurve {
-    fn draw(&self, frame: &mut Frame, is_dotted: bool) {
-        let curve = Path::new(|p| {
-            let mut point = Point::default();
-            let mut dot_start = true;
-            p.move_to(self.control_pts[0]);
-            for i in 1..=RESOLUTION {
-                let t = (i as f32) / (RESOLUTION as f32);
-                self.cubic_curve_to(&mut point, t);
-
-                if is_dotted {
-                    if dot_start {
-                        p.line_to(point);
-                    } else {
-                        p.move_to(point);
-                    }
-                    dot_start = !dot_start;
-                } else {
-                    p.line_to(point);
-                }
-            }
-        });
-
-        frame.stroke(&curve, Stroke::default().with_width(1.2));
+// `theme` with the arc colors of a project, unless it keeps the default ones
+fn with_colors(mut theme: Theme, colors: &Colors) -> Theme {
+    if *colors != Colors::default() {
+        theme.left_arc = colors.left_arc;
+        theme.right_arc = colors.right_arc;
     }
+    theme
+}
 
-    fn cubic_curve_to(&self, point: &mut Point, t: f32) -> () {
-        let t_inv = 1.0 - t;
-        let t_inv_sq = t_inv * t_inv;
-        let t_sq = t * t;
-        let b0 = t_inv_sq * t_inv;
-        let b1 = 3.0 * t_inv_sq * t;
-        let b2 = 3.0 * t_inv * t_sq;
-        let b3 = t_sq * t;
-        point_clear(point);
-        point_add_weight_vec(point, b0, &self.control_pts[0]);
-        point_add_weight_vec(point, b1, &self.control_pts[1]);
-        point_add_weight_vec(point, b2, &self.control_pts[2]);
-        point_add_weight_vec(point, b3, &self.control_pts[3]);
+// shortcut list in the top-left corner
+fn draw_help(frame: &mut Frame, help: &[(String, &str)], theme: &Theme) {
+    let line_height = 18.0;
+    let keys_width = 150.0;
+    let size = Size::new(keys_width + 260.0, line_height * help.len() as f32 + 20.0);
+    frame.fill(
+        &Path::rectangle(Point::new(10.0, 10.0), size),
+        Color::from(theme.panel),
+    );
+    for (i, (keys, description)) in help.iter().enumerate() {
+        let y = 20.0 + line_height * i as f32;
+        for &(x, content) in [(20.0, keys.as_str()), (20.0 + keys_width, *description)].iter() {
+            frame.fill_text(canvas::Text {
+                content: content.to_string(),
+                position: Point::new(x, y),
+                size: 16.0,
+                color: Color::from(theme.text),
+                ..canvas::Text::default()
+            });
+        }
     }
+}
 
-    fn cubic_deriv_to(&self, point: &mut Point, t: f32) -> () {
-        let t_inv = 1.0 - t;
-
-        let b0 = 3.0 * t_inv * t_inv;
-        let b1 = 6.0 * t * t_inv;
-        let b2 = 3.0 * t * t;
-        let p0 = &self.control_pts[0];
-        let p1 = &self.control_pts[1];
-        let p2 = &self.control_pts[2];
-        let p3 = &self.control_pts[3];
-        point_clear(point);
-        point.x = b0 * (p1.x - p0.x) + b1 * (p2.x - p1.x) + b2 * (p3.x - p2.x);
-        point.y = b0 * (p1.y - p0.y) + b1 * (p2.y - p1.y) + b2 * (p3.y - p2.y);
+// lines every `spacing` world units, thinned out when zoomed out; axes darker
+fn draw_grid(frame: &mut Frame, view: &Viewport, spacing: Real, theme: &Theme) {
+    let size = frame.size();
+    let mut step = spacing;
+    while (step as f32) * view.scale < MIN_GRID_PIXELS {
+        step *= 5.0;
     }
-
-    fn build_biarc(
-        &self,
-        arc_cell: Rc<RefCell<Tree<ArcBox>>>,
-        split_num: usize,
-        use_bezier_aabb: bool,
-    ) -> () {
-        let depth = split_num + 1;
-        let node_n = 2usize.pow((depth + 1) as u32) - 1;
-        let biarc_n = 2usize.pow(split_num as u32);
-
-        if node_n != arc_cell.borrow().len() {
-            let mut arc_mut = arc_cell.borrow_mut();
-            arc_mut.set_new_complete(depth, ArcBox::arc_builder(depth));
-        }
-
-        let delta = 1.0 / (biarc_n as f32);
-        let mut start = Point::default();
-        let mut mid = Point::default();
-        let mut end = Point::default();
-        let mut control = Point::default();
-        let mut u0 = Point::default();
-        let mut u1 = Point::default();
-        let mut mid0 = Point::default();
-        let mut mid1 = Point::default();
-        let mut v0 = Point::default();
-        let mut v1 = Point::default();
-        let mut center = Point::default();
-
-        let mut arc_mid = Point::default();
-        let mut tangent_left = Point::default();
-        let mut tangent_right = Point::default();
-        let mut tangent_mid = Point::default();
-
-        let mut i: i32 = 0;
-        let mut is_left: bool = true;
-
-        Tree::post_trav(arc_cell.clone(), |node_id| {
-            // TODO: merge radius
-            let mut left_aabb: Option<AABB> = None;
-            let mut right_aabb: Option<AABB> = None;
-            let mut aabb_radius: f32 = 0.0;
-
-            {
-                let tree = arc_cell.borrow();
-                let node = tree.get(node_id).unwrap();
-                if let Some(left_node) = tree.left(node) {
-                    left_aabb = Some(left_node.aabb.clone());
-                    aabb_radius = left_node.radius;
+    let min = view.to_world(Point::new(0.0, size.height), size);
+    let max = view.to_world(Point::new(size.width, 0.0), size);
+    let lines = |axes: bool| {
+        Path::new(|p| {
+            for i in (min.x / step).ceil() as i64..=(max.x / step).floor() as i64 {
+                if (i == 0) == axes {
+                    let x = i as Real * step;
+                    p.move_to(view.to_screen(&Vec2::new(x, min.y), size));
+                    p.line_to(view.to_screen(&Vec2::new(x, max.y), size));
                 }
-                if let Some(right_node) = tree.right(node) {
-                    right_aabb = Some(right_node.aabb.clone());
-                    if aabb_radius < right_node.radius {
-                        aabb_radius = right_node.radius;
-                    }
-                }
-
-                if let Some(ref left_value) = left_aabb {
-                    if let Some(ref right_value) = right_aabb {
-                        left_aabb = Some(AABB::merge_two(left_value, right_value));
-                    }
-                } else {
-                    if right_aabb.is_some() {
-                        left_aabb = right_aabb
-                    }
+            }
+            for i in (min.y / step).ceil() as i64..=(max.y / step).floor() as i64 {
+                if (i == 0) == axes {
+                    let y = i as Real * step;
+                    p.move_to(view.to_screen(&Vec2::new(min.x, y), size));
+                    p.line_to(view.to_screen(&Vec2::new(max.x, y), size));
                 }
             }
+        })
+    };
+    frame.stroke(
+        &lines(false),
+        Stroke::default()
+            .with_width(1.0)
+            .with_color(Color::from(theme.grid)),
+    );
+    frame.stroke(
+        &lines(true),
+        Stroke::default()
+            .with_width(1.0)
+            .with_color(Color::from(theme.grid_axis)),
+    );
+}
 
-            let mut tree = arc_cell.borrow_mut();
-            let arc_node = &mut tree.get_mut(node_id).unwrap().value;
-
-            // leaf node
-            if let Some(ref mut arc) = arc_node.arc {
-                // cache joint circle
-                if is_left {
-                    let t = delta * (i as f32);
-                    i += 1;
-                    self.cubic_curve_to(&mut start, t);
-                    self.cubic_deriv_to(&mut u0, t);
-                    normalize(&mut u0);
-
-                    self.cubic_curve_to(&mut mid, t + delta * 0.5);
-
-                    self.cubic_curve_to(&mut end, t + delta);
-                    self.cubic_deriv_to(&mut u1, t + delta);
-                    normalize(&mut u1);
-
-                    // calculate the center of joint circle
-                    mid0.x = (start.x + end.x) / 2.0;
-                    mid0.y = (start.y + end.y) / 2.0;
-                    mid1.x = (start.x + u0.x + end.x + u1.x) / 2.0;
-                    mid1.y = (start.y + u0.y + end.y + u1.y) / 2.0;
-                    v0.x = end.y - start.y;
-                    v0.y = -(end.x - start.x);
-                    v1.x = (end.y + u1.y) - (start.y + u0.y);
-                    v1.y = (start.x + u0.x) - (end.x + u1.x);
-                    ray_intersection(&mid0, &v0, &mid1, &v1, &mut center);
-
-                    // calculate radius and control point
-                    let radius = distance(&center, &start);
-                    let theta = point_angle(&center, &mid0);
-                    control.x = center.x + (radius * theta.cos()) as f32;
-                    control.y = center.y + (radius * theta.sin()) as f32;
-
-                    // calculate the center and angles of left arc
-                    arc_mid.x = (start.x + control.x) / 2.0;
-                    arc_mid.y = (start.y + control.y) / 2.0;
-                    tangent_left.x = u0.y;
-                    tangent_left.y = -u0.x;
-                    tangent_right.x = control.y - start.y;
-                    tangent_right.y = -(control.x - start.x);
-                    ray_intersection(
-                        &start,
-                        &tangent_left,
-                        &arc_mid,
-                        &tangent_right,
-                        &mut arc.center,
-                    );
-
-                    arc.radius = distance(&arc.center, &start) as f32;
-                    arc.angle0 = point_angle(&arc.center, &start);
-                    arc.angle1 = point_angle(&arc.center, &arc_mid);
-                    arc.angle2 = point_angle(&arc.center, &control);
-
-                    // is left arc is larger than half-circle?
-                    let chord_vec = Point {
-                        x: control.x - start.x,
-                        y: control.y - start.y,
-                    };
-                    let l1_angle = vec_angle(&chord_vec, &u0);
-
-                    // then invert mid-angle
-                    if l1_angle > std::f64::consts::FRAC_PI_2 {
-                        arc.angle1 = invert_angle(arc.angle1);
-                    }
-
-                    arc_mid.x = arc.center.x + (arc.radius * arc.angle1.cos() as f32);
-                    arc_mid.y = arc.center.y + (arc.radius * arc.angle1.sin() as f32);
-
-                    // calculate aabb radius
-                    tangent_right.x = control.y - arc.center.y;
-                    tangent_right.y = arc.center.x - control.x;
-                    ray_intersection(&start, &u0, &control, &tangent_right, &mut tangent_mid);
-
-                    tangent_left.x = (start.x + tangent_mid.x * 2.0) / 3.0;
-                    tangent_left.y = (start.y + tangent_mid.y * 2.0) / 3.0;
-                    tangent_right.x = (control.x + tangent_mid.x * 2.0) / 3.0;
-                    tangent_right.y = (control.y + tangent_mid.y * 2.0) / 3.0;
-
-                    // inlined cubic curve calculation
-                    point_clear(&mut tangent_mid);
-                    point_add_weight_vec(&mut tangent_mid, 0.125, &start);
-                    point_add_weight_vec(&mut tangent_mid, 0.375, &tangent_left);
-                    point_add_weight_vec(&mut tangent_mid, 0.375, &tangent_right);
-                    point_add_weight_vec(&mut tangent_mid, 0.125, &control);
-
-                    arc_node.radius = distance(&arc_mid, &tangent_mid) as f32;
-
-                    let mut dist_max = 0.0;
-                    let bezier_aabb = {
-                        let tn = t + delta / 2.0;
-                        let tn_inv = 1.0 - tn;
-                        let t_sq = t * t;
-                        let tn_sq = tn * tn;
-                        let t_inv = 1.0 - t;
-                        let t_inv_sq = t_inv * t_inv;
-                        let tn_inv_sq = tn_inv * tn_inv;
-                        let t_t_inv = t * t_inv;
-                        let tn_tn_inv = tn * tn_inv;
-
-                        let mid_control_left = Point {
-                            x: tn_inv
-                                * (t_inv_sq * self.control_pts[0].x
-                                    + 2. * t_t_inv * self.control_pts[1].x
-                                    + t_sq * self.control_pts[2].x)
-                                + tn * (t_inv_sq * self.control_pts[1].x
-                                    + 2. * t_t_inv * self.control_pts[2].x
-                                    + t_sq * self.control_pts[3].x),
-                            y: tn_inv
-                                * (t_inv_sq * self.control_pts[0].y
-                                    + 2. * t_t_inv * self.control_pts[1].y
-                                    + t_sq * self.control_pts[2].y)
-                                + tn * (t_inv_sq * self.control_pts[1].y
-                                    + 2. * t_t_inv * self.control_pts[2].y
-                                    + t_sq * self.control_pts[3].y),
-                        };
-                        let mid_control_right = Point {
-                            x: t_inv
-                                * (tn_inv_sq * self.control_pts[0].x
-                                    + 2. * tn_tn_inv * self.control_pts[1].x
-                                    + tn_sq * self.control_pts[2].x)
-                                + t * (tn_inv_sq * self.control_pts[1].x
-                                    + 2. * tn_tn_inv * self.control_pts[2].x
-                                    + tn_sq * self.control_pts[3].x),
-                            y: t_inv
-                                * (tn_inv_sq * self.control_pts[0].y
-                                    + 2. * tn_tn_inv * self.control_pts[1].y
-                                    + tn_sq * self.control_pts[2].y)
-                                + t * (tn_inv_sq * self.control_pts[1].y
-                                    + 2. * tn_tn_inv * self.control_pts[2].y
-                                    + tn_sq * self.control_pts[3].y),
-                        };
-                        let mut dist_left = distance(&mid_control_left, &tangent_left);
-                        if dist_max < dist_left {
-                            dist_max = dist_left;
-                        }
-
-                        dist_left = distance(&mid_control_right, &tangent_right);
-                        if dist_max < dist_left {
-                            dist_max = dist_left;
-                        }
-
-                        if use_bezier_aabb {
-                            let mut aabb = AABB::new_point(start, mid_control_left);
-                            let aabb2 = AABB::new_point(mid_control_right, mid);
-                            aabb.merge(&aabb2);
-                            Some(aabb)
-                        } else {
-                            None
-                        }
-                    };
+// signed distance from `p` to the outline of the box `aabb` rounded by `radius`
+fn box_distance(aabb: &AABB<Real>, radius: Real, p: &Vec2<Real>) -> Real {
+    let half_w = aabb.w / 2.0;
+    let half_h = aabb.h / 2.0;
+    let qx = (p.x - (aabb.x + half_w)).abs() - half_w;
+    let qy = (p.y - (aabb.y + half_h)).abs() - half_h;
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+    let inside = qx.max(qy).min(0.0);
+    outside + inside - radius
+}
 
-                    arc_node.radius += dist_max as f32;
-                    arc_node.aabb = arc.aabb();
-
-                    if use_bezier_aabb {
-                        let mut aabb2 = bezier_aabb.unwrap();
-                        aabb2.merge(&arc_node.aabb);
-                        if aabb2.h < arc_node.aabb.h + 2.0 * arc_node.radius
-                            && aabb2.w < arc_node.aabb.w + 2.0 * arc_node.radius
-                        {
-                            arc_node.radius = 0.0;
-                            arc_node.aabb = aabb2.clone();
-                        }
-                    }
+fn draw_curve(
+    curve: &BezierCurve<Real>,
+    frame: &mut Frame,
+    view: &Viewport,
+    is_dotted: bool,
+    stroke: Stroke,
+) {
+    let size = frame.size();
+    let path = Path::new(|p| {
+        let mut point = Vec2::zero();
+        let mut dot_start = true;
+        p.move_to(view.to_screen(&curve.control_pts[0], size));
+        for i in 1..=RESOLUTION {
+            let t = (i as Real) / (RESOLUTION as Real);
+            curve.cubic_curve_to(&mut point, t);
+
+            if is_dotted {
+                if dot_start {
+                    p.line_to(view.to_screen(&point, size));
                 } else {
-                    // calculate the center and angles of right arc
-                    arc_mid.x = (end.x + control.x) / 2.0;
-                    arc_mid.y = (end.y + control.y) / 2.0;
-                    tangent_left.x = u1.y;
-                    tangent_left.y = -u1.x;
-                    tangent_right.x = control.y - end.y;
-                    tangent_right.y = -(control.x - end.x);
-                    ray_intersection(
-                        &end,
-                        &tangent_left,
-                        &arc_mid,
-                        &tangent_right,
-                        &mut arc.center,
-                    );
-
-                    arc.radius = distance(&arc.center, &end) as f32;
-                    arc.angle0 = point_angle(&arc.center, &control);
-                    arc.angle1 = point_angle(&arc.center, &arc_mid);
-                    arc.angle2 = point_angle(&arc.center, &end);
-
-                    // is right arc is larger than half-circle?
-                    let chord_vec = Point {
-                        x: end.x - control.x,
-                        y: end.y - control.y,
-                    };
-                    let r1_angle = vec_angle(&chord_vec, &u1);
-
-                    // then invert mid-angle
-                    if r1_angle > std::f64::consts::FRAC_PI_2 {
-                        arc.angle1 = invert_angle(arc.angle1);
-                    }
-
-                    arc_mid.x = arc.center.x + (arc.radius * arc.angle1.cos() as f32);
-                    arc_mid.y = arc.center.y + (arc.radius * arc.angle1.sin() as f32);
-
-                    // calculate aabb radius
-                    tangent_right.x = control.y - arc.center.y;
-                    tangent_right.y = arc.center.x - control.x;
-                    ray_intersection(&end, &u1, &control, &tangent_right, &mut tangent_mid);
-
-                    tangent_left.x = (control.x + tangent_mid.x * 2.0) / 3.0;
-                    tangent_left.y = (control.y + tangent_mid.y * 2.0) / 3.0;
-                    tangent_right.x = (end.x + tangent_mid.x * 2.0) / 3.0;
-                    tangent_right.y = (end.y + tangent_mid.y * 2.0) / 3.0;
-
-                    // inlined cubic curve calculation
-                    point_clear(&mut tangent_mid);
-                    point_add_weight_vec(&mut tangent_mid, 0.125, &control);
-                    point_add_weight_vec(&mut tangent_mid, 0.375, &tangent_left);
-                    point_add_weight_vec(&mut tangent_mid, 0.375, &tangent_right);
-                    point_add_weight_vec(&mut tangent_mid, 0.125, &end);
-
-                    arc_node.radius = distance(&arc_mid, &tangent_mid) as f32;
-
-                    let mut dist_max = 0.0;
-                    let bezier_aabb = {
-                        let t = delta * ((i - 1) as f32 + 0.5);
-                        let tn = t + (delta / 2.0);
-                        let tn_inv = 1.0 - tn;
-                        let t_sq = t * t;
-                        let tn_sq = tn * tn;
-                        let t_inv = 1.0 - t;
-                        let t_inv_sq = t_inv * t_inv;
-                        let tn_inv_sq = tn_inv * tn_inv;
-                        let t_t_inv = t * t_inv;
-                        let tn_tn_inv = tn * tn_inv;
-
-                        let mid_control_left = Point {
-                            x: tn_inv
-                                * (t_inv_sq * self.control_pts[0].x
-                                    + 2. * t_t_inv * self.control_pts[1].x
-                                    + t_sq * self.control_pts[2].x)
-                                + tn * (t_inv_sq * self.control_pts[1].x
-                                    + 2. * t_t_inv * self.control_pts[2].x
-                                    + t_sq * self.control_pts[3].x),
-                            y: tn_inv
-                                * (t_inv_sq * self.control_pts[0].y
-                                    + 2. * t_t_inv * self.control_pts[1].y
-                                    + t_sq * self.control_pts[2].y)
-                                + tn * (t_inv_sq * self.control_pts[1].y
-                                    + 2. * t_t_inv * self.control_pts[2].y
-                                    + t_sq * self.control_pts[3].y),
-                        };
-                        let mid_control_right = Point {
-                            x: t_inv
-                                * (tn_inv_sq * self.control_pts[0].x
-                                    + 2. * tn_tn_inv * self.control_pts[1].x
-                                    + tn_sq * self.control_pts[2].x)
-                                + t * (tn_inv_sq * self.control_pts[1].x
-                                    + 2. * tn_tn_inv * self.control_pts[2].x
-                                    + tn_sq * self.control_pts[3].x),
-                            y: t_inv
-                                * (tn_inv_sq * self.control_pts[0].y
-                                    + 2. * tn_tn_inv * self.control_pts[1].y
-                                    + tn_sq * self.control_pts[2].y)
-                                + t * (tn_inv_sq * self.control_pts[1].y
-                                    + 2. * tn_tn_inv * self.control_pts[2].y
-                                    + tn_sq * self.control_pts[3].y),
-                        };
-                        let mut dist_left = distance(&mid_control_left, &tangent_left);
-                        if dist_max < dist_left {
-                            dist_max = dist_left;
-                        }
+                    p.move_to(view.to_screen(&point, size));
+                }
+                dot_start = !dot_start;
+            } else {
+                p.line_to(view.to_screen(&point, size));
+            }
+        }
+    });
 
-                        dist_left = distance(&mid_control_right, &tangent_right);
-                        if dist_max < dist_left {
-                            dist_max = dist_left;
-                        }
+    frame.stroke(&path, stroke);
+}
 
-                        if use_bezier_aabb {
-                            let mut aabb = AABB::new_point(mid, mid_control_left);
-                            let aabb2 = AABB::new_point(mid_control_right, end);
-                            aabb.merge(&aabb2);
-                            Some(aabb)
-                        } else {
-                            None
-                        }
-                    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                    arc_node.radius += dist_max as f32;
-                    arc_node.aabb = arc.aabb();
-
-                    if use_bezier_aabb {
-                        let mut aabb2 = bezier_aabb.unwrap();
-                        aabb2.merge(&arc_node.aabb);
-                        if aabb2.h < arc_node.aabb.h + 2.0 * arc_node.radius
-                            && aabb2.w < arc_node.aabb.w + 2.0 * arc_node.radius
-                        {
-                            arc_node.radius = 0.0;
-                            arc_node.aabb = aabb2.clone();
-                        }
-                    }
-                }
-                is_left = !is_left;
+    // expected exports written by the bezier-fit test of the same project
+    fn golden(name: &str) -> String {
+        let path = format!(
+            "{}/../bezier-core/tests/golden/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        std::fs::read_to_string(&path).unwrap()
+    }
 
-                // calculate aabb
-                // arc_node.aabb = arc.aabb();
-            } else {
-                if let Some(left_value) = left_aabb {
-                    arc_node.aabb = left_value;
-                    arc_node.radius = aabb_radius;
-                }
-            }
+    #[test]
+    fn exports_match_the_command_line() {
+        let project = Project::from_json(&golden("export_project.json")).unwrap();
+        let state = State::from_project(&project);
+        let options = SampleOptions::default();
+        let (samples, leaves) = state.to_csv(&options);
+        assert_eq!(samples, golden("export_samples.csv"));
+        assert_eq!(leaves, golden("export_leaves.csv"));
+        assert_eq!(
+            state.to_samples_json(&options),
+            golden("export_samples.json")
+        );
+        let gcode = state.to_gcode(&GcodeOptions {
+            flip_y: false,
+            ..GcodeOptions::default()
         });
+        assert_eq!(gcode, golden("export.nc"));
     }
-}
 
-impl Default for BezierCurve {
-    fn default() -> Self {
-        Self {
-            control_pts: [
-                Point { x: 50.0, y: 100.0 },
-                Point { x: 200.0, y: 300.0 },
-                Point { x: 400.0, y: 300.0 },
-                Point { x: 550.0, y: 100.0 },
+    #[test]
+    fn svg_uses_the_display_of_each_curve() {
+        let project = Project {
+            curves: vec![BezierPath::default(), BezierPath::default()],
+            curve_display: vec![
+                Display {
+                    is_dotted: true,
+                    is_meshed: false,
+                    aabb_depth: 2,
+                    ..Display::default()
+                },
+                Display::default(),
             ],
+            ..Project::default()
+        };
+        let svg = State::from_project(&project).to_svg();
+        assert_eq!(svg.matches(r#"stroke-dasharray="4""#).count(), 1);
+        // only the second curve has its mesh; the first keeps its boxes
+        assert_eq!(svg.matches(" L ").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 4);
+        assert_eq!(svg.matches("<rect").count(), 6 + 4);
+    }
+
+    #[test]
+    fn svg_view_box_covers_the_curves() {
+        let mut state = State::new();
+        state.add_curves(vec![BezierPath::from_curve(BezierCurve::line(
+            Vec2::new(-200.0, 50.0),
+            Vec2::new(-100.0, -400.0),
+        ))]);
+        state.select_all();
+        let svg = state.to_svg();
+        let view_box: Vec<Real> = svg
+            .split("viewBox=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap()
+            .split(' ')
+            .map(|v| v.parse().unwrap())
+            .collect();
+        let (x, y, w, h) = (view_box[0], view_box[1], view_box[2], view_box[3]);
+        assert!(svg.contains(&format!(r#"width="{}" height="{}""#, w, h)));
+
+        // SVG is y-down
+        let mut paths = state.to_project().curves;
+        mirror_y(&mut paths);
+        for pt in paths
+            .iter()
+            .flat_map(|path| path.segments.iter())
+            .flat_map(|s| s.control_pts.iter())
+        {
+            assert!(
+                x < pt.x && pt.x < x + w && y < pt.y && pt.y < y + h,
+                "{:?}",
+                pt
+            );
         }
+        assert!(x < -200.0 && y < -50.0);
     }
 }
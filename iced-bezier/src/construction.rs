@@ -0,0 +1,279 @@
+// Step-by-step view of how a curve point and a biarc are computed
+//
+// For a path parameter `t` the segment's de Casteljau polygons are drawn down to the
+// curve point and its tangent, and for the biarc interval holding the point the joint
+// circle, the joint and the centers of both arcs, as `build_biarc` derives them.
+// The overlay draws parts of that construction for every biarc at once.
+
+use iced::{
+    canvas::{Frame, Path, Stroke},
+    Color, Vector,
+};
+
+use bezier_core::bezier::{BezierCurve, JointStrategy};
+use bezier_core::overlay::Overlay;
+use bezier_core::path::{BezierPath, FittedPath};
+use bezier_core::point::Vec2;
+use bezier_core::predicates::Tolerance;
+use bezier_core::theme::Theme;
+use bezier_core::util::*;
+
+use crate::bezier::Real;
+use crate::biarc::*;
+use crate::viewport::Viewport;
+
+/// Pixels the tangent is drawn to each side of the curve point
+const TANGENT_LENGTH: f32 = 80.0;
+
+/// Pixels the overlay tangents are drawn to each side of a biarc end
+const OVERLAY_TANGENT_LENGTH: f32 = 40.0;
+
+/// Where a path parameter falls: segment, parameter within it and biarc interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub segment: usize,
+    pub t: Real,
+    pub interval: usize,
+    /// Intervals per segment
+    pub intervals: usize,
+}
+
+impl Location {
+    /// Locate `t` in `0..=1` over a path of `segments` split into `2^num_split` intervals each
+    pub fn new(segments: usize, num_split: usize, t: Real) -> Location {
+        let intervals = 2usize.pow(num_split as u32);
+        let scaled = t.max(0.0).min(1.0) * segments as Real;
+        let segment = (scaled.floor() as usize).min(segments.saturating_sub(1));
+        let t = scaled - segment as Real;
+        let interval = ((t * intervals as Real).floor() as usize).min(intervals - 1);
+        Location {
+            segment,
+            t,
+            interval,
+            intervals,
+        }
+    }
+
+    /// Parameter range of the interval within the segment
+    pub fn range(&self) -> (Real, Real) {
+        let delta = 1.0 / self.intervals as Real;
+        (
+            delta * self.interval as Real,
+            delta * (self.interval + 1) as Real,
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn draw_construction(
+    frame: &mut Frame,
+    view: &Viewport,
+    path: &BezierPath<Real>,
+    location: &Location,
+    joint: JointStrategy,
+    tolerance: &Tolerance<Real>,
+    theme: &Theme,
+) {
+    let segment = match path.segments.get(location.segment) {
+        Some(segment) => segment,
+        None => return,
+    };
+    draw_biarc_construction(frame, view, segment, location, joint, tolerance, theme);
+    draw_de_casteljau(frame, view, segment, location.t, theme);
+}
+
+fn draw_de_casteljau(
+    frame: &mut Frame,
+    view: &Viewport,
+    segment: &BezierCurve<Real>,
+    t: Real,
+    theme: &Theme,
+) {
+    let size = frame.size();
+    let levels = segment.de_casteljau(t);
+    // the last level is the curve point, drawn below
+    for (level, points) in levels[..levels.len() - 1].iter().enumerate() {
+        let color = Color::from(theme.construction_color(level));
+        let polygon = Path::new(|p| {
+            p.move_to(view.to_screen(&points[0], size));
+            for point in &points[1..] {
+                p.line_to(view.to_screen(point, size));
+            }
+        });
+        frame.stroke(
+            &polygon,
+            Stroke::default().with_width(1.5).with_color(color),
+        );
+        for point in points.iter() {
+            frame.fill(
+                &Path::circle(view.to_screen(point, size), PTS_RADIUS),
+                color,
+            );
+        }
+    }
+
+    // the last polygon edge lies on the tangent
+    let point = view.to_screen(&levels[3][0], size);
+    let edge = view.to_screen(&levels[2][1], size) - view.to_screen(&levels[2][0], size);
+    let length = (edge.x * edge.x + edge.y * edge.y).sqrt();
+    if length > 0.0 {
+        let half = edge * (TANGENT_LENGTH / length);
+        frame.stroke(
+            &Path::line(point - half, point + half),
+            Stroke::default()
+                .with_width(2.0)
+                .with_color(Color::from(theme.selected)),
+        );
+    }
+    frame.fill(
+        &Path::circle(point, PTS_RADIUS * 2.5),
+        Color::from(theme.selected),
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_biarc_construction(
+    frame: &mut Frame,
+    view: &Viewport,
+    segment: &BezierCurve<Real>,
+    location: &Location,
+    joint: JointStrategy,
+    tolerance: &Tolerance<Real>,
+    theme: &Theme,
+) {
+    let size = frame.size();
+    let (t0, t1) = location.range();
+    let construction = segment.biarc_construction(t0, t1, joint, tolerance);
+    let thin = |color: Color| Stroke::default().with_width(1.0).with_color(color);
+    let screen = |p: &Vec2<Real>| view.to_screen(p, size);
+
+    // joint circle: every joint on it keeps the biarc G1
+    let circle_color = Color {
+        a: 1.0,
+        ..Color::from(theme.arc_circle)
+    };
+    if let Some((center, radius)) = construction.joint_circle {
+        frame.stroke(
+            &Path::circle(screen(&center), radius as f32 * view.scale),
+            thin(circle_color),
+        );
+        frame.fill(&Path::circle(screen(&center), PTS_RADIUS), circle_color);
+    }
+
+    // chord and end tangents
+    let start = screen(&construction.start);
+    let end = screen(&construction.end);
+    frame.stroke(&Path::line(start, end), thin(circle_color));
+    for (point, tangent) in [
+        (start, construction.start_tangent),
+        (end, construction.end_tangent),
+    ]
+    .iter()
+    {
+        let direction = Vector::new(tangent.x as f32, -tangent.y as f32) * TANGENT_LENGTH;
+        frame.stroke(
+            &Path::line(*point - direction, *point + direction),
+            thin(circle_color),
+        );
+    }
+
+    // each arc with the radii to its ends
+    let joint = screen(&construction.joint);
+    for (arc, color, from, to) in [
+        (
+            &construction.left,
+            Color::from(theme.left_arc),
+            start,
+            joint,
+        ),
+        (
+            &construction.right,
+            Color::from(theme.right_arc),
+            joint,
+            end,
+        ),
+    ]
+    .iter()
+    {
+        let center = screen(&arc.center);
+        let radii = Path::new(|p| {
+            p.move_to(*from);
+            p.line_to(center);
+            p.line_to(*to);
+        });
+        frame.stroke(&radii, thin(*color));
+        frame.fill(&Path::circle(center, PTS_RADIUS * 1.5), *color);
+        arc.draw(frame, view, color);
+    }
+
+    frame.fill(
+        &Path::circle(joint, PTS_RADIUS * 2.0),
+        Color::from(theme.curve),
+    );
+}
+
+/// Full arc circles, joint circles and end tangents of every biarc of `fitted`
+pub fn draw_overlay(
+    frame: &mut Frame,
+    view: &Viewport,
+    fitted: &FittedPath<Real>,
+    overlay: &Overlay,
+    tolerance: &Tolerance<Real>,
+    theme: &Theme,
+) {
+    let size = frame.size();
+    let thin = |color: Color| Stroke::default().with_width(1.0).with_color(color);
+
+    if overlay.arc_circles {
+        let circles = Path::new(|p| {
+            for arc in fitted.leaves() {
+                p.circle(
+                    view.to_screen(&arc.center, size),
+                    arc.radius as f32 * view.scale,
+                );
+            }
+        });
+        frame.stroke(&circles, thin(Color::from(theme.arc_circle)));
+    }
+
+    if !overlay.needs_constructions() {
+        return;
+    }
+    let constructions = fitted.constructions(tolerance);
+
+    if overlay.joint_circles {
+        let color = Color::from(theme.joint_circle);
+        let circles = Path::new(|p| {
+            for (center, radius) in constructions.iter().filter_map(|c| c.joint_circle) {
+                p.circle(view.to_screen(&center, size), radius as f32 * view.scale);
+            }
+        });
+        frame.stroke(&circles, thin(color));
+        for construction in constructions.iter() {
+            frame.fill(
+                &Path::circle(view.to_screen(&construction.joint, size), PTS_RADIUS),
+                color,
+            );
+        }
+    }
+
+    if overlay.tangents {
+        let tangents = Path::new(|p| {
+            for construction in constructions.iter() {
+                for (point, tangent) in [
+                    (construction.start, construction.start_tangent),
+                    (construction.end, construction.end_tangent),
+                ]
+                .iter()
+                {
+                    let point = view.to_screen(point, size);
+                    let direction =
+                        Vector::new(tangent.x as f32, -tangent.y as f32) * OVERLAY_TANGENT_LENGTH;
+                    p.move_to(point - direction);
+                    p.line_to(point + direction);
+                }
+            }
+        });
+        frame.stroke(&tangents, thin(Color::from(theme.tangent)));
+    }
+}
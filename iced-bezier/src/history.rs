@@ -0,0 +1,339 @@
+// Undo / redo of scene edits
+//
+// Every change of the scene is an `Edit` that knows its inverse; undo applies the
+// inverse of the last edit, redo applies the edit again.
+
+use bezier_core::bezier::BezierCurve;
+use bezier_core::path::BezierPath;
+use bezier_core::point::Vec2;
+use bezier_core::project::{Display, Project};
+
+use crate::bezier::{PointId, Real};
+
+/// Edits kept before the oldest is dropped
+const MAX_EDITS: usize = 256;
+
+/// A curve as kept by the history, without its arc trees
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedCurve {
+    pub path: BezierPath<Real>,
+    pub display: Display,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    /// A whole drag of a control point
+    MovePoint {
+        id: PointId,
+        from: Vec2<Real>,
+        to: Vec2<Real>,
+    },
+    /// Split count, AABB depth and toggles of new curves
+    SetDisplay { from: Display, to: Display },
+    /// Split count, AABB depth and toggles of one curve
+    SetCurveDisplay {
+        curve: usize,
+        from: Display,
+        to: Display,
+    },
+    /// Segment added at the end of an open curve
+    AppendSegment {
+        curve: usize,
+        segment: BezierCurve<Real>,
+    },
+    /// Last segment of an open curve removed
+    PopSegment {
+        curve: usize,
+        segment: BezierCurve<Real>,
+    },
+    /// Curves inserted at `index`, by Add Curve, Duplicate or an import
+    InsertCurves {
+        index: usize,
+        curves: Vec<SavedCurve>,
+    },
+    RemoveCurves {
+        index: usize,
+        curves: Vec<SavedCurve>,
+    },
+    /// Z-order change: the new curve `i` is the old curve `order[i]`
+    Reorder { order: Vec<usize> },
+    /// Several edits undone and redone as one
+    Batch(Vec<Edit>),
+    /// Whole scene replaced, by Initialize
+    Replace {
+        from: Box<Project>,
+        to: Box<Project>,
+    },
+}
+
+impl Edit {
+    pub fn inverse(&self) -> Edit {
+        match self {
+            Edit::MovePoint { id, from, to } => Edit::MovePoint {
+                id: *id,
+                from: *to,
+                to: *from,
+            },
+            Edit::SetDisplay { from, to } => Edit::SetDisplay {
+                from: to.clone(),
+                to: from.clone(),
+            },
+            Edit::AppendSegment { curve, segment } => Edit::PopSegment {
+                curve: *curve,
+                segment: segment.clone(),
+            },
+            Edit::PopSegment { curve, segment } => Edit::AppendSegment {
+                curve: *curve,
+                segment: segment.clone(),
+            },
+            Edit::SetCurveDisplay { curve, from, to } => Edit::SetCurveDisplay {
+                curve: *curve,
+                from: to.clone(),
+                to: from.clone(),
+            },
+            Edit::InsertCurves { index, curves } => Edit::RemoveCurves {
+                index: *index,
+                curves: curves.clone(),
+            },
+            Edit::RemoveCurves { index, curves } => Edit::InsertCurves {
+                index: *index,
+                curves: curves.clone(),
+            },
+            Edit::Reorder { order } => {
+                let mut inverse = vec![0; order.len()];
+                for (i, &old) in order.iter().enumerate() {
+                    inverse[old] = i;
+                }
+                Edit::Reorder { order: inverse }
+            }
+            Edit::Batch(edits) => Edit::Batch(edits.iter().rev().map(Edit::inverse).collect()),
+            Edit::Replace { from, to } => Edit::Replace {
+                from: to.clone(),
+                to: from.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    /// Record an edit that was just applied; the redo stack is dropped
+    pub fn push(&mut self, edit: Edit) {
+        if self.undo.len() == MAX_EDITS {
+            self.undo.remove(0);
+        }
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+
+    /// The edit to revert, moved to the redo stack
+    pub fn undo(&mut self) -> Option<Edit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+        Some(edit.inverse())
+    }
+
+    /// The edit to apply again, moved back to the undo stack
+    pub fn redo(&mut self) -> Option<Edit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        Some(edit)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bezier::State;
+
+    fn point(curve: usize, segment: usize, index: usize) -> PointId {
+        PointId {
+            curve,
+            segment,
+            index,
+        }
+    }
+
+    fn control_point(state: &State, id: PointId) -> Vec2<Real> {
+        state.to_project().curves[id.curve].segments[id.segment].control_pts[id.index]
+    }
+
+    #[test]
+    fn edits_apply_and_revert() {
+        let mut state = State::new();
+        let project = state.to_project();
+        let path = project.curves[0].clone();
+        let end = path.segments[0].control_pts[3];
+        let display = Display {
+            num_split: 3,
+            is_dotted: true,
+            ..Display::default()
+        };
+        let saved = SavedCurve {
+            path: path.clone(),
+            display: display.clone(),
+        };
+        let edits = vec![
+            Edit::MovePoint {
+                id: point(0, 0, 1),
+                from: path.segments[0].control_pts[1],
+                to: Vec2::new(1.0, 2.0),
+            },
+            Edit::SetDisplay {
+                from: project.display.clone(),
+                to: display.clone(),
+            },
+            Edit::SetCurveDisplay {
+                curve: 0,
+                from: project.curve_display(0).clone(),
+                to: display,
+            },
+            Edit::AppendSegment {
+                curve: 0,
+                segment: BezierCurve::line(end, Vec2::new(700.0, 300.0)),
+            },
+            Edit::InsertCurves {
+                index: 1,
+                curves: vec![saved.clone()],
+            },
+            Edit::RemoveCurves {
+                index: 0,
+                curves: vec![SavedCurve {
+                    path,
+                    display: project.curve_display(0).clone(),
+                }],
+            },
+            Edit::Batch(vec![
+                Edit::InsertCurves {
+                    index: 1,
+                    curves: vec![saved],
+                },
+                Edit::Reorder { order: vec![1, 0] },
+            ]),
+            Edit::Replace {
+                from: Box::new(project.clone()),
+                to: Box::new(Project {
+                    curves: Vec::new(),
+                    ..project
+                }),
+            },
+        ];
+
+        for edit in edits {
+            let before = state.to_project();
+            state.commit(edit.clone());
+            let after = state.to_project();
+            assert_ne!(before, after, "{:?}", edit);
+            assert_eq!(edit.inverse().inverse(), edit);
+
+            assert!(state.undo());
+            assert_eq!(state.to_project(), before, "{:?}", edit);
+            assert!(state.redo());
+            assert_eq!(state.to_project(), after, "{:?}", edit);
+            assert!(state.undo());
+        }
+    }
+
+    #[test]
+    fn reorder_inverse_restores_order() {
+        let order = vec![2, 0, 3, 1];
+        let edit = Edit::Reorder {
+            order: order.clone(),
+        };
+        match edit.inverse() {
+            Edit::Reorder { order: inverse } => {
+                for (i, &old) in order.iter().enumerate() {
+                    assert_eq!(inverse[old], i);
+                }
+            }
+            edit => panic!("{:?}", edit),
+        }
+    }
+
+    #[test]
+    fn drag_steps_merge_on_release() {
+        let mut state = State::new();
+        let id = point(0, 0, 2);
+        let start = control_point(&state, id);
+        state.start_drag(id);
+        for step in 1..=5 {
+            state.drag_point(id, start + Vec2::new(10.0 * step as Real, 0.0));
+        }
+        assert!(!state.can_undo());
+        state.end_drag(id);
+
+        // one undo takes back the whole drag
+        assert!(state.undo());
+        assert!(!state.can_undo());
+        assert_eq!(control_point(&state, id), start);
+        assert!(state.redo());
+        assert_eq!(control_point(&state, id), start + Vec2::new(50.0, 0.0));
+
+        // a drag back to where it started records nothing
+        state.start_drag(id);
+        state.drag_point(id, Vec2::zero());
+        state.drag_point(id, start + Vec2::new(50.0, 0.0));
+        state.end_drag(id);
+        assert!(state.undo());
+        assert!(!state.can_undo());
+    }
+
+    #[test]
+    fn replace_keeps_the_view() {
+        let mut state = State::new();
+        state.show_grid = true;
+        state.snap_grid = true;
+        state.show_construction = true;
+        state.initialize();
+        state.add_curve();
+        state.initialize();
+        assert!(state.undo());
+        assert_eq!(state.curve_count(), 2);
+        assert!(state.show_grid);
+        assert!(state.snap_grid && state.show_construction);
+        assert!(state.can_undo());
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let reorder = |order: Vec<usize>| Edit::Reorder { order };
+        let mut history = History::default();
+        history.push(reorder(vec![1, 0]));
+        history.push(reorder(vec![0, 2, 1]));
+        assert_eq!(history.undo(), Some(reorder(vec![0, 2, 1])));
+        assert!(history.can_redo());
+
+        history.push(reorder(vec![2, 0, 1]));
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo(), Some(reorder(vec![1, 2, 0])));
+        assert_eq!(history.undo(), Some(reorder(vec![1, 0])));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn oldest_edit_is_dropped() {
+        let mut history = History::default();
+        for _ in 0..MAX_EDITS + 1 {
+            history.push(Edit::Reorder { order: vec![0] });
+        }
+        let mut count = 0;
+        while history.undo().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_EDITS);
+    }
+}
@@ -1,37 +1,218 @@
 use iced::{
-    button, executor, slider, window, Align, Application, Button, Canvas, Checkbox, Clipboard,
-    Column, Command, Element, Length, Row, Settings, Slider, Text,
+    button, executor, keyboard, pick_list, scrollable, slider, text_input, window, Align,
+    Application, Button, Canvas, Checkbox, Clipboard, Color, Column, Command, Element, Length,
+    PickList, Row, Scrollable, Settings, Slider, Subscription, Text, TextInput,
 };
+use iced_native::{event, Event};
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use bezier_core::dxf::DxfOptions;
+use bezier_core::font::Font;
+use bezier_core::gcode::{GcodeOptions, Units};
+use bezier_core::keymap::{Action, KeyChord, Keymap};
+use bezier_core::path::mirror_y;
+use bezier_core::point::Vec2;
+use bezier_core::project::{Display, Project};
+use bezier_core::samples::{SampleOptions, Spacing};
+use bezier_core::svg;
+use bezier_core::svg_path;
+use bezier_core::theme::{Theme, THEMES};
 
 pub mod bezier;
 pub mod biarc;
-pub mod tree;
-pub mod util;
+pub mod construction;
+pub mod history;
+pub mod ticks;
+pub mod viewport;
+
+/// Font of Import Text, unless the path field names a .ttf file
+const DEFAULT_FONT: &[u8] = include_bytes!("../../rs-bezier/assets/NotoSans-Regular.ttf");
+
+/// Interval between animation frames of the construction
+const ANIMATION_TICK: Duration = Duration::from_millis(30);
+/// Time the animated construction takes to run along the curve
+const ANIMATION_PERIOD: Duration = Duration::from_secs(8);
 
+/// Keymap loaded when `--keymap` is not given, if it exists
+const DEFAULT_KEYMAP_FILE: &str = "bezier-keys.txt";
+/// Theme loaded when `--theme` is not given, if it exists
+const DEFAULT_THEME_FILE: &str = "bezier-theme.txt";
+/// Zoom factor of the zoom shortcuts
+const ZOOM_STEP: f32 = 1.25;
+/// Range of the Arc Split slider
+const MAX_SPLIT: usize = 5;
+/// Numeric G-code export options, in the order of the panel
+const GCODE_FIELDS: [&str; 8] = [
+    "Scale",
+    "Feed",
+    "Plunge",
+    "Safe Z",
+    "Cut Z",
+    "Digits",
+    "Offset",
+    "Max radius",
+];
+
+// usage: iced-bezier [--keymap FILE] [--theme NAME|FILE] [PROJECT]
 pub fn main() -> iced::Result {
+    let mut flags = Flags::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--keymap" {
+            flags.keymap = args.next().map(PathBuf::from);
+        } else if arg == "--theme" {
+            flags.theme = args.next();
+        } else {
+            flags.project = Some(PathBuf::from(arg));
+        }
+    }
+
     Bezier::run(Settings {
         antialiasing: true,
         window: window::Settings {
-            size: (720, 480),
+            size: (800, 600),
             ..window::Settings::default()
         },
+        flags,
         ..Settings::default()
     })
 }
 
+#[derive(Debug, Default)]
+struct Flags {
+    /// Project file to open
+    project: Option<PathBuf>,
+    keymap: Option<PathBuf>,
+    /// Built-in theme name or theme file
+    theme: Option<String>,
+}
+
 struct Bezier {
     canvas: bezier::State,
+    keymap: Keymap,
+    /// Built-in themes, then a loaded one
+    themes: Vec<Theme>,
+    theme_state: pick_list::State<String>,
     init_state: button::State,
     dot_state: button::State,
     mesh_state: button::State,
+    help_state: button::State,
+    undo_state: button::State,
+    redo_state: button::State,
+    add_curve_state: button::State,
+    delete_state: button::State,
+    duplicate_state: button::State,
+    raise_state: button::State,
+    lower_state: button::State,
+    add_segment_state: button::State,
+    remove_segment_state: button::State,
+    fit_state: button::State,
+    grid_spacing_state: text_input::State,
+    point_x_state: text_input::State,
+    point_y_state: text_input::State,
+    set_point_state: button::State,
+    construction_slider_state: slider::State,
+    box_opacity_slider_state: slider::State,
+    parent_node_state: button::State,
+    left_node_state: button::State,
+    right_node_state: button::State,
+    clear_node_state: button::State,
+    panel_state: scrollable::State,
+    animate_state: button::State,
     arc_slider_state: slider::State,
     aabb_slider_state: slider::State,
-    checkbox_state: bool,
+    path_state: text_input::State,
+    open_state: button::State,
+    save_state: button::State,
+    save_as_state: button::State,
+    export_svg_state: button::State,
+    import_svg_state: button::State,
+    export_gcode_state: button::State,
+    export_dxf_state: button::State,
+    text_state: text_input::State,
+    import_text_state: button::State,
+    export_csv_state: button::State,
+    export_json_state: button::State,
+    gcode_states: [text_input::State; GCODE_FIELDS.len()],
+    // options of Export G-code, and their fields as typed
+    gcode: GcodeOptions,
+    gcode_inputs: Vec<String>,
+    // sample exports at equal arc length instead of equal t
+    arc_length_samples: bool,
+    // the construction parameter runs along the curve
+    animating: bool,
+    // file of Save, set by Open / Save As
+    project_path: Option<PathBuf>,
+    path_input: String,
+    text_input: String,
+    grid_spacing_input: String,
+    // coordinates of the selected control point, as typed
+    point_x_input: String,
+    point_y_input: String,
+    status: String,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Message {
+#[derive(Debug, Clone)]
+pub enum Message {
     Initialize,
+    Undo,
+    Redo,
+    AddCurve,
+    DeleteSelected,
+    DuplicateSelected,
+    RaiseSelected,
+    LowerSelected,
+    SelectAll,
+    AddSegment,
+    RemoveSegment,
+    FitView,
+    /// Curves were selected or a control point grabbed or dragged on the canvas
+    SelectionChanged,
+    PointXChanged(String),
+    PointYChanged(String),
+    SetPoint,
+    /// The arc or box under the cursor changed
+    NodeHovered,
+    SelectNode(usize),
+    ClearNode,
+    ToggleGrid(bool),
+    GridSpacingChanged(String),
+    SnapGrid(bool),
+    SnapPoints(bool),
+    SnapCurves(bool),
+    KeyPressed(KeyChord),
+    /// Flip the overlay element of a keymap action
+    ToggleOverlay(Action),
+    ToggleBoxes,
+    Zoom(f32),
+    ToggleHelp,
+    SetTheme(String),
+    SetBoxOpacity(f32),
+    OutlineOnly(bool),
+    NextTheme,
+    ToggleConstruction(bool),
+    SetConstructionT(f64),
+    ToggleAnimation,
+    Tick(Instant),
+    PathChanged(String),
+    Open,
+    Save,
+    SaveAs,
+    ExportSvg,
+    ImportSvg,
+    ExportGcode,
+    /// Typed text of the G-code option at an index of `GCODE_FIELDS`
+    GcodeFieldChanged(usize, String),
+    GcodeInches(bool),
+    GcodeFlipY(bool),
+    ExportDxf,
+    TextChanged(String),
+    ImportText,
+    ExportCsv,
+    ExportSamplesJson,
+    ToggleArcLength(bool),
     ToggleDotted,
     ToggleMesh,
     ToggleAABB(bool),
@@ -42,31 +223,174 @@ enum Message {
 impl Application for Bezier {
     type Executor = executor::Default;
     type Message = Message;
-    type Flags = ();
-
-    fn new(_flags: ()) -> (Self, Command<Message>) {
-        (
-            Bezier {
-                canvas: bezier::State::new(),
-                init_state: Default::default(),
-                dot_state: Default::default(),
-                mesh_state: Default::default(),
-                arc_slider_state: Default::default(),
-                aabb_slider_state: Default::default(),
-                checkbox_state: false,
+    type Flags = Flags;
+
+    fn new(flags: Flags) -> (Self, Command<Message>) {
+        let mut bezier = Bezier {
+            canvas: bezier::State::new(),
+            keymap: Keymap::default(),
+            themes: THEMES
+                .iter()
+                .filter_map(|name| Theme::named(name))
+                .collect(),
+            theme_state: Default::default(),
+            init_state: Default::default(),
+            dot_state: Default::default(),
+            mesh_state: Default::default(),
+            help_state: Default::default(),
+            undo_state: Default::default(),
+            redo_state: Default::default(),
+            add_curve_state: Default::default(),
+            delete_state: Default::default(),
+            duplicate_state: Default::default(),
+            raise_state: Default::default(),
+            lower_state: Default::default(),
+            add_segment_state: Default::default(),
+            remove_segment_state: Default::default(),
+            fit_state: Default::default(),
+            grid_spacing_state: Default::default(),
+            point_x_state: Default::default(),
+            point_y_state: Default::default(),
+            set_point_state: Default::default(),
+            construction_slider_state: Default::default(),
+            box_opacity_slider_state: Default::default(),
+            parent_node_state: Default::default(),
+            left_node_state: Default::default(),
+            right_node_state: Default::default(),
+            clear_node_state: Default::default(),
+            panel_state: Default::default(),
+            animate_state: Default::default(),
+            animating: false,
+            arc_slider_state: Default::default(),
+            aabb_slider_state: Default::default(),
+            path_state: Default::default(),
+            open_state: Default::default(),
+            save_state: Default::default(),
+            save_as_state: Default::default(),
+            export_svg_state: Default::default(),
+            import_svg_state: Default::default(),
+            export_gcode_state: Default::default(),
+            export_dxf_state: Default::default(),
+            text_state: Default::default(),
+            import_text_state: Default::default(),
+            export_csv_state: Default::default(),
+            export_json_state: Default::default(),
+            gcode_states: Default::default(),
+            // the world is y-up like the machine
+            gcode: GcodeOptions {
+                flip_y: false,
+                ..GcodeOptions::default()
             },
-            Command::none(),
-        )
+            gcode_inputs: Vec::new(),
+            arc_length_samples: false,
+            project_path: None,
+            path_input: String::from("bezier.json"),
+            text_input: String::from("A"),
+            grid_spacing_input: String::new(),
+            point_x_input: String::new(),
+            point_y_input: String::new(),
+            status: String::new(),
+        };
+        bezier.grid_spacing_input = bezier.canvas.grid_spacing.to_string();
+        bezier.gcode_inputs = (0..GCODE_FIELDS.len())
+            .map(|index| gcode_field(&bezier.gcode, index))
+            .collect();
+        let keymap = flags.keymap.or_else(|| {
+            let path = PathBuf::from(DEFAULT_KEYMAP_FILE);
+            if path.exists() {
+                Some(path)
+            } else {
+                None
+            }
+        });
+        if let Some(path) = keymap {
+            match Keymap::load(&path) {
+                Ok(keymap) => bezier.keymap = keymap,
+                Err(err) => bezier.status = format!("Cannot load {}: {}", path.display(), err),
+            }
+        }
+        match flags.theme.as_deref().and_then(Theme::named) {
+            Some(theme) => bezier.canvas.set_theme(theme, None),
+            None => {
+                let theme = flags.theme.map(PathBuf::from).or_else(|| {
+                    let path = PathBuf::from(DEFAULT_THEME_FILE);
+                    if path.exists() {
+                        Some(path)
+                    } else {
+                        None
+                    }
+                });
+                if let Some(path) = theme {
+                    match Theme::load(&path) {
+                        Ok(theme) => {
+                            bezier.themes.retain(|builtin| builtin.name != theme.name);
+                            bezier.themes.push(theme.clone());
+                            bezier.canvas.set_theme(theme, None);
+                        }
+                        Err(err) => {
+                            bezier.status = format!("Cannot load {}: {}", path.display(), err)
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(path) = flags.project {
+            bezier.path_input = path.display().to_string();
+            bezier.open(path);
+        }
+        (bezier, Command::none())
     }
 
     fn title(&self) -> String {
         String::from("GeoModel Assignment 2")
     }
 
-    fn update(&mut self, message: Message, _clipboard: &mut Clipboard) -> Command<Message> {
+    fn update(&mut self, message: Message, clipboard: &mut Clipboard) -> Command<Message> {
         match message {
             Message::Initialize => {
-                self.canvas = bezier::State::new();
+                self.canvas.initialize();
+            }
+            Message::Undo => {
+                if !self.canvas.undo() {
+                    self.status = String::from("Nothing to undo");
+                }
+            }
+            Message::Redo => {
+                if !self.canvas.redo() {
+                    self.status = String::from("Nothing to redo");
+                }
+            }
+            Message::AddCurve => {
+                self.canvas.add_curve();
+            }
+            Message::DeleteSelected => {
+                if !self.canvas.delete_selected() {
+                    self.status = String::from("No curve selected");
+                }
+            }
+            Message::DuplicateSelected => {
+                if !self.canvas.duplicate_selected() {
+                    self.status = String::from("No curve selected");
+                }
+            }
+            Message::RaiseSelected => {
+                self.canvas.raise_selected();
+            }
+            Message::LowerSelected => {
+                self.canvas.lower_selected();
+            }
+            Message::SelectAll => {
+                self.canvas.select_all();
+            }
+            Message::AddSegment => {
+                if !self.canvas.append_segment() {
+                    self.status = String::from("No open curve to extend");
+                }
+            }
+            Message::RemoveSegment => {
+                if !self.canvas.pop_segment() {
+                    self.status = String::from("The curve has a single segment");
+                }
             }
             Message::ToggleDotted => {
                 self.canvas.toggle_dotted();
@@ -81,28 +405,543 @@ impl Application for Bezier {
                 self.canvas.set_aabb_depth(aabb_depth as usize);
             }
             Message::ToggleAABB(checked) => {
-                self.checkbox_state = checked;
                 self.canvas.set_bezier_aabb(checked);
             }
+            Message::FitView => {
+                self.canvas.fit_to_content();
+            }
+            Message::SelectionChanged => {}
+            Message::PointXChanged(x) => {
+                self.point_x_input = x;
+            }
+            Message::PointYChanged(y) => {
+                self.point_y_input = y;
+            }
+            Message::SetPoint => {
+                let x = self.point_x_input.trim().parse::<f64>();
+                let y = self.point_y_input.trim().parse::<f64>();
+                match (x, y) {
+                    (Ok(x), Ok(y)) if x.is_finite() && y.is_finite() => {
+                        if !self.canvas.move_selected(Vec2::new(x, y)) {
+                            self.status = String::from("No point selected");
+                        }
+                    }
+                    _ => self.status = String::from("Invalid coordinates"),
+                }
+            }
+            Message::KeyPressed(chord) => {
+                if let Some(action) = self.keymap.action(&chord) {
+                    let message = action_message(action, &self.canvas.display());
+                    return self.update(message, clipboard);
+                }
+            }
+            Message::ToggleOverlay(action) => {
+                self.canvas.toggle_overlay(action);
+            }
+            Message::ToggleBoxes => {
+                self.canvas.toggle_boxes();
+            }
+            Message::Zoom(factor) => {
+                self.canvas.zoom(factor);
+            }
+            Message::SetTheme(name) => {
+                if let Some(theme) = self.themes.iter().find(|theme| theme.name == name) {
+                    self.canvas.set_theme(theme.clone(), None);
+                }
+            }
+            Message::SetBoxOpacity(fill_opacity) => {
+                self.canvas.set_box_style(bezier::BoxStyle {
+                    fill_opacity,
+                    ..self.canvas.box_style
+                });
+            }
+            Message::OutlineOnly(outline_only) => {
+                self.canvas.set_box_style(bezier::BoxStyle {
+                    outline_only,
+                    ..self.canvas.box_style
+                });
+            }
+            Message::NextTheme => {
+                let current = self
+                    .themes
+                    .iter()
+                    .position(|theme| theme.name == self.canvas.theme.name);
+                let next = current.map_or(0, |i| (i + 1) % self.themes.len());
+                self.canvas.set_theme(self.themes[next].clone(), None);
+            }
+            Message::ToggleHelp => {
+                self.canvas.help = match self.canvas.help {
+                    Some(_) => None,
+                    None => Some(self.keymap.help()),
+                };
+                self.canvas.request_redraw();
+            }
+            Message::NodeHovered => {}
+            Message::SelectNode(index) => {
+                self.canvas.select_node(index);
+            }
+            Message::ClearNode => {
+                self.canvas.clear_node();
+            }
+            Message::ToggleGrid(checked) => {
+                self.canvas.set_show_grid(checked);
+            }
+            Message::GridSpacingChanged(spacing) => {
+                match spacing.trim().parse::<f64>() {
+                    Ok(value) if value > 0.0 && value.is_finite() => {
+                        self.canvas.set_grid_spacing(value)
+                    }
+                    _ => {}
+                }
+                self.grid_spacing_input = spacing;
+            }
+            Message::SnapGrid(checked) => {
+                self.canvas.snap_grid = checked;
+            }
+            Message::SnapPoints(checked) => {
+                self.canvas.snap_points = checked;
+            }
+            Message::SnapCurves(checked) => {
+                self.canvas.snap_curves = checked;
+            }
+            Message::ToggleConstruction(checked) => {
+                self.canvas.set_show_construction(checked);
+                if !checked {
+                    self.animating = false;
+                }
+            }
+            Message::SetConstructionT(t) => {
+                self.canvas.set_construction_t(t);
+            }
+            Message::ToggleAnimation => {
+                self.animating = !self.animating;
+                if self.animating {
+                    self.canvas.set_show_construction(true);
+                }
+            }
+            Message::Tick(_) => {
+                let step = ANIMATION_TICK.as_secs_f64() / ANIMATION_PERIOD.as_secs_f64();
+                self.canvas.advance_construction(step);
+            }
+            Message::PathChanged(path) => {
+                self.path_input = path;
+            }
+            Message::Open => {
+                self.open(PathBuf::from(&self.path_input));
+            }
+            Message::Save => match self.project_path.clone() {
+                Some(path) => self.save(path),
+                None => self.save(PathBuf::from(&self.path_input)),
+            },
+            Message::SaveAs => {
+                self.save(PathBuf::from(&self.path_input));
+            }
+            Message::ExportSvg => {
+                let svg = self.canvas.to_svg();
+                self.export("svg", svg);
+            }
+            Message::ExportGcode => {
+                if self.gcode.safe_z <= self.gcode.cut_z {
+                    self.status = String::from("Safe Z must be above Cut Z");
+                } else {
+                    let gcode = self.canvas.to_gcode(&self.gcode);
+                    self.export("nc", gcode);
+                }
+            }
+            Message::GcodeFieldChanged(index, text) => {
+                // invalid text is kept for editing, the option keeps its last value
+                set_gcode_field(&mut self.gcode, index, &text);
+                self.gcode_inputs[index] = text;
+            }
+            Message::GcodeInches(checked) => {
+                self.gcode.units = if checked {
+                    Units::Inches
+                } else {
+                    Units::Millimeters
+                };
+            }
+            Message::GcodeFlipY(checked) => {
+                self.gcode.flip_y = checked;
+            }
+            Message::ExportDxf => {
+                let dxf = self.canvas.to_dxf(&DxfOptions {
+                    spline: true,
+                    flip_y: false,
+                    ..DxfOptions::default()
+                });
+                self.export("dxf", dxf);
+            }
+            Message::ImportSvg => {
+                let path = PathBuf::from(&self.path_input).with_extension("svg");
+                self.import_svg(path);
+            }
+            Message::ExportCsv => {
+                let (samples, leaves) = self.canvas.to_csv(&self.sample_options());
+                if self.export("samples.csv", samples) {
+                    self.export("leaves.csv", leaves);
+                }
+            }
+            Message::ExportSamplesJson => {
+                let json = self.canvas.to_samples_json(&self.sample_options());
+                self.export("samples.json", json);
+            }
+            Message::ToggleArcLength(checked) => {
+                self.arc_length_samples = checked;
+            }
+            Message::TextChanged(text) => {
+                self.text_input = text;
+            }
+            Message::ImportText => {
+                let path = PathBuf::from(&self.path_input);
+                let is_font = matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("ttf") | Some("TTF")
+                );
+                if is_font {
+                    self.import_text(Some(path));
+                } else {
+                    self.import_text(None);
+                }
+            }
         }
 
+        self.sync_point_inputs();
         Command::none()
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        let shortcuts = iced_native::subscription::events_with(key_pressed);
+        if self.animating {
+            Subscription::batch(vec![
+                shortcuts,
+                ticks::every(ANIMATION_TICK).map(Message::Tick),
+            ])
+        } else {
+            shortcuts
+        }
+    }
+
     fn view(&mut self) -> Element<Message> {
-        let num_split = self.canvas.num_split;
-        let aabb_depth = self.canvas.aabb_depth;
+        let arc_length_samples = self.arc_length_samples;
+        // the settings of the first selected curve, else those of new curves
+        let display = self.canvas.display();
+        let num_split = display.num_split;
+        let aabb_depth = display.aabb_depth;
+        let use_bezier_aabb = display.use_bezier_aabb;
+        let has_selection = !self.canvas.selection().is_empty();
+        let selection_label = format!(
+            "{} of {} curves selected",
+            self.canvas.selection().len(),
+            self.canvas.curve_count()
+        );
         let num_string = num_split.to_string();
         let aabb_string = aabb_depth.to_string();
+        let can_undo = self.canvas.can_undo();
+        let can_redo = self.canvas.can_redo();
+        let show_grid = self.canvas.show_grid;
+        let snap_grid = self.canvas.snap_grid;
+        let snap_points = self.canvas.snap_points;
+        let snap_curves = self.canvas.snap_curves;
+        let overlay = self.canvas.overlay;
+        let theme_names: Vec<String> = self.themes.iter().map(|theme| theme.name.clone()).collect();
+        let theme_name = self.canvas.theme.name.clone();
+        let box_style = self.canvas.box_style;
+        // one colored line per shown tree level
+        let box_legend =
+            self.canvas
+                .box_levels()
+                .iter()
+                .fold(Column::new().spacing(2), |legend, level| {
+                    legend.push(
+                        Text::new(format!(
+                            "Depth {}: {} boxes, area {:.1}",
+                            level.depth, level.count, level.area
+                        ))
+                        .size(16)
+                        .color(Color::from(self.canvas.theme.box_color(level.depth))),
+                    )
+                });
+        let show_construction = self.canvas.show_construction;
+        let construction_t = self.canvas.construction_t;
+        let construction_label = match self.canvas.construction_location() {
+            Some((curve, location)) => format!(
+                "Curve {}, segment {}, t = {:.3}, interval {} of {}",
+                curve,
+                location.segment,
+                location.t,
+                location.interval + 1,
+                location.intervals
+            ),
+            None => String::from("No curve"),
+        };
+        let animate_label = if self.animating { "Pause" } else { "Play" };
+        let node = self.canvas.selected_node();
+        let node_label = match node {
+            Some(ref info) => format_node(info),
+            None => String::from("Click an arc or a box"),
+        };
+        let parent_node = node.as_ref().and_then(|info| info.parent);
+        let child_nodes = node.as_ref().and_then(|info| info.children);
+        let gcode_inches = self.gcode.units == Units::Inches;
+        let gcode_flip_y = self.gcode.flip_y;
+        let gcode_fields = self
+            .gcode_states
+            .iter_mut()
+            .zip(&self.gcode_inputs)
+            .enumerate()
+            .fold(
+                Column::new().spacing(5),
+                |fields, (index, (state, input))| {
+                    fields.push(
+                        Row::new()
+                            .spacing(5)
+                            .align_items(Align::Center)
+                            .push(Text::new(GCODE_FIELDS[index]).width(Length::Units(80)))
+                            .push(
+                                TextInput::new(state, "", input, move |text| {
+                                    Message::GcodeFieldChanged(index, text)
+                                })
+                                .padding(5),
+                            ),
+                    )
+                },
+            );
+        let point_label = match self.canvas.selected_point() {
+            Some((id, _)) => format!(
+                "Curve {}, segment {}, point {}",
+                id.curve, id.segment, id.index
+            ),
+            None => String::from("No point selected"),
+        };
 
         Column::new()
             .padding(20)
             .spacing(10)
             .align_items(Align::Start)
             .push(
-                Canvas::new(&mut self.canvas)
-                    .width(Length::Fill)
-                    .height(Length::Fill),
+                Row::new()
+                    .spacing(10)
+                    .height(Length::Fill)
+                    .push(
+                        Canvas::new(&mut self.canvas)
+                            .width(Length::Fill)
+                            .height(Length::Fill),
+                    )
+                    .push(
+                        Scrollable::new(&mut self.panel_state)
+                            .width(Length::Units(200))
+                            .spacing(8)
+                            .align_items(Align::Start)
+                            .push(Text::new("Grid"))
+                            .push(Checkbox::new(show_grid, "Show grid", Message::ToggleGrid))
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .align_items(Align::Center)
+                                    .push(Text::new("Spacing").width(Length::Units(60)))
+                                    .push(
+                                        TextInput::new(
+                                            &mut self.grid_spacing_state,
+                                            "spacing",
+                                            &self.grid_spacing_input,
+                                            Message::GridSpacingChanged,
+                                        )
+                                        .padding(5),
+                                    ),
+                            )
+                            .push(Checkbox::new(snap_grid, "Snap to grid", Message::SnapGrid))
+                            .push(Checkbox::new(
+                                snap_points,
+                                "Snap to points",
+                                Message::SnapPoints,
+                            ))
+                            .push(Checkbox::new(
+                                snap_curves,
+                                "Snap to curves",
+                                Message::SnapCurves,
+                            ))
+                            .push(Text::new("Shift: 15\u{b0} steps").size(16))
+                            .push(Text::new("Theme"))
+                            .push(PickList::new(
+                                &mut self.theme_state,
+                                theme_names,
+                                Some(theme_name),
+                                Message::SetTheme,
+                            ))
+                            .push(Text::new("Boxes"))
+                            .push(Checkbox::new(
+                                box_style.outline_only,
+                                "Outline only",
+                                Message::OutlineOnly,
+                            ))
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .align_items(Align::Center)
+                                    .push(Text::new("Fill").width(Length::Units(60)))
+                                    .push(
+                                        Slider::new(
+                                            &mut self.box_opacity_slider_state,
+                                            0.0..=1.0,
+                                            box_style.fill_opacity,
+                                            Message::SetBoxOpacity,
+                                        )
+                                        .step(0.05),
+                                    ),
+                            )
+                            .push(box_legend)
+                            .push(Text::new("Overlay"))
+                            .push(Checkbox::new(overlay.arc_circles, "Arc circles", |_| {
+                                Message::ToggleOverlay(Action::ToggleCircles)
+                            }))
+                            .push(Checkbox::new(
+                                overlay.joint_circles,
+                                "Joint circles",
+                                |_| Message::ToggleOverlay(Action::ToggleJointCircles),
+                            ))
+                            .push(Checkbox::new(overlay.tangents, "Tangents", |_| {
+                                Message::ToggleOverlay(Action::ToggleTangents)
+                            }))
+                            .push(Text::new("Construction"))
+                            .push(Checkbox::new(
+                                show_construction,
+                                "Show construction",
+                                Message::ToggleConstruction,
+                            ))
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .align_items(Align::Center)
+                                    .push(
+                                        Button::new(
+                                            &mut self.animate_state,
+                                            Text::new(animate_label),
+                                        )
+                                        .padding(5)
+                                        .on_press(Message::ToggleAnimation),
+                                    )
+                                    .push(
+                                        Slider::new(
+                                            &mut self.construction_slider_state,
+                                            0.0..=1.0,
+                                            construction_t,
+                                            Message::SetConstructionT,
+                                        )
+                                        .step(0.001),
+                                    ),
+                            )
+                            .push(Text::new(construction_label).size(16))
+                            .push(Text::new("Curves"))
+                            .push(Text::new(selection_label).size(16))
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .push(on_press_if(
+                                        Button::new(&mut self.delete_state, Text::new("Delete"))
+                                            .padding(5),
+                                        has_selection,
+                                        Message::DeleteSelected,
+                                    ))
+                                    .push(on_press_if(
+                                        Button::new(
+                                            &mut self.duplicate_state,
+                                            Text::new("Duplicate"),
+                                        )
+                                        .padding(5),
+                                        has_selection,
+                                        Message::DuplicateSelected,
+                                    )),
+                            )
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .push(on_press_if(
+                                        Button::new(&mut self.raise_state, Text::new("Raise"))
+                                            .padding(5),
+                                        has_selection,
+                                        Message::RaiseSelected,
+                                    ))
+                                    .push(on_press_if(
+                                        Button::new(&mut self.lower_state, Text::new("Lower"))
+                                            .padding(5),
+                                        has_selection,
+                                        Message::LowerSelected,
+                                    )),
+                            )
+                            .push(Text::new("Point"))
+                            .push(Text::new(point_label).size(16))
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .align_items(Align::Center)
+                                    .push(Text::new("X").width(Length::Units(20)))
+                                    .push(
+                                        TextInput::new(
+                                            &mut self.point_x_state,
+                                            "x",
+                                            &self.point_x_input,
+                                            Message::PointXChanged,
+                                        )
+                                        .padding(5)
+                                        .on_submit(Message::SetPoint),
+                                    ),
+                            )
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .align_items(Align::Center)
+                                    .push(Text::new("Y").width(Length::Units(20)))
+                                    .push(
+                                        TextInput::new(
+                                            &mut self.point_y_state,
+                                            "y",
+                                            &self.point_y_input,
+                                            Message::PointYChanged,
+                                        )
+                                        .padding(5)
+                                        .on_submit(Message::SetPoint),
+                                    ),
+                            )
+                            .push(
+                                Button::new(&mut self.set_point_state, Text::new("Set"))
+                                    .padding(8)
+                                    .on_press(Message::SetPoint),
+                            )
+                            .push(Text::new("Node"))
+                            .push(Text::new(node_label).size(16))
+                            .push(
+                                Row::new()
+                                    .spacing(5)
+                                    .push(on_press_if(
+                                        Button::new(&mut self.parent_node_state, Text::new("Up"))
+                                            .padding(5),
+                                        parent_node.is_some(),
+                                        Message::SelectNode(parent_node.unwrap_or_default()),
+                                    ))
+                                    .push(on_press_if(
+                                        Button::new(&mut self.left_node_state, Text::new("Left"))
+                                            .padding(5),
+                                        child_nodes.is_some(),
+                                        Message::SelectNode(child_nodes.map_or(0, |c| c.0)),
+                                    ))
+                                    .push(on_press_if(
+                                        Button::new(&mut self.right_node_state, Text::new("Right"))
+                                            .padding(5),
+                                        child_nodes.is_some(),
+                                        Message::SelectNode(child_nodes.map_or(0, |c| c.1)),
+                                    ))
+                                    .push(on_press_if(
+                                        Button::new(&mut self.clear_node_state, Text::new("Clear"))
+                                            .padding(5),
+                                        node.is_some(),
+                                        Message::ClearNode,
+                                    )),
+                            )
+                            .push(Text::new("G-code"))
+                            .push(Checkbox::new(gcode_inches, "Inches", Message::GcodeInches))
+                            .push(Checkbox::new(gcode_flip_y, "Flip Y", Message::GcodeFlipY))
+                            .push(gcode_fields),
+                    ),
             )
             .push(
                 Row::new()
@@ -131,10 +970,61 @@ impl Application for Bezier {
                                         Button::new(&mut self.dot_state, Text::new("Dashed"))
                                             .padding(8)
                                             .on_press(Message::ToggleDotted),
+                                    )
+                                    .push(
+                                        Button::new(&mut self.help_state, Text::new("Keys"))
+                                            .padding(8)
+                                            .on_press(Message::ToggleHelp),
+                                    ),
+                            )
+                            .push(
+                                Row::new()
+                                    .spacing(10)
+                                    .align_items(Align::Start)
+                                    .push(on_press_if(
+                                        Button::new(&mut self.undo_state, Text::new("Undo"))
+                                            .padding(8),
+                                        can_undo,
+                                        Message::Undo,
+                                    ))
+                                    .push(on_press_if(
+                                        Button::new(&mut self.redo_state, Text::new("Redo"))
+                                            .padding(8),
+                                        can_redo,
+                                        Message::Redo,
+                                    ))
+                                    .push(
+                                        Button::new(
+                                            &mut self.add_curve_state,
+                                            Text::new("Add Curve"),
+                                        )
+                                        .padding(8)
+                                        .on_press(Message::AddCurve),
+                                    )
+                                    .push(
+                                        Button::new(
+                                            &mut self.add_segment_state,
+                                            Text::new("Add Segment"),
+                                        )
+                                        .padding(8)
+                                        .on_press(Message::AddSegment),
+                                    )
+                                    .push(
+                                        Button::new(
+                                            &mut self.remove_segment_state,
+                                            Text::new("Remove Segment"),
+                                        )
+                                        .padding(8)
+                                        .on_press(Message::RemoveSegment),
+                                    )
+                                    .push(
+                                        Button::new(&mut self.fit_state, Text::new("Fit View"))
+                                            .padding(8)
+                                            .on_press(Message::FitView),
                                     ),
                             )
                             .push(Checkbox::new(
-                                self.checkbox_state,
+                                use_bezier_aabb,
                                 "Use Bezier AABB",
                                 Message::ToggleAABB,
                             )),
@@ -152,7 +1042,7 @@ impl Application for Bezier {
                                     .push(Text::new(num_string).width(Length::Units(10)))
                                     .push(Slider::new(
                                         &mut self.arc_slider_state,
-                                        1..=5,
+                                        1..=(MAX_SPLIT as u8),
                                         num_split as u8,
                                         Message::SetBiarc,
                                     )),
@@ -173,6 +1063,358 @@ impl Application for Bezier {
                             ),
                     ),
             )
+            .push(
+                Row::new()
+                    .padding(5)
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(
+                        TextInput::new(
+                            &mut self.path_state,
+                            "project file",
+                            &self.path_input,
+                            Message::PathChanged,
+                        )
+                        .padding(8)
+                        .width(Length::Units(200))
+                        .on_submit(Message::Open),
+                    )
+                    .push(
+                        Button::new(&mut self.open_state, Text::new("Open"))
+                            .padding(8)
+                            .on_press(Message::Open),
+                    )
+                    .push(
+                        Button::new(&mut self.save_state, Text::new("Save"))
+                            .padding(8)
+                            .on_press(Message::Save),
+                    )
+                    .push(
+                        Button::new(&mut self.save_as_state, Text::new("Save As"))
+                            .padding(8)
+                            .on_press(Message::SaveAs),
+                    )
+                    .push(Text::new(&self.status)),
+            )
+            .push(
+                Row::new()
+                    .padding(5)
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(
+                        Button::new(&mut self.import_svg_state, Text::new("Import SVG"))
+                            .padding(8)
+                            .on_press(Message::ImportSvg),
+                    )
+                    .push(
+                        Button::new(&mut self.export_svg_state, Text::new("Export SVG"))
+                            .padding(8)
+                            .on_press(Message::ExportSvg),
+                    )
+                    .push(
+                        Button::new(&mut self.export_gcode_state, Text::new("Export G-code"))
+                            .padding(8)
+                            .on_press(Message::ExportGcode),
+                    )
+                    .push(
+                        Button::new(&mut self.export_dxf_state, Text::new("Export DXF"))
+                            .padding(8)
+                            .on_press(Message::ExportDxf),
+                    )
+                    .push(
+                        TextInput::new(
+                            &mut self.text_state,
+                            "text",
+                            &self.text_input,
+                            Message::TextChanged,
+                        )
+                        .padding(8)
+                        .width(Length::Units(100))
+                        .on_submit(Message::ImportText),
+                    )
+                    .push(
+                        Button::new(&mut self.import_text_state, Text::new("Import Text"))
+                            .padding(8)
+                            .on_press(Message::ImportText),
+                    ),
+            )
+            .push(
+                Row::new()
+                    .padding(5)
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(
+                        Button::new(&mut self.export_csv_state, Text::new("Export CSV"))
+                            .padding(8)
+                            .on_press(Message::ExportCsv),
+                    )
+                    .push(
+                        Button::new(
+                            &mut self.export_json_state,
+                            Text::new("Export Samples JSON"),
+                        )
+                        .padding(8)
+                        .on_press(Message::ExportSamplesJson),
+                    )
+                    .push(Checkbox::new(
+                        arc_length_samples,
+                        "Arc-length spacing",
+                        Message::ToggleArcLength,
+                    )),
+            )
             .into()
     }
 }
+
+impl Bezier {
+    fn open(&mut self, path: PathBuf) {
+        match Project::load(&path) {
+            Ok(project) => {
+                let theme = self.canvas.theme.clone();
+                self.canvas = bezier::State::from_project(&project);
+                self.canvas.set_theme(theme, Some(&project.colors));
+                self.canvas.fit_to_content();
+                self.status = format!("Opened {}", path.display());
+                self.project_path = Some(path);
+            }
+            Err(err) => self.status = format!("Cannot open {}: {}", path.display(), err),
+        }
+    }
+
+    fn import_svg(&mut self, path: PathBuf) {
+        let paths = std::fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|svg| svg_path::import_svg(&svg).map_err(|err| err.to_string()));
+        match paths {
+            Ok(mut paths) => {
+                // SVG is y-down
+                mirror_y(&mut paths);
+                self.status = format!("Imported {} curves from {}", paths.len(), path.display());
+                self.canvas.add_curves(paths);
+            }
+            Err(err) => self.status = format!("Cannot import {}: {}", path.display(), err),
+        }
+    }
+
+    // show the selected point's coordinates, unless they are being typed
+    fn sync_point_inputs(&mut self) {
+        if self.point_x_state.is_focused() || self.point_y_state.is_focused() {
+            return;
+        }
+        match self.canvas.selected_point() {
+            Some((_, p)) => {
+                self.point_x_input = format_coordinate(p.x);
+                self.point_y_input = format_coordinate(p.y);
+            }
+            None => {
+                self.point_x_input.clear();
+                self.point_y_input.clear();
+            }
+        }
+    }
+
+    fn sample_options(&self) -> SampleOptions {
+        SampleOptions {
+            spacing: if self.arc_length_samples {
+                Spacing::ArcLength
+            } else {
+                Spacing::Parameter
+            },
+            ..SampleOptions::default()
+        }
+    }
+
+    // glyph outlines of the text field, laid out y-down and mirrored into the world
+    // outline the text with the font at `path`, or the built-in one
+    fn import_text(&mut self, path: Option<PathBuf>) {
+        let size = 200.0;
+        let (font, name) = match &path {
+            Some(path) => (Font::load(path), path.display().to_string()),
+            None => (
+                Font::parse(DEFAULT_FONT.to_vec()),
+                String::from("the default font"),
+            ),
+        };
+        let paths =
+            font.and_then(|font| font.text_outline(&self.text_input, size, Vec2::new(50.0, size)));
+        match paths {
+            Ok(mut paths) => {
+                mirror_y(&mut paths);
+                self.status = format!("Imported {} contours from {}", paths.len(), name);
+                self.canvas.add_curves(paths);
+            }
+            Err(err) => self.status = format!("Cannot import {}: {}", name, err),
+        }
+    }
+
+    // write next to the project file, with the format's extension; false on failure
+    fn export(&mut self, extension: &str, contents: String) -> bool {
+        let path = PathBuf::from(&self.path_input).with_extension(extension);
+        match std::fs::write(&path, contents) {
+            Ok(()) => {
+                self.status = format!("Exported {}", path.display());
+                true
+            }
+            Err(err) => {
+                self.status = format!("Cannot export {}: {}", path.display(), err);
+                false
+            }
+        }
+    }
+
+    fn save(&mut self, path: PathBuf) {
+        match self.canvas.to_project().save(&path) {
+            Ok(()) => {
+                self.status = format!("Saved {}", path.display());
+                self.project_path = Some(path);
+            }
+            Err(err) => self.status = format!("Cannot save {}: {}", path.display(), err),
+        }
+    }
+}
+
+// value of the G-code option at an index of `GCODE_FIELDS`
+fn gcode_field(options: &GcodeOptions, index: usize) -> String {
+    let value = match index {
+        0 => options.scale,
+        1 => options.feed_rate,
+        2 => options.plunge_rate,
+        3 => options.safe_z,
+        4 => options.cut_z,
+        5 => return options.precision.to_string(),
+        6 => options.offset,
+        _ => options.max_radius,
+    };
+    value.to_string()
+}
+
+// applies valid text to the G-code option at an index of `GCODE_FIELDS`
+fn set_gcode_field(options: &mut GcodeOptions, index: usize, text: &str) {
+    if index == 5 {
+        if let Ok(digits @ 0..=9) = text.trim().parse::<usize>() {
+            options.precision = digits;
+        }
+        return;
+    }
+    let value = match text.trim().parse::<f64>() {
+        Ok(value) if value.is_finite() => value,
+        _ => return,
+    };
+    // scale, rates and radius must be positive
+    if value <= 0.0 && !matches!(index, 3 | 4 | 6) {
+        return;
+    }
+    let field = match index {
+        0 => &mut options.scale,
+        1 => &mut options.feed_rate,
+        2 => &mut options.plunge_rate,
+        3 => &mut options.safe_z,
+        4 => &mut options.cut_z,
+        6 => &mut options.offset,
+        _ => &mut options.max_radius,
+    };
+    *field = value;
+}
+
+fn format_coordinate(value: f64) -> String {
+    svg::format_num(value, 3)
+}
+
+// index, depth, interval, box and arc of a node, one per line
+fn format_node(info: &bezier::NodeInfo) -> String {
+    let aabb = &info.aabb;
+    let mut lines = vec![
+        format!(
+            "Curve {}, segment {}, node {}, depth {}",
+            info.id.curve, info.id.segment, info.id.index, info.depth
+        ),
+        format!(
+            "t {} to {}",
+            format_coordinate(info.interval.0),
+            format_coordinate(info.interval.1)
+        ),
+        format!(
+            "AABB ({}, {}) {} x {}",
+            format_coordinate(aabb.x),
+            format_coordinate(aabb.y),
+            format_coordinate(aabb.w),
+            format_coordinate(aabb.h)
+        ),
+        format!("Rounding {}", format_coordinate(info.radius)),
+    ];
+    if let Some(ref arc) = info.arc {
+        lines.push(format!(
+            "Arc center ({}, {}), radius {}",
+            format_coordinate(arc.center.x),
+            format_coordinate(arc.center.y),
+            format_coordinate(arc.radius)
+        ));
+        lines.push(format!(
+            "Start {}\u{b0}, sweep {}\u{b0}",
+            format_coordinate(arc.start.to_degrees()),
+            format_coordinate(arc.sweep.to_degrees())
+        ));
+    }
+    lines.join("\n")
+}
+
+// a button without a message is drawn disabled
+fn on_press_if(button: Button<Message>, enabled: bool, message: Message) -> Button<Message> {
+    if enabled {
+        button.on_press(message)
+    } else {
+        button
+    }
+}
+
+// key presses no widget took, for the keymap
+fn key_pressed(event: Event, status: event::Status) -> Option<Message> {
+    if status == event::Status::Captured {
+        return None;
+    }
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code,
+            modifiers,
+        }) => Some(Message::KeyPressed(KeyChord::new(
+            &format!("{:?}", key_code),
+            modifiers.is_command_pressed(),
+            modifiers.shift,
+            modifiers.alt,
+        ))),
+        _ => None,
+    }
+}
+
+// the message a keymap action stands for
+fn action_message(action: Action, display: &Display) -> Message {
+    match action {
+        Action::Initialize => Message::Initialize,
+        Action::ToggleDotted => Message::ToggleDotted,
+        Action::ToggleMesh => Message::ToggleMesh,
+        Action::ToggleCircles | Action::ToggleJointCircles | Action::ToggleTangents => {
+            Message::ToggleOverlay(action)
+        }
+        Action::SetSplit(n) => Message::SetBiarc(n as u8),
+        Action::SplitUp => Message::SetBiarc((display.num_split + 1).min(MAX_SPLIT) as u8),
+        Action::SplitDown => Message::SetBiarc(display.num_split.max(2) as u8 - 1),
+        Action::DepthUp => {
+            Message::SetAABBDepth((display.aabb_depth + 1).min(display.num_split + 2) as u8)
+        }
+        Action::DepthDown => Message::SetAABBDepth(display.aabb_depth.max(1) as u8 - 1),
+        Action::ToggleBoxes => Message::ToggleBoxes,
+        Action::Undo => Message::Undo,
+        Action::Redo => Message::Redo,
+        Action::ZoomIn => Message::Zoom(ZOOM_STEP),
+        Action::ZoomOut => Message::Zoom(1.0 / ZOOM_STEP),
+        Action::FitView => Message::FitView,
+        Action::SelectAll => Message::SelectAll,
+        Action::Delete => Message::DeleteSelected,
+        Action::Duplicate => Message::DuplicateSelected,
+        Action::Raise => Message::RaiseSelected,
+        Action::Lower => Message::LowerSelected,
+        Action::ToggleAnimation => Message::ToggleAnimation,
+        Action::NextTheme => Message::NextTheme,
+        Action::ToggleHelp => Message::ToggleHelp,
+    }
+}
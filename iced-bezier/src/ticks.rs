@@ -0,0 +1,44 @@
+// Timer subscription for animations
+//
+// The `time` module of iced needs an async runtime feature; this ticks from a
+// thread of its own instead, so the executor's pool never blocks on the sleep.
+
+use std::hash::Hash;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use iced::Subscription;
+use iced_native::futures::channel::mpsc;
+use iced_native::futures::stream::{BoxStream, StreamExt};
+use iced_native::subscription::Recipe;
+
+/// Messages every `duration`, the first one after `duration`
+pub fn every(duration: Duration) -> Subscription<Instant> {
+    Subscription::from_recipe(Every(duration))
+}
+
+struct Every(Duration);
+
+impl<H: std::hash::Hasher, E> Recipe<H, E> for Every {
+    type Output = Instant;
+
+    fn hash(&self, state: &mut H) {
+        std::any::TypeId::of::<Self>().hash(state);
+        self.0.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<'static, E>) -> BoxStream<'static, Instant> {
+        let duration = self.0;
+        // ticks the UI has not taken yet are dropped; the thread ends with the stream
+        let (mut sender, receiver) = mpsc::channel(1);
+        thread::spawn(move || loop {
+            thread::sleep(duration);
+            if let Err(err) = sender.try_send(Instant::now()) {
+                if err.is_disconnected() {
+                    break;
+                }
+            }
+        });
+        receiver.boxed()
+    }
+}
@@ -0,0 +1,94 @@
+// Transform between y-up world units and canvas pixels
+//
+// The world origin sits `offset` pixels right of and above the bottom-left corner
+// of the canvas, and one world unit is `scale` pixels. The default viewport is
+// the screen of the original editor: one unit per pixel, origin at the bottom left.
+
+use iced::{Point, Size, Vector};
+
+use bezier_core::point::Vec2;
+use bezier_core::scalar::Scalar;
+
+use crate::bezier::Real;
+
+const MIN_SCALE: f32 = 0.01;
+const MAX_SCALE: f32 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// Pixels per world unit
+    pub scale: f32,
+    /// Pixels from the bottom-left corner to the world origin, y-up
+    pub offset: Vector,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            scale: 1.0,
+            offset: Vector::new(0.0, 0.0),
+        }
+    }
+}
+
+impl Viewport {
+    /// Canvas position of a world point, on a canvas of `size`
+    pub fn to_screen<S: Scalar>(&self, p: &Vec2<S>, size: Size) -> Point {
+        Point {
+            x: self.offset.x + p.x.to_f32() * self.scale,
+            y: size.height - (self.offset.y + p.y.to_f32() * self.scale),
+        }
+    }
+
+    /// World point under a canvas position
+    pub fn to_world(&self, p: Point, size: Size) -> Vec2<Real> {
+        Vec2::new(
+            Real::from((p.x - self.offset.x) / self.scale),
+            Real::from((size.height - p.y - self.offset.y) / self.scale),
+        )
+    }
+
+    /// World length of `pixels`
+    pub fn to_world_length(&self, pixels: f32) -> Real {
+        Real::from(pixels / self.scale)
+    }
+
+    /// Scale by `factor`, keeping the world point under `anchor` in place
+    pub fn zoom(&mut self, anchor: Point, factor: f32, size: Size) {
+        let scale = (self.scale * factor).max(MIN_SCALE).min(MAX_SCALE);
+        let world = self.to_world(anchor, size);
+        self.scale = scale;
+        let moved = self.to_screen(&world, size);
+        self.pan(anchor - moved);
+    }
+
+    /// Move the content by `delta` canvas pixels (y-down, as the cursor moves)
+    pub fn pan(&mut self, delta: Vector) {
+        self.offset.x += delta.x;
+        self.offset.y -= delta.y;
+    }
+
+    /// Show the world box `min`-`max` centered, with `margin` pixels around it
+    pub fn fit(&mut self, min: Vec2<Real>, max: Vec2<Real>, size: Size, margin: f32) {
+        let width = (max.x - min.x) as f32;
+        let height = (max.y - min.y) as f32;
+        let avail_w = (size.width - 2.0 * margin).max(1.0);
+        let avail_h = (size.height - 2.0 * margin).max(1.0);
+        let scale = if width <= 0.0 && height <= 0.0 {
+            1.0
+        } else if width <= 0.0 {
+            avail_h / height
+        } else if height <= 0.0 {
+            avail_w / width
+        } else {
+            (avail_w / width).min(avail_h / height)
+        };
+        self.scale = scale.max(MIN_SCALE).min(MAX_SCALE);
+        let center_x = (min.x + max.x) as f32 / 2.0;
+        let center_y = (min.y + max.y) as f32 / 2.0;
+        self.offset = Vector::new(
+            size.width / 2.0 - center_x * self.scale,
+            size.height / 2.0 - center_y * self.scale,
+        );
+    }
+}
@@ -1,58 +1,155 @@
-use crate::settings::Ids;
+pub use bezier_core::bezier::BezierCurve;
+pub use bezier_core::point::Vec2 as Point;
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Point {
-    pub value: [f32; 2],
-}
+use bezier_core::biarc::ArcData;
+use bezier_core::keymap::Action;
+use bezier_core::overlay::Overlay;
+use bezier_core::path::{BezierPath, FittedPath};
+use bezier_core::predicates::Tolerance;
+use bezier_core::theme::{Theme, THEMES};
+use bezier_core::util::PTS_RADIUS;
+
+/// The glium renderer works in `f32`
+pub type CubicBezierCurve = BezierCurve<f32>;
+
+/// Range of the split count, as in the iced front-end
+const MAX_SPLIT: usize = 5;
 
-#[derive(Debug, PartialEq)]
-pub struct CubicBezierCurve {
-    pub control_pts: [Point; 4],
+/// The edited curve, its biarcs and the display toggles of the keymap.
+/// Positions are window pixels from the bottom-left corner, y-up.
+#[derive(Debug)]
+pub struct Editor {
+    pub fitted: FittedPath<f32>,
+    pub num_split: usize,
+    pub is_dotted: bool,
+    pub is_meshed: bool,
+    pub overlay: Overlay,
+    pub show_help: bool,
+    pub theme: Theme,
+    // themes of `NextTheme`: the built-in ones, then a loaded one
+    themes: Vec<Theme>,
+    pub tolerance: Tolerance<f32>,
+    // index of the grabbed control point
+    dragging: Option<usize>,
 }
 
-impl Point {
-    pub fn new(x: f32, y: f32) -> Self {
-        Point { value: [x, y] }
+impl Default for Editor {
+    fn default() -> Self {
+        let tolerance = Tolerance::default();
+        let num_split = 1;
+        Editor {
+            fitted: FittedPath::new(BezierPath::default(), num_split, false, &tolerance),
+            num_split,
+            is_dotted: false,
+            is_meshed: true,
+            overlay: Overlay::default(),
+            show_help: true,
+            theme: Theme::default(),
+            themes: THEMES
+                .iter()
+                .filter_map(|name| Theme::named(name))
+                .collect(),
+            tolerance,
+            dragging: None,
+        }
     }
 }
 
-impl CubicBezierCurve {
-    pub fn new() -> Self {
-        CubicBezierCurve {
-            control_pts: [
-                Point::new(50., 100.),
-                Point::new(200., 300.),
-                Point::new(400., 300.),
-                Point::new(550., 100.),
-            ],
+impl Editor {
+    pub fn curve(&self) -> &CubicBezierCurve {
+        &self.fitted.path.segments[0]
+    }
+
+    pub fn leaves(&self) -> Vec<ArcData<f32>> {
+        self.fitted.leaves()
+    }
+
+    /// Whether `perform` knows the action
+    pub fn handles(action: Action) -> bool {
+        matches!(
+            action,
+            Action::Initialize
+                | Action::ToggleDotted
+                | Action::ToggleMesh
+                | Action::ToggleCircles
+                | Action::ToggleJointCircles
+                | Action::ToggleTangents
+                | Action::SetSplit(_)
+                | Action::SplitUp
+                | Action::SplitDown
+                | Action::NextTheme
+                | Action::ToggleHelp
+        )
+    }
+
+    /// Apply a keymap action; false if this front-end has no such action
+    pub fn perform(&mut self, action: Action) -> bool {
+        match action {
+            Action::Initialize => {
+                self.fitted.path = BezierPath::default();
+                self.dragging = None;
+                self.rebuild();
+            }
+            Action::ToggleDotted => self.is_dotted = !self.is_dotted,
+            Action::ToggleMesh => self.is_meshed = !self.is_meshed,
+            Action::ToggleCircles | Action::ToggleJointCircles | Action::ToggleTangents => {
+                self.overlay.toggle(action);
+            }
+            Action::SetSplit(n) => self.set_split(n),
+            Action::SplitUp => self.set_split(self.num_split + 1),
+            Action::SplitDown => self.set_split(self.num_split.saturating_sub(1)),
+            Action::NextTheme => {
+                let current = self.themes.iter().position(|t| t.name == self.theme.name);
+                let next = current.map_or(0, |i| (i + 1) % self.themes.len());
+                self.theme = self.themes[next].clone();
+            }
+            Action::ToggleHelp => self.show_help = !self.show_help,
+            _ => return false,
         }
+        true
     }
 
-    pub fn evaluate(&self, t: f32, point: &mut Point) -> () {
-        let t_inv = 1.0 - t;
-        let t_inv_sq = t_inv * t_inv;
-        let t_sq = t * t;
-        let b0 = t_inv_sq * t_inv;
-        let b1 = 3.0 * t_inv_sq * t;
-        let b2 = 3.0 * t_inv * t_sq;
-        let b3 = t_sq * t;
+    /// Use `theme`, also in the `NextTheme` cycle if it is not built in
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.themes.retain(|t| t.name != theme.name);
+        self.themes.push(theme.clone());
+        self.theme = theme;
+    }
 
-        point.set_value(0.0, 0.0);
-        point.vec_scalar_add(&self.control_pts[0], b0);
-        point.vec_scalar_add(&self.control_pts[1], b1);
-        point.vec_scalar_add(&self.control_pts[2], b2);
-        point.vec_scalar_add(&self.control_pts[3], b3);
+    fn set_split(&mut self, num_split: usize) {
+        self.num_split = num_split.max(1).min(MAX_SPLIT);
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        self.fitted.rebuild(self.num_split, false, &self.tolerance);
+    }
+
+    /// Grab the control point under `p`; false if there is none
+    pub fn press(&mut self, p: Point<f32>) -> bool {
+        // make clickable range * 1.5
+        let local_rad = PTS_RADIUS * 1.5;
+        self.dragging = self
+            .curve()
+            .control_pts
+            .iter()
+            .position(|pt| (pt.x - p.x).abs() <= local_rad && (pt.y - p.y).abs() <= local_rad);
+        self.dragging.is_some()
     }
-}
 
-impl Point {
-    fn set_value(&mut self, v1: f32, v2: f32) -> () {
-        self.value[0] = v1;
-        self.value[1] = v2;
+    /// Move the grabbed control point to `p`; false if none is grabbed
+    pub fn drag(&mut self, p: Point<f32>) -> bool {
+        match self.dragging {
+            Some(index) => {
+                self.fitted.path.set_point(0, index, p);
+                self.rebuild();
+                true
+            }
+            None => false,
+        }
     }
 
-    fn vec_scalar_add(&mut self, v: &Point, s: f32) -> () {
-        self.value[0] += s * v.value[0];
-        self.value[1] += s * v.value[1];
+    pub fn release(&mut self) {
+        self.dragging = None;
     }
 }
@@ -1,3 +1,11 @@
+// the front-end keeps the style of conrod's glium example it started from
+#![allow(
+    clippy::needless_borrow,
+    clippy::single_match,
+    clippy::toplevel_ref_arg
+)]
+
+#[allow(unused_imports)]
 #[macro_use]
 extern crate glium;
 
@@ -11,40 +19,208 @@ pub mod curve;
 mod settings;
 mod support;
 
+use std::path::PathBuf;
+
 use conrod_glium::Renderer;
 use glium::Surface;
 
+use bezier_core::keymap::{Action, KeyChord, Keymap};
+use bezier_core::theme::Theme;
+use bezier_core::util::*;
+
+/// Pixels the overlay tangents are drawn to each side of a biarc end
+const TANGENT_LENGTH: f32 = 40.0;
+
+/// Keymap loaded when `--keymap` is not given, if it exists
+const DEFAULT_KEYMAP_FILE: &str = "bezier-keys.txt";
+/// Theme loaded when `--theme` is not given, if it exists
+const DEFAULT_THEME_FILE: &str = "bezier-theme.txt";
+
 fn main_draw(
     ref mut ui: conrod_core::UiCell,
-    ids: &settings::Ids,
-    curve: &mut curve::CubicBezierCurve,
+    ids: &mut settings::Ids,
+    editor: &curve::Editor,
+    keyconfig: &str,
 ) {
-    use conrod_core::{widget, Colorable, Labelable, Positionable, Sizeable, Widget};
-    use std::iter::once;
+    use conrod_core::{color, widget, Colorable, Positionable, Widget};
 
     const MARGIN: conrod_core::Scalar = 5.0;
     const KEYCONFIG_SIZE: conrod_core::FontSize = 12;
 
     // `Canvas` is a widget that provides some basic functionality for laying out children widgets.
     // By default, its size is the size of the window. We'll use this as a background for the
-    // following widgets, as well as a scrollable container for the children widgets.
+    // following widgets.
+    let theme = &editor.theme;
+    let to_color = |rgba: Rgba| color::rgba(rgba[0], rgba[1], rgba[2], rgba[3]);
     widget::Canvas::new()
-        .scroll_kids_vertically()
         .pad(MARGIN)
+        .color(to_color(theme.background))
         .set(ids.canvas, ui);
 
-    const KEYCONFIG: &'static str = "Key [I]: Initialize all control points\n\
-     Key [L]: Make the line dotted\n\
-     Key [C]: Draw control mesh\n\
-     Key [R]: Draw full control circles\n\
-     Key [1-4]: Draw biarc (2, 4, 8, 16)";
-    widget::Text::new(KEYCONFIG)
-        .font_size(KEYCONFIG_SIZE)
-        .bottom_left_with_margin_on(ids.canvas, MARGIN)
-        .line_spacing(5.0)
-        .set(ids.keyconfig, ui);
-
-    
+    // the editor is y-up from the bottom-left corner, conrod from the middle of the window
+    let (half_w, half_h) = (ui.win_w / 2.0, ui.win_h / 2.0);
+    let to_ui = |p: &curve::Point<f32>| [p.x as f64 - half_w, p.y as f64 - half_h];
+    let curve = editor.curve();
+
+    // draw bezier curve
+    let mut point = curve::Point::zero();
+    let samples: Vec<_> = (0..=RESOLUTION)
+        .map(|i| {
+            curve.cubic_curve_to(&mut point, i as f32 / RESOLUTION as f32);
+            to_ui(&point)
+        })
+        .collect();
+    if editor.is_dotted {
+        let dashes: Vec<_> = samples.chunks_exact(2).collect();
+        ids.dashes
+            .resize(dashes.len(), &mut ui.widget_id_generator());
+        for (dash, &id) in dashes.iter().zip(ids.dashes.iter()) {
+            widget::Line::abs(dash[0], dash[1])
+                .thickness(1.2)
+                .color(to_color(theme.curve))
+                .parent(ids.canvas)
+                .set(id, ui);
+        }
+    } else {
+        widget::PointPath::abs(samples)
+            .thickness(1.2)
+            .color(to_color(theme.curve))
+            .parent(ids.canvas)
+            .set(ids.curve, ui);
+    }
+
+    // draw control mesh and biarcs, alternating colors along the curve
+    let leaves = editor.leaves();
+    if editor.is_meshed {
+        widget::PointPath::abs(curve.control_pts.iter().map(to_ui))
+            .thickness(2.0)
+            .color(to_color(theme.mesh))
+            .parent(ids.canvas)
+            .set(ids.mesh, ui);
+
+        ids.arcs.resize(leaves.len(), &mut ui.widget_id_generator());
+        for (i, (arc, &id)) in leaves.iter().zip(ids.arcs.iter()).enumerate() {
+            let color = theme.arc_color(i);
+            widget::PointPath::abs(arc.sample(RESOLUTION / 2).iter().map(to_ui))
+                .thickness(3.0)
+                .color(to_color(color))
+                .parent(ids.canvas)
+                .set(id, ui);
+        }
+    }
+
+    // draw the overlay: full circle of every arc, joint circles and end tangents
+    if editor.overlay.arc_circles {
+        ids.circles
+            .resize(leaves.len(), &mut ui.widget_id_generator());
+        for (arc, &id) in leaves.iter().zip(ids.circles.iter()) {
+            let [x, y] = to_ui(&arc.center);
+            widget::Circle::outline(arc.radius as f64)
+                .x_y(x, y)
+                .color(to_color(theme.arc_circle))
+                .parent(ids.canvas)
+                .set(id, ui);
+        }
+    }
+
+    if editor.overlay.needs_constructions() {
+        let constructions = editor.fitted.constructions(&editor.tolerance);
+        let joint_color = to_color(theme.joint_circle);
+        if editor.overlay.joint_circles {
+            let circles: Vec<_> = constructions
+                .iter()
+                .filter_map(|c| c.joint_circle)
+                .collect();
+            ids.joint_circles
+                .resize(circles.len(), &mut ui.widget_id_generator());
+            for ((center, radius), &id) in circles.iter().zip(ids.joint_circles.iter()) {
+                let [x, y] = to_ui(center);
+                widget::Circle::outline(*radius as f64)
+                    .x_y(x, y)
+                    .color(joint_color)
+                    .parent(ids.canvas)
+                    .set(id, ui);
+            }
+            ids.joints
+                .resize(constructions.len(), &mut ui.widget_id_generator());
+            for (construction, &id) in constructions.iter().zip(ids.joints.iter()) {
+                let [x, y] = to_ui(&construction.joint);
+                widget::Circle::fill(PTS_RADIUS as f64)
+                    .x_y(x, y)
+                    .color(joint_color)
+                    .parent(ids.canvas)
+                    .set(id, ui);
+            }
+        }
+        if editor.overlay.tangents {
+            let tangents: Vec<_> = constructions
+                .iter()
+                .flat_map(|c| vec![(c.start, c.start_tangent), (c.end, c.end_tangent)])
+                .collect();
+            ids.tangents
+                .resize(tangents.len(), &mut ui.widget_id_generator());
+            for ((point, tangent), &id) in tangents.iter().zip(ids.tangents.iter()) {
+                let half = *tangent * TANGENT_LENGTH;
+                widget::Line::abs(to_ui(&(*point - half)), to_ui(&(*point + half)))
+                    .thickness(1.0)
+                    .color(to_color(theme.tangent))
+                    .parent(ids.canvas)
+                    .set(id, ui);
+            }
+        }
+    }
+
+    // draw control points
+    ids.points.resize(4, &mut ui.widget_id_generator());
+    for (pt, &id) in curve.control_pts.iter().zip(ids.points.iter()) {
+        let [x, y] = to_ui(pt);
+        widget::Circle::fill((PTS_RADIUS * 2.0) as f64)
+            .x_y(x, y)
+            .color(to_color(theme.control_point))
+            .parent(ids.canvas)
+            .set(id, ui);
+    }
+
+    if editor.show_help {
+        widget::Text::new(keyconfig)
+            .font_size(KEYCONFIG_SIZE)
+            .color(to_color(theme.text))
+            .bottom_left_with_margin_on(ids.canvas, MARGIN)
+            .line_spacing(5.0)
+            .set(ids.keyconfig, ui);
+    }
+}
+
+// `--keymap FILE`, else the default keymap file if it exists
+fn load_keymap() -> Keymap {
+    let mut args = std::env::args().skip_while(|arg| arg != "--keymap").skip(1);
+    let path = match args.next() {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(DEFAULT_KEYMAP_FILE),
+    };
+    if !path.exists() {
+        return Keymap::default();
+    }
+    Keymap::load(&path).unwrap_or_else(|err| {
+        eprintln!("Cannot load {}: {}", path.display(), err);
+        Keymap::default()
+    })
+}
+
+// `--theme NAME|FILE`, else the default theme file if it exists
+fn load_theme() -> Option<Theme> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--theme").skip(1);
+    let name = args.next();
+    if let Some(theme) = name.as_deref().and_then(Theme::named) {
+        return Some(theme);
+    }
+    let path = PathBuf::from(name.unwrap_or_else(|| String::from(DEFAULT_THEME_FILE)));
+    if !path.exists() {
+        return None;
+    }
+    Theme::load(&path)
+        .map_err(|err| eprintln!("Cannot load {}: {}", path.display(), err))
+        .ok()
 }
 
 fn main() {
@@ -60,11 +236,17 @@ fn main() {
         .with_multisampling(4);
     let display = glium::Display::new(window, context, &event_loop).unwrap();
 
+    // MAIN CURVE
+    let mut editor = curve::Editor::default();
+    if let Some(theme) = load_theme() {
+        editor.set_theme(theme);
+    }
+
     let mut ui = conrod_core::UiBuilder::new([WIDTH as f64, HEIGHT as f64])
-        .theme(settings::theme())
+        .theme(settings::theme(&editor.theme))
         .build();
 
-    let ids = settings::Ids::new(ui.widget_id_generator());
+    let mut ids = settings::Ids::new(ui.widget_id_generator());
 
     let assets = find_folder::Search::KidsThenParents(3, 5)
         .for_folder("assets")
@@ -76,8 +258,15 @@ fn main() {
 
     let image_map = conrod_core::image::Map::<glium::texture::Texture2d>::new();
 
-    // MAIN CURVE
-    let mut curve = curve::CubicBezierCurve::new();
+    let keymap = load_keymap();
+    let keyconfig = keymap
+        .help_for(curve::Editor::handles)
+        .iter()
+        .map(|(keys, description)| format!("Key [{}]: {}", keys, description))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut modifiers = glium::glutin::event::ModifiersState::empty();
+    let mut cursor = curve::Point::zero();
 
     support::run_loop(display, event_loop, move |request, display| {
         match request {
@@ -93,26 +282,72 @@ fn main() {
                 }
 
                 match event {
-                    glium::glutin::event::Event::WindowEvent { event, .. } => match event {
-                        // Break from the loop upon `Escape`.
-                        glium::glutin::event::WindowEvent::CloseRequested
-                        | glium::glutin::event::WindowEvent::KeyboardInput {
-                            input:
-                                glium::glutin::event::KeyboardInput {
-                                    virtual_keycode:
-                                        Some(glium::glutin::event::VirtualKeyCode::Escape),
-                                    ..
-                                },
-                            ..
-                        } => *should_exit = true,
-                        _ => {}
-                    },
+                    glium::glutin::event::Event::WindowEvent { event, .. } => {
+                        use glium::glutin::event::{ElementState, MouseButton, WindowEvent};
+                        match event {
+                            // Break from the loop upon `Escape`.
+                            glium::glutin::event::WindowEvent::CloseRequested
+                            | glium::glutin::event::WindowEvent::KeyboardInput {
+                                input:
+                                    glium::glutin::event::KeyboardInput {
+                                        virtual_keycode:
+                                            Some(glium::glutin::event::VirtualKeyCode::Escape),
+                                        ..
+                                    },
+                                ..
+                            } => *should_exit = true,
+                            WindowEvent::ModifiersChanged(state) => modifiers = *state,
+                            WindowEvent::KeyboardInput {
+                                input:
+                                    glium::glutin::event::KeyboardInput {
+                                        state: ElementState::Pressed,
+                                        virtual_keycode: Some(key),
+                                        ..
+                                    },
+                                ..
+                            } => {
+                                let chord = KeyChord::new(
+                                    &format!("{:?}", key),
+                                    modifiers.ctrl() || modifiers.logo(),
+                                    modifiers.shift(),
+                                    modifiers.alt(),
+                                );
+                                if let Some(action) = keymap.action(&chord) {
+                                    *should_update_ui |= editor.perform(action);
+                                    if action == Action::NextTheme {
+                                        ui.theme = settings::theme(&editor.theme);
+                                    }
+                                }
+                            }
+                            WindowEvent::CursorMoved { position, .. } => {
+                                // y-up logical pixels, as the editor
+                                let scale = display.gl_window().window().scale_factor();
+                                let position = position.to_logical::<f64>(scale);
+                                cursor = curve::Point::new(
+                                    position.x as f32,
+                                    (ui.win_h - position.y) as f32,
+                                );
+                                *should_update_ui |= editor.drag(cursor);
+                            }
+                            WindowEvent::MouseInput {
+                                state,
+                                button: MouseButton::Left,
+                                ..
+                            } => match state {
+                                ElementState::Pressed => {
+                                    editor.press(cursor);
+                                }
+                                ElementState::Released => editor.release(),
+                            },
+                            _ => {}
+                        }
+                    }
                     _ => {}
                 }
             }
             support::Request::SetUi { needs_redraw } => {
                 // Instantiate a GUI demonstrating every widget type provided by conrod.
-                main_draw(ui.set_widgets(), &ids, &mut curve);
+                main_draw(ui.set_widgets(), &mut ids, &editor, &keyconfig);
 
                 *needs_redraw = ui.has_changed();
             }
@@ -122,7 +357,8 @@ fn main() {
 
                 renderer.fill(display, primitives, &image_map);
                 let mut target = display.draw();
-                target.clear_color(0.0, 0.0, 0.0, 1.0);
+                let [r, g, b, a] = editor.theme.background;
+                target.clear_color(r, g, b, a);
                 renderer.draw(display, &mut target, &image_map).unwrap();
                 target.finish().unwrap();
             }
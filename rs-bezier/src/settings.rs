@@ -1,17 +1,18 @@
 extern crate conrod_core;
 
-pub fn theme() -> conrod_core::Theme {
+pub fn theme(colors: &bezier_core::theme::Theme) -> conrod_core::Theme {
     use conrod_core::position::{Align, Direction, Padding, Position, Relative};
+    let color = |[r, g, b, a]: bezier_core::util::Rgba| conrod_core::color::rgba(r, g, b, a);
     conrod_core::Theme {
         name: "Main Theme".to_string(),
         padding: Padding::none(),
         x_position: Position::Relative(Relative::Align(Align::Start), None),
         y_position: Position::Relative(Relative::Direction(Direction::Backwards, 20.0), None),
-        background_color: conrod_core::color::WHITE,
+        background_color: color(colors.background),
         shape_color: conrod_core::color::LIGHT_CHARCOAL,
         border_color: conrod_core::color::BLACK,
         border_width: 0.0,
-        label_color: conrod_core::color::BLACK,
+        label_color: color(colors.text),
         font_id: None,
         font_size_large: 26,
         font_size_medium: 18,
@@ -28,5 +29,14 @@ widget_ids! {
         canvas,
         title,
         keyconfig,
+        curve,
+        dashes[],
+        mesh,
+        arcs[],
+        circles[],
+        joint_circles[],
+        joints[],
+        tangents[],
+        points[],
     }
 }